@@ -1,11 +1,56 @@
 use std::fmt::Display;
 use std::path::Path;
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Duration;
 
+use serde::Deserialize;
+use serde::Serialize;
 use tempdir::TempDir;
 
+use crate::datatype::DataType;
 use crate::error::Result;
+use crate::migration::MigrationRegistry;
+
+/// (De)serializes an `Option<Duration>` as an `Option<u64>` of milliseconds,
+/// matching the format `write_cache_config!`/`Display` already print these
+/// fields as.
+mod duration_millis {
+    use std::time::Duration;
+
+    use serde::Deserialize;
+    use serde::Deserializer;
+    use serde::Serialize;
+    use serde::Serializer;
+
+    pub fn serialize<S: Serializer>(value: &Option<Duration>, serializer: S) -> Result<S::Ok, S::Error> {
+        value.map(|d| d.as_millis() as u64).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Duration>, D::Error> {
+        let millis: Option<u64> = Option::deserialize(deserializer)?;
+        Ok(millis.map(Duration::from_millis))
+    }
+}
+
+/// (De)serializes a `PathBuf` as a lossily-converted string, matching the
+/// format `InstanceConfig`'s `Display` impl already prints `dir` as.
+mod path_lossy {
+    use std::path::PathBuf;
+
+    use serde::Deserialize;
+    use serde::Deserializer;
+    use serde::Serializer;
+
+    pub fn serialize<S: Serializer>(value: &PathBuf, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&value.to_string_lossy())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<PathBuf, D::Error> {
+        let raw = String::deserialize(deserializer)?;
+        Ok(PathBuf::from(raw))
+    }
+}
 
 pub trait CacheConfig {
     fn max_cache(&self) -> Option<usize>;
@@ -86,11 +131,14 @@ macro_rules! write_cache_config {
     };
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InstanceConfig {
+    #[serde(with = "path_lossy")]
     dir: PathBuf,
     max_cache: Option<usize>,
+    #[serde(with = "duration_millis")]
     min_live_time: Option<Duration>,
+    #[serde(with = "duration_millis")]
     max_idle_time: Option<Duration>,
 }
 
@@ -125,25 +173,69 @@ impl Display for InstanceConfig {
     }
 }
 
-#[derive(Debug, Clone, Default)]
+/// Codec used to persist the [`crate::model::TableDefine`] rows a schema's
+/// metadata table stores. `Json` round-trips through `serde_json` and stays
+/// interoperable with anything that can read the on-disk bytes directly;
+/// `Archive` stores the `rkyv` zero-copy encoding (see
+/// [`crate::model::TableDefine::archive`]) so a read skips the JSON decode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum RecordCodec {
+    #[default]
+    Json,
+    Archive,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SchemaConfig {
     readonly: bool,
     temporary: bool,
     max_cache: Option<usize>,
+    #[serde(with = "duration_millis")]
     min_live_time: Option<Duration>,
+    #[serde(with = "duration_millis")]
     max_idle_time: Option<Duration>,
+    record_codec: RecordCodec,
+    /// Upgraders applied to a [`crate::model::TableDefine`] row whose stored
+    /// `version` trails [`crate::model::CURRENT_SCHEMA_VERSION`]. Empty by
+    /// default, meaning such a row fails with `ErrorKind::IncompatibleSchema`.
+    /// Registered closures have no serde representation, so this is left out
+    /// of the (de)serialized form and comes back empty on every round trip —
+    /// a reopened environment re-registers its migrations in code, same as
+    /// it constructs the rest of `SchemaConfig`.
+    #[serde(skip)]
+    migrations: Arc<MigrationRegistry>,
 }
 
 impl SchemaConfig {
     pub fn new(readonly: bool, temporary: bool) -> Self {
-        Self { 
-            readonly, 
-            temporary, 
-            max_cache: None, 
-            min_live_time: None, 
-            max_idle_time: None 
+        Self {
+            readonly,
+            temporary,
+            max_cache: None,
+            min_live_time: None,
+            max_idle_time: None,
+            record_codec: RecordCodec::Json,
+            migrations: Arc::new(MigrationRegistry::new()),
         }
     }
+
+    pub fn record_codec(&self) -> RecordCodec {
+        self.record_codec
+    }
+
+    pub fn set_record_codec(mut self, record_codec: RecordCodec) -> Self {
+        self.record_codec = record_codec;
+        self
+    }
+
+    pub fn migrations(&self) -> Arc<MigrationRegistry> {
+        self.migrations.clone()
+    }
+
+    pub fn set_migrations(mut self, migrations: MigrationRegistry) -> Self {
+        self.migrations = Arc::new(migrations);
+        self
+    }
 }
 
 impl_cache_config!(SchemaConfig as setter);
@@ -154,29 +246,129 @@ impl Display for SchemaConfig {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "[readonly = {}, temporary = {}", self.readonly, self.temporary)?;
         write_cache_config!(f, self);
+        if self.record_codec != RecordCodec::Json {
+            write!(f, ", record_codec = {:?}", self.record_codec)?;
+        }
         write!(f, "]")
     }
 }
 
-#[derive(Debug, Clone, Default)]
+/// Codec applied to a database's stored *values* before they're written and
+/// after they're read back; keys are never compressed, so comparator
+/// ordering (and `bufdb_level`'s suffix-based duplicate encoding) is
+/// unaffected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Compression {
+    #[default]
+    None,
+    Snappy,
+}
+
+/// Strategy used to encode a column's values before [`Compression`] wraps the
+/// resulting block, selected per [`DataType`] via [`TableConfig::set_column_encoding`].
+/// See `bufdb_storage::column_codec` for the actual codecs. `Delta` is only
+/// defined for [`DataType::INT`]/[`DataType::LONG`]/[`DataType::DATETIME`],
+/// and `Gorilla` only for [`DataType::DOUBLE`]; encoding a column of another
+/// type with either is an [`crate::error::ErrorKind::DataType`] error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ColumnEncoding {
+    /// Every value stored independently, tagged by its `DataType`.
+    #[default]
+    Plain,
+    /// Consecutive equal values collapsed into a (run length, value) pair.
+    Rle,
+    /// The first value stored verbatim, later ones as a zigzag-varint
+    /// difference from the value before them.
+    Delta,
+    /// A Gorilla-style XOR of each value's bits against the previous value's.
+    Gorilla,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TableConfig {
     pub readonly: bool,
-    pub temporary: bool
+    pub temporary: bool,
+    /// TTL applied to entries written through `put` when they aren't given
+    /// an explicit per-entry TTL. `None` means entries never expire.
+    #[serde(with = "duration_millis")]
+    default_ttl: Option<Duration>,
+    /// Codec applied to stored values. Defaults to [`Compression::None`].
+    compression: Compression,
+    /// Per-`DataType` [`ColumnEncoding`] overrides, checked in declaration
+    /// order. A `DataType` with no entry falls back to [`ColumnEncoding::Plain`].
+    column_encodings: Vec<(DataType, ColumnEncoding)>,
 }
 
 impl TableConfig {
     pub fn new(readonly: bool, temporary: bool) -> Self {
-        Self { 
-            readonly, 
-            temporary
+        Self {
+            readonly,
+            temporary,
+            default_ttl: None,
+            compression: Compression::None,
+            column_encodings: Vec::new(),
         }
     }
+
+    pub fn default_ttl(&self) -> Option<Duration> {
+        self.default_ttl
+    }
+
+    pub fn set_default_ttl(mut self, default_ttl: Option<Duration>) -> Self {
+        self.default_ttl = default_ttl;
+        self
+    }
+
+    pub fn compression(&self) -> Compression {
+        self.compression
+    }
+
+    pub fn set_compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// The [`ColumnEncoding`] to use for `datatype`, or [`ColumnEncoding::Plain`]
+    /// if [`Self::set_column_encoding`] was never called for it.
+    pub fn column_encoding(&self, datatype: DataType) -> ColumnEncoding {
+        self.column_encodings.iter()
+            .find(|(t, _)| *t == datatype)
+            .map(|(_, encoding)| *encoding)
+            .unwrap_or_default()
+    }
+
+    /// Every `(DataType, ColumnEncoding)` override set by [`Self::set_column_encoding`].
+    pub fn column_encodings(&self) -> &[(DataType, ColumnEncoding)] {
+        &self.column_encodings
+    }
+
+    /// Overrides the [`ColumnEncoding`] used for `datatype`, replacing any
+    /// override already set for it.
+    pub fn set_column_encoding(mut self, datatype: DataType, encoding: ColumnEncoding) -> Self {
+        match self.column_encodings.iter_mut().find(|(t, _)| *t == datatype) {
+            Some((_, existing)) => *existing = encoding,
+            None => self.column_encodings.push((datatype, encoding)),
+        }
+        self
+    }
 }
 
 impl_cache_config!(TableConfig as object);
 
 impl Display for TableConfig {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "[readonly = {}, temporary = {}]", self.readonly, self.temporary)
+        write!(f, "[readonly = {}, temporary = {}", self.readonly, self.temporary)?;
+        if let Some(default_ttl) = self.default_ttl {
+            write!(f, ", default_ttl = {}", default_ttl.as_millis())?;
+        }
+        if self.compression != Compression::None {
+            write!(f, ", compression = {:?}", self.compression)?;
+        }
+        for (datatype, encoding) in &self.column_encodings {
+            if *encoding != ColumnEncoding::Plain {
+                write!(f, ", {}_encoding = {:?}", datatype, encoding)?;
+            }
+        }
+        write!(f, "]")
     }
 }
@@ -0,0 +1,104 @@
+use std::str::FromStr;
+
+use chrono::DateTime;
+use chrono::NaiveDateTime;
+
+use crate::error::Error;
+use crate::error::ErrorKind;
+use crate::error::Result;
+
+/// Decoded value produced by [`Conversion::convert`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypedValue {
+    Bytes(Vec<u8>),
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+    /// Non-leap milliseconds since the Unix epoch.
+    Timestamp(i64),
+}
+
+/// Declares how the raw bytes of an entry should be interpreted.
+///
+/// A `Conversion` is typically parsed from a short, human-authored name (see
+/// [`FromStr`]) so that a column's decoding strategy can be configured declaratively.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Conversion {
+    /// Leaves the bytes untouched.
+    Bytes,
+    Integer,
+    Float,
+    Boolean,
+    /// Parses an RFC3339 timestamp or an epoch-millis integer.
+    Timestamp,
+    /// Parses a timestamp using the given `chrono` format string.
+    TimestampFmt(String),
+}
+
+impl FromStr for Conversion {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "asis" | "bytes" | "string" => Ok(Self::Bytes),
+            "int" | "integer" => Ok(Self::Integer),
+            "float" => Ok(Self::Float),
+            "bool" | "boolean" => Ok(Self::Boolean),
+            "timestamp" => Ok(Self::Timestamp),
+            _ => {
+                if let Some((kind, fmt)) = s.split_once('|') {
+                    if kind == "timestamp" {
+                        return Ok(Self::TimestampFmt(fmt.into()));
+                    }
+                }
+
+                Err(ErrorKind::UndefinedExpr.into())
+            }
+        }
+    }
+}
+
+impl Conversion {
+    fn as_str(bytes: &[u8]) -> Result<&str> {
+        std::str::from_utf8(bytes).map_err(|_| Error::from(ErrorKind::DataType))
+    }
+
+    fn parse_timestamp(s: &str) -> Result<i64> {
+        if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+            Ok(dt.timestamp_millis())
+        } else {
+            let millis = s.parse::<i64>()?;
+            Ok(millis)
+        }
+    }
+
+    /// Decodes the bytes of `entry` according to this conversion.
+    pub fn convert<E: AsRef<[u8]>>(&self, entry: &E) -> Result<TypedValue> {
+        let bytes = entry.as_ref();
+
+        match self {
+            Self::Bytes => Ok(TypedValue::Bytes(bytes.into())),
+            Self::Integer => {
+                let v = Self::as_str(bytes)?.parse::<i64>()?;
+                Ok(TypedValue::Integer(v))
+            },
+            Self::Float => {
+                let v = Self::as_str(bytes)?.parse::<f64>()?;
+                Ok(TypedValue::Float(v))
+            },
+            Self::Boolean => {
+                let v = Self::as_str(bytes)?.parse::<bool>()?;
+                Ok(TypedValue::Boolean(v))
+            },
+            Self::Timestamp => {
+                let v = Self::parse_timestamp(Self::as_str(bytes)?)?;
+                Ok(TypedValue::Timestamp(v))
+            },
+            Self::TimestampFmt(fmt) => {
+                let s = Self::as_str(bytes)?;
+                let dt = NaiveDateTime::parse_from_str(s, fmt).map_err(ErrorKind::ParseTimestamp)?;
+                Ok(TypedValue::Timestamp(dt.and_utc().timestamp_millis()))
+            },
+        }
+    }
+}
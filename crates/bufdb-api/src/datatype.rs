@@ -0,0 +1,438 @@
+use std::fmt::Display as FmtDisplay;
+use std::fmt::Write;
+
+use chrono::NaiveDateTime;
+use rkyv::Archive;
+use rkyv::Deserialize as RkyvDeserialize;
+use rkyv::Serialize as RkyvSerialize;
+use serde::Deserialize;
+use serde::Deserializer;
+use serde::Serialize;
+use serde::Serializer;
+use serde::de::Error as DeError;
+use serde::de::SeqAccess;
+use serde::de::Visitor;
+use strum::Display;
+use strum::EnumString;
+use strum::FromRepr;
+
+use crate::error::ErrorKind;
+use crate::error::Result;
+
+/// Defines supported datatypes in bufdb.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Display, EnumString, FromRepr, Serialize, Deserialize, Archive, RkyvSerialize, RkyvDeserialize)]
+#[archive(check_bytes)]
+#[archive_attr(derive(Debug, PartialEq, Eq))]
+pub enum DataType {
+    #[default]
+    #[strum(serialize = "string")]
+    STRING = 1,
+    #[strum(serialize = "double")]
+    DOUBLE = 2,
+    #[strum(serialize = "int")]
+    INT = 3,
+    #[strum(serialize = "long")]
+    LONG = 4,
+    #[strum(serialize = "datetime")]
+    DATETIME = 5,
+    #[strum(serialize = "bool")]
+    BOOL = 6,
+    #[strum(serialize = "blob")]
+    BLOB = 7,
+}
+
+/// Stores a datetime as the number of non-leap milliseconds since January 1,
+/// 1970 0:00:00 UTC (a "UNIX timestamp").
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct TimeStamp(i64);
+
+impl TimeStamp {
+    /// The raw millisecond count this timestamp wraps.
+    pub fn millis(&self) -> i64 {
+        self.0
+    }
+}
+
+impl Into<i64> for TimeStamp {
+    fn into(self) -> i64 {
+        self.0
+    }
+}
+
+impl From<i64> for TimeStamp {
+    fn from(value: i64) -> Self {
+        Self(value)
+    }
+}
+
+impl Into<NaiveDateTime> for TimeStamp {
+    fn into(self) -> NaiveDateTime {
+        NaiveDateTime::from_timestamp_millis(self.0).unwrap()
+    }
+}
+
+impl From<NaiveDateTime> for TimeStamp {
+    fn from(value: NaiveDateTime) -> Self {
+        Self(value.timestamp_millis())
+    }
+}
+
+impl FmtDisplay for TimeStamp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(dt) = NaiveDateTime::from_timestamp_millis(self.0) {
+            write!(f, "{}", dt)
+        } else {
+            Err(std::fmt::Error {})
+        }
+    }
+}
+
+/// A single typed value, as stored in a [`crate::model::FieldDefine`]'s
+/// column. Every non-[`Value::NULL`] variant corresponds to exactly one
+/// [`DataType`]; see [`Value::datatype`].
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum Value {
+    #[default]
+    NULL,
+    STRING(Box<String>),
+    DOUBLE(f64),
+    INT(i32),
+    LONG(i64),
+    DATETIME(TimeStamp),
+    BOOL(bool),
+    BLOB(#[serde(with = "blob_bytes")] Box<Vec<u8>>)
+}
+
+/// Serializes [`Value::BLOB`]'s payload as a byte string instead of the
+/// numeric sequence a plain `Vec<u8>` derive would produce.
+mod blob_bytes {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(value: &Box<Vec<u8>>, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(value)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Box<Vec<u8>>, D::Error> {
+        struct BytesVisitor;
+
+        impl<'de> Visitor<'de> for BytesVisitor {
+            type Value = Vec<u8>;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "a byte array")
+            }
+
+            fn visit_bytes<E: DeError>(self, v: &[u8]) -> std::result::Result<Self::Value, E> {
+                Ok(v.to_vec())
+            }
+
+            fn visit_byte_buf<E: DeError>(self, v: Vec<u8>) -> std::result::Result<Self::Value, E> {
+                Ok(v)
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> std::result::Result<Self::Value, A::Error> {
+                let mut out = Vec::new();
+                while let Some(b) = seq.next_element()? {
+                    out.push(b);
+                }
+                Ok(out)
+            }
+        }
+
+        Ok(Box::new(deserializer.deserialize_bytes(BytesVisitor)?))
+    }
+}
+
+impl Value {
+    pub fn is_null(&self) -> bool {
+        self == &Value::NULL
+    }
+
+    /// The [`DataType`] this value's variant corresponds to, or `None` for
+    /// [`Value::NULL`], which carries no type of its own.
+    pub fn datatype(&self) -> Option<DataType> {
+        match self {
+            Value::NULL => None,
+            Value::STRING(_) => Some(DataType::STRING),
+            Value::DOUBLE(_) => Some(DataType::DOUBLE),
+            Value::INT(_) => Some(DataType::INT),
+            Value::LONG(_) => Some(DataType::LONG),
+            Value::DATETIME(_) => Some(DataType::DATETIME),
+            Value::BOOL(_) => Some(DataType::BOOL),
+            Value::BLOB(_) => Some(DataType::BLOB),
+        }
+    }
+
+    /// Appends this value's compact wire encoding to `buf`: one tag byte (`0`
+    /// for [`Value::NULL`], otherwise [`DataType`]'s discriminant) followed by
+    /// the variant's payload — fixed-width for `DOUBLE`/`INT`/`LONG`/
+    /// `DATETIME`/`BOOL`, a 4-byte big-endian length prefix then raw bytes
+    /// for `STRING`/`BLOB`. Pair with [`Self::decode_from`] to read it back;
+    /// the tag byte lets the format evolve without breaking older readers'
+    /// ability to at least recognize a variant they don't understand.
+    pub fn encode_to(&self, buf: &mut Vec<u8>) {
+        match self {
+            Value::NULL => buf.push(0),
+            Value::STRING(v) => {
+                buf.push(DataType::STRING as u8);
+                buf.extend_from_slice(&(v.len() as u32).to_be_bytes());
+                buf.extend_from_slice(v.as_bytes());
+            },
+            Value::DOUBLE(v) => {
+                buf.push(DataType::DOUBLE as u8);
+                buf.extend_from_slice(&v.to_bits().to_be_bytes());
+            },
+            Value::INT(v) => {
+                buf.push(DataType::INT as u8);
+                buf.extend_from_slice(&v.to_be_bytes());
+            },
+            Value::LONG(v) => {
+                buf.push(DataType::LONG as u8);
+                buf.extend_from_slice(&v.to_be_bytes());
+            },
+            Value::DATETIME(v) => {
+                buf.push(DataType::DATETIME as u8);
+                buf.extend_from_slice(&v.millis().to_be_bytes());
+            },
+            Value::BOOL(v) => {
+                buf.push(DataType::BOOL as u8);
+                buf.push(if *v { 1 } else { 0 });
+            },
+            Value::BLOB(v) => {
+                buf.push(DataType::BLOB as u8);
+                buf.extend_from_slice(&(v.len() as u32).to_be_bytes());
+                buf.extend_from_slice(v);
+            },
+        }
+    }
+
+    /// Decodes one [`Self::encode_to`]-encoded value off the front of
+    /// `bytes`, returning it alongside how many bytes it consumed so callers
+    /// can decode a run of values back to back.
+    pub fn decode_from(bytes: &[u8]) -> Result<(Value, usize)> {
+        let (tag, rest) = bytes.split_first().ok_or(ErrorKind::OutOfBounds)?;
+
+        if *tag == 0 {
+            return Ok((Value::NULL, 1));
+        }
+
+        let datatype = DataType::from_repr(*tag).ok_or(ErrorKind::DataType)?;
+
+        Ok(match datatype {
+            DataType::STRING => {
+                let (payload, len) = Self::read_bytes(rest)?;
+                let s = String::from_utf8(payload.to_vec()).map_err(|_| ErrorKind::DataType)?;
+                (Value::STRING(Box::new(s)), 1 + 4 + len)
+            },
+            DataType::DOUBLE => {
+                let raw: [u8; 8] = rest.get(..8).ok_or(ErrorKind::OutOfBounds)?.try_into().unwrap();
+                (Value::DOUBLE(f64::from_bits(u64::from_be_bytes(raw))), 9)
+            },
+            DataType::INT => {
+                let raw: [u8; 4] = rest.get(..4).ok_or(ErrorKind::OutOfBounds)?.try_into().unwrap();
+                (Value::INT(i32::from_be_bytes(raw)), 5)
+            },
+            DataType::LONG => {
+                let raw: [u8; 8] = rest.get(..8).ok_or(ErrorKind::OutOfBounds)?.try_into().unwrap();
+                (Value::LONG(i64::from_be_bytes(raw)), 9)
+            },
+            DataType::DATETIME => {
+                let raw: [u8; 8] = rest.get(..8).ok_or(ErrorKind::OutOfBounds)?.try_into().unwrap();
+                (Value::DATETIME(TimeStamp::from(i64::from_be_bytes(raw))), 9)
+            },
+            DataType::BOOL => {
+                let b = *rest.first().ok_or(ErrorKind::OutOfBounds)?;
+                (Value::BOOL(b != 0), 2)
+            },
+            DataType::BLOB => {
+                let (payload, len) = Self::read_bytes(rest)?;
+                (Value::BLOB(Box::new(payload.to_vec())), 1 + 4 + len)
+            },
+        })
+    }
+
+    /// Reads a 4-byte big-endian length prefix off the front of `rest`,
+    /// followed by that many payload bytes.
+    fn read_bytes(rest: &[u8]) -> Result<(&[u8], usize)> {
+        let raw: [u8; 4] = rest.get(..4).ok_or(ErrorKind::OutOfBounds)?.try_into().unwrap();
+        let len = u32::from_be_bytes(raw) as usize;
+        let payload = rest.get(4..4 + len).ok_or(ErrorKind::OutOfBounds)?;
+        Ok((payload, len))
+    }
+}
+
+impl From<&str> for Value {
+    fn from(val: &str) -> Self {
+        Self::STRING(Box::new(val.into()))
+    }
+}
+
+impl From<String> for Value {
+    fn from(val: String) -> Self {
+        Self::STRING(Box::new(val))
+    }
+}
+
+impl From<f64> for Value {
+    fn from(val: f64) -> Self {
+        Self::DOUBLE(val)
+    }
+}
+
+impl From<i32> for Value {
+    fn from(val: i32) -> Self {
+        Self::INT(val)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(val: i64) -> Self {
+        Self::LONG(val)
+    }
+}
+
+impl From<TimeStamp> for Value {
+    fn from(val: TimeStamp) -> Self {
+        Self::DATETIME(val)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(val: bool) -> Self {
+        Self::BOOL(val)
+    }
+}
+
+impl From<&[u8]> for Value {
+    fn from(val: &[u8]) -> Self {
+        Self::BLOB(Box::new(val.into()))
+    }
+}
+
+impl From<Vec<u8>> for Value {
+    fn from(val: Vec<u8>) -> Self {
+        Self::BLOB(Box::new(val))
+    }
+}
+
+impl<T> From<Option<T>> for Value where T: Into<Value> {
+    fn from(value: Option<T>) -> Self {
+        if let Some(v) = value {
+            v.into()
+        } else {
+            Value::NULL
+        }
+    }
+}
+
+macro_rules! to_hex_string {
+    ($f:expr, $arr:expr) => {
+        {
+            for b in $arr.iter() {
+                write!($f, "{:02X}", b)?;
+            }
+            Ok(())
+        }
+    };
+}
+
+impl FmtDisplay for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NULL => write!(f, "<null>"),
+            Self::STRING(v) => write!(f, "\"{}\"", v),
+            Self::DOUBLE(v) => write!(f, "{}", v),
+            Self::INT(v) => write!(f, "{}", v),
+            Self::LONG(v) => write!(f, "{}", v),
+            Self::DATETIME(v) => write!(f, "{}", v),
+            Self::BOOL(v) => write!(f, "{}", v),
+            Self::BLOB(v) => to_hex_string!(f, v),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DataType;
+    use super::TimeStamp;
+    use super::Value;
+
+    fn round_trip(value: Value) {
+        let mut buf = Vec::new();
+        value.encode_to(&mut buf);
+
+        let (decoded, consumed) = Value::decode_from(&buf).unwrap();
+        assert_eq!(decoded, value);
+        assert_eq!(consumed, buf.len());
+    }
+
+    #[test]
+    fn test_encode_decode_round_trip() {
+        round_trip(Value::NULL);
+        round_trip(Value::from("hello"));
+        round_trip(Value::from(1.5));
+        round_trip(Value::from(42i32));
+        round_trip(Value::from(42i64));
+        round_trip(Value::from(TimeStamp::from(1_700_000_000_000i64)));
+        round_trip(Value::from(true));
+        round_trip(Value::from(vec![1u8, 2, 3]));
+    }
+
+    #[test]
+    fn test_encode_tag_byte_matches_datatype_discriminant() {
+        let mut buf = Vec::new();
+        Value::NULL.encode_to(&mut buf);
+        assert_eq!(buf[0], 0);
+
+        buf.clear();
+        Value::from("x").encode_to(&mut buf);
+        assert_eq!(buf[0], DataType::STRING as u8);
+
+        buf.clear();
+        Value::from(1.0).encode_to(&mut buf);
+        assert_eq!(buf[0], DataType::DOUBLE as u8);
+
+        buf.clear();
+        Value::from(1i32).encode_to(&mut buf);
+        assert_eq!(buf[0], DataType::INT as u8);
+
+        buf.clear();
+        Value::from(1i64).encode_to(&mut buf);
+        assert_eq!(buf[0], DataType::LONG as u8);
+
+        buf.clear();
+        Value::from(TimeStamp::from(0i64)).encode_to(&mut buf);
+        assert_eq!(buf[0], DataType::DATETIME as u8);
+
+        buf.clear();
+        Value::from(true).encode_to(&mut buf);
+        assert_eq!(buf[0], DataType::BOOL as u8);
+
+        buf.clear();
+        Value::from(vec![0u8]).encode_to(&mut buf);
+        assert_eq!(buf[0], DataType::BLOB as u8);
+    }
+
+    #[test]
+    fn test_decode_rejects_truncated_bytes() {
+        assert!(Value::decode_from(&[]).is_err());
+        assert!(Value::decode_from(&[DataType::LONG as u8, 1, 2]).is_err());
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let value = Value::from(vec![9u8, 8, 7]);
+        let json = serde_json::to_string(&value).unwrap();
+        let back: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, value);
+    }
+
+    #[test]
+    fn test_blob_serializes_as_bytes_not_numeric_array() {
+        use serde_test::Token;
+        use serde_test::assert_ser_tokens;
+
+        let value = Value::from(vec![1u8, 2, 3]);
+        assert_ser_tokens(&value, &[Token::NewtypeVariant { name: "Value", variant: "BLOB" }, Token::Bytes(&[1, 2, 3])]);
+    }
+}
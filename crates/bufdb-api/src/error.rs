@@ -0,0 +1,313 @@
+use std::fmt::Display;
+
+use failure::Context;
+use failure::Fail;
+
+/// Enumerates error kinds.
+#[derive(Debug, Fail, Default)]
+pub enum ErrorKind {
+    #[default]
+    #[fail(display = "Unknown error")]
+    Unknown,
+    #[fail(display = "Not found")]
+    NotFound,
+    #[fail(display = "Error datatype")]
+    DataType,
+    #[fail(display = "Index out of bounds")]
+    OutOfBounds,
+    #[fail(display = "Null value")]
+    NullValue,
+    #[fail(display = "Undefined expression")]
+    UndefinedExpr,
+    #[fail(display = "Invalidate configuration")]
+    Configuration,
+    #[fail(display = "Close using object")]
+    CloseUsing,
+    #[fail(display = "Create duplicate object")]
+    CreateDuplicate,
+    #[fail(display = "Too many files")]
+    TooManyFiles,
+    #[fail(display = "Object is already closed")]
+    AlreadyClosed,
+    #[fail(display = "Incompatible on-disk format version")]
+    IncompatibleVersion,
+    #[fail(display = "Schema migration conflict")]
+    Migration,
+    #[fail(display = "Stored schema is newer than this version of bufdb supports")]
+    IncompatibleSchema,
+    #[fail(display = "Malformed archived record")]
+    Archive,
+    #[fail(display = "Syntax error")]
+    Syntax(SyntaxError),
+    #[fail(display = "Format error")]
+    Format(#[cause] std::fmt::Error),
+    #[fail(display = "Parse float error")]
+    ParseFloat(#[cause] std::num::ParseFloatError),
+    #[fail(display = "Parse int error")]
+    ParseInt(#[cause] std::num::ParseIntError),
+    #[fail(display = "Parse bool error")]
+    ParseBool(#[cause] std::str::ParseBoolError),
+    #[fail(display = "Parse datetime error")]
+    ParseDateTime(#[cause] chrono::format::ParseError),
+    #[fail(display = "Parse timestamp error")]
+    ParseTimestamp(#[cause] chrono::format::ParseError),
+    #[fail(display = "IO error")]
+    IO(#[cause] std::io::Error),
+    #[fail(display = "JSON error")]
+    JSON(serde_json::Error),
+    #[fail(display = "database open error")]
+    DBOpen(#[cause] PhantomError),
+    #[fail(display = "database read error")]
+    DBRead(#[cause] PhantomError),
+    #[fail(display = "database write error")]
+    DBWrite(#[cause] PhantomError),
+    #[fail(display = "database close error")]
+    DBClose(#[cause] PhantomError),
+    #[fail(display = "database error")]
+    DBOther(#[cause] PhantomError),
+}
+
+/// Stable, exhaustively-matchable classification of an [`Error`], decoupled
+/// from [`ErrorKind`]'s variant shape so downstream code (and any future
+/// wire protocol) can branch on failure category without matching on
+/// payload types that may grow a `#[cause]`. The `DB*` kinds carry their own
+/// code (see [`PhantomError::code`]); every other kind maps to a fixed one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ErrorCode {
+    #[default]
+    Unknown,
+    NotFound,
+    DataType,
+    OutOfBounds,
+    NullValue,
+    UndefinedExpr,
+    Configuration,
+    CloseUsing,
+    CreateDuplicate,
+    TooManyFiles,
+    AlreadyClosed,
+    IncompatibleVersion,
+    Migration,
+    IncompatibleSchema,
+    Archive,
+    Syntax,
+    Format,
+    ParseFloat,
+    ParseInt,
+    ParseBool,
+    ParseDateTime,
+    ParseTimestamp,
+    IO,
+    JSON,
+    /// The stored or transmitted bytes don't decode to what they should.
+    Corruption,
+    /// A lock (file lock, row lock, merge lock) is held elsewhere.
+    LockContention,
+    /// The backend doesn't support the requested operation at all.
+    Unsupported,
+    Other,
+}
+
+/// Defines error type for bufdb lib.
+#[derive(Debug)]
+pub struct Error {
+    inner: Context<ErrorKind>
+}
+
+impl Fail for Error {
+    fn cause(&self) -> Option<&dyn Fail> {
+        self.inner.cause()
+    }
+
+    fn backtrace(&self) -> Option<&failure::Backtrace> {
+        self.inner.backtrace()
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.inner, f)
+    }
+}
+
+impl Default for Error {
+    fn default() -> Self {
+        Self {
+            inner: Context::new(Default::default())
+        }
+    }
+}
+
+impl Error {
+    pub fn new_datatype_err() -> Self {
+        Self {
+            inner: Context::new(ErrorKind::DataType)
+        }
+    }
+
+    pub fn kind(&self) -> &ErrorKind {
+        self.inner.get_context()
+    }
+
+    /// This error's stable [`ErrorCode`]. For the `DB*` kinds this is
+    /// whatever code the failing backend attached (see `bufdb_lib`'s
+    /// `db_error!` macro); every other kind maps to a fixed code.
+    pub fn code(&self) -> ErrorCode {
+        match self.kind() {
+            ErrorKind::Unknown => ErrorCode::Unknown,
+            ErrorKind::NotFound => ErrorCode::NotFound,
+            ErrorKind::DataType => ErrorCode::DataType,
+            ErrorKind::OutOfBounds => ErrorCode::OutOfBounds,
+            ErrorKind::NullValue => ErrorCode::NullValue,
+            ErrorKind::UndefinedExpr => ErrorCode::UndefinedExpr,
+            ErrorKind::Configuration => ErrorCode::Configuration,
+            ErrorKind::CloseUsing => ErrorCode::CloseUsing,
+            ErrorKind::CreateDuplicate => ErrorCode::CreateDuplicate,
+            ErrorKind::TooManyFiles => ErrorCode::TooManyFiles,
+            ErrorKind::AlreadyClosed => ErrorCode::AlreadyClosed,
+            ErrorKind::IncompatibleVersion => ErrorCode::IncompatibleVersion,
+            ErrorKind::Migration => ErrorCode::Migration,
+            ErrorKind::IncompatibleSchema => ErrorCode::IncompatibleSchema,
+            ErrorKind::Archive => ErrorCode::Archive,
+            ErrorKind::Syntax(_) => ErrorCode::Syntax,
+            ErrorKind::Format(_) => ErrorCode::Format,
+            ErrorKind::ParseFloat(_) => ErrorCode::ParseFloat,
+            ErrorKind::ParseInt(_) => ErrorCode::ParseInt,
+            ErrorKind::ParseBool(_) => ErrorCode::ParseBool,
+            ErrorKind::ParseDateTime(_) => ErrorCode::ParseDateTime,
+            ErrorKind::ParseTimestamp(_) => ErrorCode::ParseTimestamp,
+            ErrorKind::IO(_) => ErrorCode::IO,
+            ErrorKind::JSON(_) => ErrorCode::JSON,
+            ErrorKind::DBOpen(p) | ErrorKind::DBRead(p) | ErrorKind::DBWrite(p) | ErrorKind::DBClose(p) | ErrorKind::DBOther(p) => p.code(),
+        }
+    }
+}
+
+impl From<ErrorKind> for Error {
+    fn from(kind: ErrorKind) -> Self {
+        Self { inner: Context::new(kind) }
+    }
+}
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+impl From<std::fmt::Error> for Error {
+    fn from(err: std::fmt::Error) -> Self {
+        ErrorKind::Format(err).into()
+    }
+}
+
+impl From<std::num::ParseFloatError> for Error {
+    fn from(err: std::num::ParseFloatError) -> Self {
+        ErrorKind::ParseFloat(err).into()
+    }
+}
+
+impl From<std::num::ParseIntError> for Error {
+    fn from(err: std::num::ParseIntError) -> Self {
+        ErrorKind::ParseInt(err).into()
+    }
+}
+
+impl From<std::str::ParseBoolError> for Error {
+    fn from(err: std::str::ParseBoolError) -> Self {
+        ErrorKind::ParseBool(err).into()
+    }
+}
+
+impl From<chrono::format::ParseError> for Error {
+    fn from(err: chrono::format::ParseError) -> Self {
+        ErrorKind::ParseDateTime(err).into()
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        ErrorKind::IO(err).into()
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        ErrorKind::JSON(err).into()
+    }
+}
+
+/// Carries a backend failure into a `DB*` [`ErrorKind`] without flattening
+/// it to a string: a stable [`ErrorCode`] callers can match on, the
+/// backend's message, and — when the backend error was given to us, rather
+/// than just a literal — the original error kept alive as a [`Fail`] cause,
+/// so [`Fail::cause`] still walks into it.
+#[derive(Debug, Default)]
+pub struct PhantomError {
+    code: ErrorCode,
+    message: Option<String>,
+    cause: Option<Box<dyn Fail>>,
+}
+
+impl PhantomError {
+    pub fn from<T: Fail>(err: T) -> Self {
+        Self::with_code(ErrorCode::default(), err)
+    }
+
+    /// Like [`Self::from`], but tagging the failure with an explicit
+    /// [`ErrorCode`] instead of the default [`ErrorCode::Unknown`].
+    pub fn with_code<T: Fail>(code: ErrorCode, err: T) -> Self {
+        let message = err.to_string();
+        Self {
+            code,
+            message: if message.is_empty() { None } else { Some(message) },
+            cause: Some(Box::new(err)),
+        }
+    }
+
+    pub fn from_str(msg: &str) -> Self {
+        Self::with_code_str(ErrorCode::default(), msg)
+    }
+
+    /// Like [`Self::from_str`], but tagging the failure with an explicit
+    /// [`ErrorCode`] instead of the default [`ErrorCode::Unknown`].
+    pub fn with_code_str(code: ErrorCode, msg: &str) -> Self {
+        Self {
+            code,
+            message: Some(msg.into()),
+            cause: None,
+        }
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        self.code
+    }
+}
+
+impl Display for PhantomError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(ref msg) = self.message {
+            write!(f, "{}", msg)
+        } else {
+            write!(f, "unknown error")
+        }
+    }
+}
+
+impl Fail for PhantomError {
+    fn cause(&self) -> Option<&dyn Fail> {
+        self.cause.as_deref()
+    }
+}
+
+/// A lexer/parser failure against a DDL/query statement, carrying the
+/// 1-based line/column the offending token started at so a caller can point
+/// at the exact span instead of just a description.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl Display for SyntaxError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (line {}, column {})", self.message, self.line, self.column)
+    }
+}
@@ -0,0 +1,6 @@
+pub mod config;
+pub mod conversion;
+pub mod datatype;
+pub mod error;
+pub mod migration;
+pub mod model;
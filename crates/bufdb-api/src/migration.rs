@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+use crate::error::ErrorKind;
+use crate::error::Result;
+
+/// Upgrades a [`crate::model::TableDefine`] row's raw JSON from the schema
+/// version it was stored under to the next one.
+type Migration = Box<dyn Fn(serde_json::Value) -> Result<serde_json::Value> + Send + Sync>;
+
+/// Chain of upgraders an application registers so a [`crate::model::TableDefine`]
+/// stored under an older `version` can still be read back under the current
+/// one, keyed by the version a migration upgrades *from*. Mirrors how a
+/// distributed system gates compatibility on a numeric database version: the
+/// registry is consulted only when a stored row's version trails
+/// [`crate::model::CURRENT_SCHEMA_VERSION`], and is applied one step at a
+/// time until it catches up.
+#[derive(Default)]
+pub struct MigrationRegistry {
+    migrations: HashMap<u16, Migration>,
+}
+
+impl MigrationRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `upgrade` to turn a row stored under `from_version` into its
+    /// `from_version + 1` shape. Replaces any upgrader previously registered
+    /// for the same `from_version`.
+    pub fn register<F>(&mut self, from_version: u16, upgrade: F) where F: Fn(serde_json::Value) -> Result<serde_json::Value> + Send + Sync + 'static {
+        self.migrations.insert(from_version, Box::new(upgrade));
+    }
+
+    /// Applies every registered upgrader needed to bring `value` from
+    /// `version` up to `target`, one step at a time. Fails with
+    /// [`ErrorKind::IncompatibleSchema`] the moment a required step has no
+    /// registered upgrader.
+    pub fn upgrade(&self, mut value: serde_json::Value, mut version: u16, target: u16) -> Result<serde_json::Value> {
+        while version < target {
+            let upgrade = self.migrations.get(&version).ok_or(ErrorKind::IncompatibleSchema)?;
+            value = upgrade(value)?;
+            version += 1;
+        }
+
+        Ok(value)
+    }
+}
+
+impl Debug for MigrationRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MigrationRegistry").field("registered", &self.migrations.len()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::MigrationRegistry;
+
+    #[test]
+    fn test_upgrade() {
+        let mut registry = MigrationRegistry::new();
+        registry.register(0, |mut v| {
+            v["comment"] = json!(null);
+            Ok(v)
+        });
+        registry.register(1, |mut v| {
+            v["version"] = json!(2);
+            Ok(v)
+        });
+
+        let value = json!({"name": "t"});
+        let upgraded = registry.upgrade(value, 0, 2).unwrap();
+
+        assert_eq!(upgraded["comment"], json!(null));
+        assert_eq!(upgraded["version"], json!(2));
+    }
+
+    #[test]
+    fn test_upgrade_missing_step() {
+        let registry = MigrationRegistry::new();
+        let value = json!({"name": "t"});
+
+        assert!(registry.upgrade(value, 0, 1).is_err());
+    }
+}
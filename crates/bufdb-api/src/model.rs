@@ -1,36 +1,130 @@
+use rkyv::Archive;
+use rkyv::Deserialize as RkyvDeserialize;
+use rkyv::Serialize as RkyvSerialize;
 use serde::Deserialize;
 use serde::Serialize;
 
 use crate::datatype::DataType;
+use crate::error::ErrorKind;
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize, Archive, RkyvSerialize, RkyvDeserialize)]
+#[archive(check_bytes)]
+#[archive_attr(derive(Debug, PartialEq, Eq))]
 pub enum IndexType {
     #[default]
     NORMAL = 0,
     UNIQUE = 1,
+    /// An FST-backed inverted index over the tokenized terms of
+    /// [`IndexDefine::fields`]' values, supporting prefix and fuzzy
+    /// (Levenshtein) lookups in addition to exact matches. `OrderMode` on
+    /// those fields is meaningless for a term and is ignored.
+    FULLTEXT = 2,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize, Archive, RkyvSerialize, RkyvDeserialize)]
+#[archive(check_bytes)]
+#[archive_attr(derive(Debug, PartialEq, Eq))]
 pub enum OrderMode {
     #[default]
     ASC = 0,
     DESC = 1
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Archive, RkyvSerialize, RkyvDeserialize)]
+#[archive(check_bytes)]
+#[archive_attr(derive(Debug))]
 pub struct FieldDefine {
     pub name: String,
     pub datatype: DataType,
-    pub comment: Option<String>
+    pub comment: Option<String>,
+    /// Value used to backfill this field on rows that predate it. Required
+    /// by `Schema::migrate` when the field is being added to an existing
+    /// table; ignored for a field present from a table's creation.
+    ///
+    /// `serde_json::Value` has no `rkyv` support, so this field is left out
+    /// of the archived representation (`#[with(Skip)]` below) — it reads
+    /// back as `None` off an archive. `Schema::migrate` only ever reads a
+    /// `TableDefine` parsed from JSON (see [`TableDefine::try_from`]), so
+    /// this doesn't affect migration's own use of the default.
+    #[serde(default)]
+    #[with(rkyv::with::Skip)]
+    pub default: Option<serde_json::Value>,
+    /// Whether this field's value is written into the primary row. A
+    /// non-stored field exists purely to be indexed, letting the storage
+    /// layer skip keeping primary-row state for columns that are only ever
+    /// looked up through one of [`TableDefine::indexes`].
+    #[serde(default = "default_stored")]
+    pub stored: bool,
+    /// Whether this field participates in at least one
+    /// [`TableDefine::indexes`] entry. `MetaStorage::put` rejects an
+    /// `IndexDefine`/`OrderedField` naming a field for which this is `false`.
+    #[serde(default)]
+    pub indexed: bool,
+    /// Whether this field may be absent/null on a row. Defaults to `true`;
+    /// unlike `stored`/`indexed` this is advisory metadata only — nothing
+    /// in this crate enforces it against a written row yet.
+    #[serde(default = "default_nullable")]
+    pub nullable: bool,
+}
+
+fn default_stored() -> bool {
+    true
+}
+
+fn default_nullable() -> bool {
+    true
+}
+
+impl FieldDefine {
+    pub fn new<S: Into<String>>(name: S, datatype: DataType) -> Self {
+        Self {
+            name: name.into(),
+            datatype,
+            comment: None,
+            default: None,
+            stored: true,
+            indexed: false,
+            nullable: true,
+        }
+    }
+
+    pub fn set_comment<S: Into<String>>(mut self, comment: S) -> Self {
+        self.comment = Some(comment.into());
+        self
+    }
+
+    pub fn set_default(mut self, default: serde_json::Value) -> Self {
+        self.default = Some(default);
+        self
+    }
+
+    pub fn set_stored(mut self, stored: bool) -> Self {
+        self.stored = stored;
+        self
+    }
+
+    pub fn set_indexed(mut self, indexed: bool) -> Self {
+        self.indexed = indexed;
+        self
+    }
+
+    pub fn set_nullable(mut self, nullable: bool) -> Self {
+        self.nullable = nullable;
+        self
+    }
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Archive, RkyvSerialize, RkyvDeserialize)]
+#[archive(check_bytes)]
+#[archive_attr(derive(Debug))]
 pub struct OrderedField {
     pub field_name: String,
     pub order_mode: OrderMode
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Archive, RkyvSerialize, RkyvDeserialize)]
+#[archive(check_bytes)]
+#[archive_attr(derive(Debug))]
 pub struct IndexDefine {
     pub name: String,
     pub index_type: IndexType,
@@ -38,25 +132,106 @@ pub struct IndexDefine {
     pub comment: Option<String>
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+/// Current shape of a JSON-encoded [`TableDefine`]. Stamped onto every
+/// `TableDefine` created through [`TableDefine::new`]; a row read back with
+/// an older `version` is upgraded through a
+/// [`MigrationRegistry`](crate::migration::MigrationRegistry) before it's
+/// parsed, and one read back with a newer `version` than this is rejected
+/// with [`ErrorKind::IncompatibleSchema`] rather than silently misread.
+pub const CURRENT_SCHEMA_VERSION: u16 = 1;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Archive, RkyvSerialize, RkyvDeserialize)]
+#[archive(check_bytes)]
+#[archive_attr(derive(Debug))]
 pub struct TableDefine {
     pub name: String,
     pub comment: Option<String>,
     pub fields: Vec<FieldDefine>,
     pub key_fields: Vec<String>,
-    pub indexes: Vec<IndexDefine>
+    pub indexes: Vec<IndexDefine>,
+    /// JSON schema-format version this definition was stored under. Missing
+    /// on a row written before versioning existed, which `#[serde(default)]`
+    /// reads back as `0` — the oldest recognized version.
+    #[serde(default)]
+    pub version: u16,
 }
 
+/// Layout version stamped on every [`TableDefine::archive`] output, ahead of
+/// the archived bytes themselves, so a future change to the archived
+/// representation can be told apart from this one instead of being
+/// misread as (or failing `bytecheck` validation against) the current
+/// layout.
+const ARCHIVE_VERSION: u8 = 1;
+
+/// Bytes reserved ahead of the archived payload for [`ARCHIVE_VERSION`],
+/// padded out to [`rkyv::AlignedVec`]'s own alignment so the payload that
+/// follows starts on the same alignment boundary `rkyv` serialized it
+/// against. Writing the version byte straight onto the front of the buffer
+/// would shift the payload by one byte and break zero-copy access.
+const HEADER_LEN: usize = 16;
+
+/// Byte buffer returned by [`TableDefine::archive`]. An alias rather than a
+/// bare `Vec<u8>` so callers don't need to depend on `rkyv` directly to name
+/// the type, and so the alignment [`rkyv::AlignedVec`] guarantees for
+/// zero-copy access is visible in the signature.
+pub type AlignedBytes = rkyv::AlignedVec;
+
 impl TableDefine {
     pub fn new<S: Into<String>>(name: S) -> Self {
-        Self { 
-            name: name.into(), 
-            comment: None, 
-            fields: Vec::new(), 
-            key_fields: Vec::new(), 
-            indexes: Vec::new() 
+        Self {
+            name: name.into(),
+            comment: None,
+            fields: Vec::new(),
+            key_fields: Vec::new(),
+            indexes: Vec::new(),
+            version: CURRENT_SCHEMA_VERSION,
         }
     }
+
+    pub fn add_field(mut self, field: FieldDefine) -> Self {
+        self.fields.push(field);
+        self
+    }
+
+    pub fn add_key_field<S: Into<String>>(mut self, name: S) -> Self {
+        self.key_fields.push(name.into());
+        self
+    }
+
+    pub fn add_index(mut self, index: IndexDefine) -> Self {
+        self.indexes.push(index);
+        self
+    }
+
+    /// Serializes this definition to a zero-copy [`rkyv`] archive, prefixed
+    /// with the current [`ARCHIVE_VERSION`]. The result can be read back
+    /// in place with [`Self::access_archived`] — no allocation beyond the
+    /// buffer itself, unlike the `serde_json` round trip `TryFrom`/`TryInto`
+    /// do.
+    pub fn archive(&self) -> AlignedBytes {
+        let bytes = rkyv::to_bytes::<_, 256>(self).expect("TableDefine archival is infallible");
+
+        let mut framed = AlignedBytes::with_capacity(HEADER_LEN + bytes.len());
+        framed.push(ARCHIVE_VERSION);
+        for _ in 1..HEADER_LEN {
+            framed.push(0);
+        }
+        framed.extend_from_slice(&bytes);
+        framed
+    }
+
+    /// Validates `bytes` as a [`Self::archive`] output and hands back a
+    /// reference straight into it, without decoding. Malformed bytes (a
+    /// version mismatch, or a `bytecheck` failure on the archived payload)
+    /// come back as [`ErrorKind::Archive`] rather than undefined behavior.
+    pub fn access_archived(bytes: &[u8]) -> crate::error::Result<&ArchivedTableDefine> {
+        if bytes.len() < HEADER_LEN || bytes[0] != ARCHIVE_VERSION {
+            return Err(ErrorKind::Archive.into());
+        }
+
+        let payload = &bytes[HEADER_LEN..];
+        rkyv::check_archived_root::<TableDefine>(payload).map_err(|_| ErrorKind::Archive.into())
+    }
 }
 
 impl TryInto<String> for &TableDefine {
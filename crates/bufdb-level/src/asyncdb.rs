@@ -0,0 +1,88 @@
+use std::sync::Arc;
+
+use bufdb_lib::db_error;
+use bufdb_lib::error::Result;
+use bufdb_storage::async_engine::AsyncDatabase;
+use bufdb_storage::async_engine::AsyncStorageEngine;
+use bufdb_storage::entry::BufferEntry;
+use tokio::task::spawn_blocking;
+
+use crate::database::DBImpl;
+
+/// Async facade over a [`DBImpl`], running every call on the blocking
+/// thread-pool via `spawn_blocking` so the synchronous `leveldb` FFI never
+/// stalls a tokio executor.
+#[derive(Clone)]
+pub struct AsyncDBImpl {
+    database: Arc<DBImpl>,
+}
+
+impl AsyncDBImpl {
+    pub(crate) fn new(database: Arc<DBImpl>) -> Self {
+        Self { database }
+    }
+}
+
+async fn run_blocking<T, F>(f: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T> + Send + 'static,
+{
+    match spawn_blocking(f).await {
+        Ok(result) => result,
+        Err(e) => Err(db_error!(IO => e)),
+    }
+}
+
+impl AsyncDatabase for AsyncDBImpl {
+    async fn count(&self) -> Result<usize> {
+        let database = self.database.clone();
+        run_blocking(move || database.count()).await
+    }
+
+    async fn put(&self, key: &BufferEntry, data: &BufferEntry) -> Result<()> {
+        let database = self.database.clone();
+        let key = key.clone();
+        let data = data.clone();
+        run_blocking(move || database.put(&key, &data)).await
+    }
+
+    async fn get(&self, key: &BufferEntry) -> Result<Option<BufferEntry>> {
+        let database = self.database.clone();
+        let key = key.clone();
+        run_blocking(move || database.get(&key)).await
+    }
+
+    async fn delete(&self, key: &BufferEntry) -> Result<()> {
+        let database = self.database.clone();
+        let key = key.clone();
+        run_blocking(move || database.delete(&key)).await
+    }
+
+    async fn delete_exist(&self, key: &BufferEntry) -> Result<bool> {
+        let database = self.database.clone();
+        let key = key.clone();
+        run_blocking(move || {
+            if database.get(&key)?.is_some() {
+                database.delete(&key)?;
+                Ok(true)
+            } else {
+                Ok(false)
+            }
+        }).await
+    }
+}
+
+/// Async counterpart of [`crate::LevelDBEngine`], kept alongside the
+/// synchronous engine rather than replacing it.
+#[derive(Debug, Clone, Copy)]
+pub struct AsyncLevelDBEngine {}
+
+impl AsyncStorageEngine for AsyncLevelDBEngine {
+    type DATABASE = AsyncDBImpl;
+    type SDATABASE = AsyncDBImpl;
+
+    fn name(&self) -> &str {
+        "Level DB Engine (async)"
+    }
+}
@@ -1,11 +1,17 @@
+use std::sync::Arc;
+
 use bufdb_storage::KeyComparator;
+use bufdb_storage::comparator::KeyTransform;
 use bufdb_storage::entry::BufferEntry;
 use leveldb::comparator::Comparator;
 use libc::c_char;
 
 use crate::suffix::unwrap_suffix;
 
-pub struct PKComparator<C: KeyComparator>(C);
+pub struct PKComparator<C: KeyComparator> {
+    comparator: Arc<C>,
+    transform: Option<Arc<dyn KeyTransform>>
+}
 
 impl <C: KeyComparator> Comparator for PKComparator<C> {
     type K = BufferEntry;
@@ -15,23 +21,38 @@ impl <C: KeyComparator> Comparator for PKComparator<C> {
     }
 
     fn compare(&self, a: &Self::K, b: &Self::K) -> std::cmp::Ordering {
-        self.0.compare(a, b).unwrap()
+        match &self.transform {
+            Some(transform) => transform.compare_encoded(a, b).unwrap(),
+            None => self.comparator.compare(a, b).unwrap()
+        }
+    }
+}
+
+impl <T: KeyComparator> PKComparator<T> {
+    /// Applies `transform` to every key this comparator is asked to order,
+    /// so on-disk keys can use a collation (e.g. [`bufdb_storage::comparator::SignFlipI64Transform`])
+    /// that a raw byte-wise or [`KeyComparator`] compare wouldn't get right.
+    pub fn with_transform(comparator: Arc<T>, transform: Arc<dyn KeyTransform>) -> Self {
+        Self { comparator, transform: Some(transform) }
     }
 }
 
-impl <T: KeyComparator> From<T> for PKComparator<T> {
-    fn from(value: T) -> Self {
-        Self(value)
+impl <T: KeyComparator> From<Arc<T>> for PKComparator<T> {
+    fn from(value: Arc<T>) -> Self {
+        Self { comparator: value, transform: None }
     }
 }
 
 impl <T: KeyComparator> AsRef<T> for PKComparator<T> {
     fn as_ref(&self) -> &T {
-        &self.0
+        &self.comparator
     }
 }
 
-pub struct IDXComparator<C: KeyComparator>(C);
+pub struct IDXComparator<C: KeyComparator> {
+    comparator: Arc<C>,
+    transform: Option<Arc<dyn KeyTransform>>
+}
 
 impl <C: KeyComparator> Comparator for IDXComparator<C> {
     type K = BufferEntry;
@@ -44,7 +65,11 @@ impl <C: KeyComparator> Comparator for IDXComparator<C> {
         let (key1, ord1) = unwrap_suffix(a).unwrap();
         let (key2, ord2) = unwrap_suffix(b).unwrap();
 
-        let c = self.0.compare(&key1, &key2).unwrap();
+        let c = match &self.transform {
+            Some(transform) => transform.compare_encoded(&key1, &key2).unwrap(),
+            None => self.comparator.compare(&key1, &key2).unwrap()
+        };
+
         if c.is_eq() {
             ord1.cmp(&ord2).reverse()
         } else {
@@ -53,14 +78,22 @@ impl <C: KeyComparator> Comparator for IDXComparator<C> {
     }
 }
 
-impl <T: KeyComparator> From<T> for IDXComparator<T> {
-    fn from(value: T) -> Self {
-        Self(value)
+impl <T: KeyComparator> IDXComparator<T> {
+    /// See [`PKComparator::with_transform`]; applied to the index key
+    /// portion only, before the suffix ordinal tie-break.
+    pub fn with_transform(comparator: Arc<T>, transform: Arc<dyn KeyTransform>) -> Self {
+        Self { comparator, transform: Some(transform) }
+    }
+}
+
+impl <T: KeyComparator> From<Arc<T>> for IDXComparator<T> {
+    fn from(value: Arc<T>) -> Self {
+        Self { comparator: value, transform: None }
     }
 }
 
 impl <T: KeyComparator> AsRef<T> for IDXComparator<T> {
     fn as_ref(&self) -> &T {
-        &self.0
+        &self.comparator
     }
-}
\ No newline at end of file
+}
@@ -1,18 +1,23 @@
+use std::cmp::Ordering;
 use std::sync::Arc;
 
 use bufdb_lib::db_error_s;
 use bufdb_lib::error::Result;
 use bufdb_storage::PrimaryCursor;
 use bufdb_storage::SecondaryCursor;
+use bufdb_storage::comparator::ErasedComparator;
 use bufdb_storage::entry::BufferEntry;
 use bufdb_storage::entry::Entry;
+use bufdb_storage::entry::compare;
 use leveldb::iterator::Iterator;
 use leveldb::iterator::LevelDBIterator;
+use leveldb::options::ReadOptions;
 
 use crate::database::DBImpl;
 use crate::suffix::append_suffix;
 use crate::suffix::size_of_suffix;
 use crate::suffix::trucate_suffix;
+use crate::transaction::StagedOp;
 
 macro_rules! vec_to_buf {
     ($data: expr, $buf: ident) => {
@@ -31,24 +36,121 @@ macro_rules! buf_to_buf {
 }
 
 pub struct PKCursor<'a> {
-    iter: Iterator<'a, BufferEntry>
+    db: &'a Arc<DBImpl>,
+    iter: Iterator<'a, BufferEntry>,
+    /// Item pulled from `iter` but not yet returned, cached so the merge with
+    /// `staged` can peek at it without consuming it.
+    base_peek: Option<(BufferEntry, Vec<u8>)>,
+    /// Snapshot of a transaction's staged mutations, sorted ascending by key,
+    /// taken when the cursor was opened. Empty for a cursor opened directly
+    /// on the database outside of a transaction.
+    staged: Vec<StagedOp>,
+    staged_pos: usize,
+    comparator: ErasedComparator,
 }
 
 impl <'a> PKCursor<'a> {
     pub(crate) fn new(db: &'a Arc<DBImpl>) -> Self {
-        let iter = db.iter(read_options!());
+        Self::new_staged(db, Vec::new())
+    }
+
+    /// Creates a cursor that also merges in `staged`, the uncommitted writes
+    /// of the transaction it was opened from.
+    pub(crate) fn new_staged(db: &'a Arc<DBImpl>, staged: Vec<StagedOp>) -> Self {
+        Self::new_with_options(db, read_options!(), staged)
+    }
+
+    /// Creates a cursor that reads through `options` rather than the
+    /// default read view, e.g. a [`crate::snapshot::LevelEnvSnapshot`]'s
+    /// `leveldb` snapshot.
+    pub(crate) fn new_with_options(db: &'a Arc<DBImpl>, options: ReadOptions<'a, BufferEntry>, staged: Vec<StagedOp>) -> Self {
+        let iter = db.iter(options);
         Self {
-            iter
+            db,
+            iter,
+            base_peek: None,
+            staged,
+            staged_pos: 0,
+            comparator: db.comparator().clone(),
+        }
+    }
+
+    /// Pulls the next entry off the committed iterator, stripping its TTL
+    /// expiry prefix (if any, same as [`DBImpl::get`]) and decompressing its
+    /// value so it sits alongside the (already plaintext) staged entries on
+    /// equal footing. See [`bufdb_storage::compression`]. An entry whose TTL
+    /// has already passed is skipped, same as `get` returning `None` for it.
+    fn fill_base_peek(&mut self) -> Result<()> {
+        while self.base_peek.is_none() {
+            match self.iter.next() {
+                Some((key, data)) => {
+                    if let Some(data) = self.db.untag(BufferEntry::from(data))? {
+                        let data = bufdb_storage::compression::decompress(&data)?;
+                        self.base_peek = Some((key, data.slice().to_vec()));
+                    }
+                },
+                None => break,
+            };
+        }
+
+        Ok(())
+    }
+
+    /// Seeks the committed iterator and the staged snapshot to the first
+    /// entry `>= key`.
+    fn seek(&mut self, key: &BufferEntry) {
+        self.iter.seek(key);
+        self.base_peek = None;
+        self.staged_pos = self.staged.partition_point(|(k, _)| compare(k, key) == Ordering::Less);
+    }
+
+    /// Pulls the next `(key, data)` pair from the merge of the committed
+    /// iterator and the staged snapshot, in ascending key order. On a
+    /// matching key the staged entry shadows the committed one; a staged
+    /// tombstone (delete) is skipped entirely rather than returned.
+    fn advance(&mut self) -> Result<Option<(BufferEntry, Vec<u8>)>> {
+        loop {
+            self.fill_base_peek()?;
+
+            let take_staged = |this: &mut Self| {
+                let (key, value) = this.staged[this.staged_pos].clone();
+                this.staged_pos += 1;
+                value.map(|data| (key, data))
+            };
+
+            match (&self.base_peek, self.staged.get(self.staged_pos)) {
+                (None, None) => return Ok(None),
+                (Some(_), None) => return Ok(self.base_peek.take()),
+                (None, Some(_)) => {
+                    if let Some(entry) = take_staged(self) {
+                        return Ok(Some(entry));
+                    }
+                }
+                (Some((b_key, _)), Some((s_key, _))) => match compare(b_key, s_key) {
+                    Ordering::Less => return Ok(self.base_peek.take()),
+                    Ordering::Greater => {
+                        if let Some(entry) = take_staged(self) {
+                            return Ok(Some(entry));
+                        }
+                    }
+                    Ordering::Equal => {
+                        self.base_peek = None;
+                        if let Some(entry) = take_staged(self) {
+                            return Ok(Some(entry));
+                        }
+                    }
+                },
+            }
         }
     }
 }
 
 impl <'a> PrimaryCursor<'a> for PKCursor<'a> {
     fn search(&mut self, key: &bufdb_storage::entry::BufferEntry, data: Option<&mut bufdb_storage::entry::BufferEntry>) -> bufdb_lib::error::Result<bool> {
-        self.iter.seek(key);
+        self.seek(key);
 
-        if let Some((n_key, n_data)) = self.iter.next() {
-            if *key == n_key {
+        if let Some((n_key, n_data)) = self.advance()? {
+            if self.comparator.eq(key, &n_key) {
                 vec_to_buf!(n_data, data);
 
                 Ok(true)
@@ -61,13 +163,13 @@ impl <'a> PrimaryCursor<'a> for PKCursor<'a> {
     }
 
     fn search_range(&mut self, key: &mut bufdb_storage::entry::BufferEntry, data: Option<&mut bufdb_storage::entry::BufferEntry>) -> bufdb_lib::error::Result<bool> {
-        self.iter.seek(key);
+        self.seek(key);
 
         self.next(Some(key), data)
     }
 
     fn next(&mut self, key: Option<&mut bufdb_storage::entry::BufferEntry>, data: Option<&mut bufdb_storage::entry::BufferEntry>) -> bufdb_lib::error::Result<bool> {
-        if let Some((n_key, n_data)) = self.iter.next() {
+        if let Some((n_key, n_data)) = self.advance()? {
             buf_to_buf!(n_key, key);
             vec_to_buf!(n_data, data);
 
@@ -83,7 +185,7 @@ impl <'a> PrimaryCursor<'a> for PKCursor<'a> {
 
     fn skip(&mut self, count: usize, key: Option<&mut bufdb_storage::entry::BufferEntry>, data: Option<&mut bufdb_storage::entry::BufferEntry>) -> bufdb_lib::error::Result<bool> {
         let mut count = count;
-        while let Some((n_key, n_data)) = self.iter.next() {
+        while let Some((n_key, n_data)) = self.advance()? {
             count -= 1;
             if count == 0 {
                 buf_to_buf!(n_key, key);
@@ -97,21 +199,37 @@ impl <'a> PrimaryCursor<'a> for PKCursor<'a> {
     }
 }
 
+/// A cursor over a secondary index.
+///
+/// Unlike [`PKCursor`], this does not merge a transaction's staged writes:
+/// secondary index entries are only maintained by `IndexListener` when a
+/// primary `put`/`delete` actually commits, so a secondary cursor opened from
+/// a transaction only sees already-committed index state until that
+/// transaction commits.
 pub struct IDXCursor<'a> {
     db: Arc<DBImpl>,
     iter: Iterator<'a, BufferEntry>,
+    comparator: ErasedComparator,
     do_seek: fn (&mut Self, &BufferEntry) -> Result<()>,
-    do_match: fn (&BufferEntry, &BufferEntry) -> Result<bool>,
+    do_match: fn (&Self, &BufferEntry, &BufferEntry) -> Result<bool>,
     do_rekey: fn (&mut BufferEntry),
     do_next_dup: fn (&mut Self) -> Result<Option<(BufferEntry, Vec<u8>)>>,
 }
 
 impl <'a> IDXCursor<'a> {
     pub(crate) fn new(pdb: &'a Arc<DBImpl>, idb: &'a Arc<DBImpl>) -> Self {
-        let iter = idb.iter(read_options!());
-        Self { 
-            db: pdb.clone(), 
-            iter, 
+        Self::new_with_options(pdb, idb, read_options!())
+    }
+
+    /// Creates a secondary cursor that reads through `options` rather than
+    /// the default read view, e.g. a [`crate::snapshot::LevelEnvSnapshot`]'s
+    /// `leveldb` snapshot.
+    pub(crate) fn new_with_options(pdb: &'a Arc<DBImpl>, idb: &'a Arc<DBImpl>, options: ReadOptions<'a, BufferEntry>) -> Self {
+        let iter = idb.iter(options);
+        Self {
+            db: pdb.clone(),
+            iter,
+            comparator: idb.comparator().clone(),
             do_seek: if idb.unique() { Self::seek_unique } else { Self::seek_non_unique },
             do_match: if idb.unique() { Self::match_unique } else { Self::match_non_unique },
             do_rekey: if idb.unique() { Self::rekey_unique } else { Self::rekey_non_unique },
@@ -137,16 +255,21 @@ impl <'a> IDXCursor<'a> {
 
     fn match_key(&self, key: &BufferEntry, skey: &BufferEntry) -> Result<bool> {
         let match_fn = &self.do_match;
-        match_fn(key, skey)
+        match_fn(self, key, skey)
     }
 
-    fn match_unique(key: &BufferEntry, skey: &BufferEntry) -> Result<bool> {
-        Ok(key == skey)
+    fn match_unique(&self, key: &BufferEntry, skey: &BufferEntry) -> Result<bool> {
+        Ok(self.comparator.eq(key, skey))
     }
 
-    fn match_non_unique(key: &BufferEntry, skey: &BufferEntry) -> Result<bool> {
+    fn match_non_unique(&self, key: &BufferEntry, skey: &BufferEntry) -> Result<bool> {
         let slice = skey.left(skey.size() - size_of_suffix(skey))?;
-        Ok(key.as_slice_entry() == slice)
+        if self.comparator.can_differ_bytes_equal() {
+            let slice = BufferEntry::from(slice.slice().to_vec());
+            Ok(self.comparator.eq(key, &slice))
+        } else {
+            Ok(key.slice() == slice.slice())
+        }
     }
 
     fn rekey(&self, skey: BufferEntry) -> BufferEntry {
@@ -179,7 +302,12 @@ impl <'a> IDXCursor<'a> {
         if let Some((n_key, n_data)) = self.iter.next() {
             let prev = trucate_suffix(&key)?;
             let cur = trucate_suffix(&n_key)?;
-            if prev == cur {
+            let matches = if self.comparator.can_differ_bytes_equal() {
+                self.comparator.eq(&prev, &cur)
+            } else {
+                prev == cur
+            };
+            if matches {
                 Ok(Some((n_key, n_data)))
             } else {
                 Ok(None)
@@ -197,7 +325,7 @@ impl <'a> IDXCursor<'a> {
                 if let Some(found) = self.db.get(key)? {
                     data.set_buffer(found);
                 } else {
-                    return Err(db_error_s!(read => "Index mismatch"));
+                    return Err(db_error_s!(read, Corruption => "Index mismatch"));
                 }
             }
         } else if let Some(data) = data {
@@ -205,7 +333,7 @@ impl <'a> IDXCursor<'a> {
             if let Some(found) = self.db.get(&key)? {
                 data.set_buffer(found);
             } else {
-                return Err(db_error_s!(read => "Index mismatch"));
+                return Err(db_error_s!(read, Corruption => "Index mismatch"));
             }
         }
 
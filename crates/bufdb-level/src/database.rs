@@ -1,16 +1,29 @@
 use std::fmt::Debug;
 use std::fmt::Display;
+use std::io::Write;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::Mutex;
 use std::sync::RwLock;
+use std::time::Duration;
 
+use bufdb_api::config::Compression as ValueCompression;
 use bufdb_lib::db_error;
+use bufdb_lib::db_error_s;
 use bufdb_lib::error::Result;
 use bufdb_storage::KeyComparator;
 use bufdb_storage::KeyCreator;
+use bufdb_storage::MergeOperator;
 use bufdb_storage::SDatabaseConfig;
+use bufdb_storage::cache::now;
+use bufdb_storage::comparator::ErasedComparator;
 use bufdb_storage::entry::BufferEntry;
 use bufdb_storage::entry::Entry;
+use bufdb_storage::io::BufferOutput;
+use bufdb_storage::io::Input;
+use bufdb_storage::io::Output;
+use leveldb::batch::Batch;
+use leveldb::batch::Writebatch;
 use leveldb::database::Database;
 use leveldb::iterator::Iterable;
 use leveldb::iterator::Iterator;
@@ -20,6 +33,8 @@ use leveldb::kv::KV;
 use leveldb::options::Options;
 use leveldb::options::ReadOptions;
 use leveldb::options::WriteOptions;
+use leveldb::snapshots::Snapshot as LevelSnapshot;
+use leveldb::snapshots::Snapshots;
 use leveldb_sys::Compression;
 
 use crate::comparator::IDXComparator;
@@ -40,21 +55,45 @@ macro_rules! read_options {
     };
 }
 
+/// Width, in bytes, of the absolute expiry timestamp [`DBImpl::tag`]
+/// prefixes onto a value when [`DBImpl::ttl`] is configured.
+const TTL_PREFIX_LEN: usize = 8;
+
 pub(crate) struct DBImpl{
     name: String,
     dir: PathBuf,
     readonly: bool,
     temporary: bool,
     unique: bool,
-    db: Database<BufferEntry>
+    comparator: ErasedComparator,
+    db: Database<BufferEntry>,
+    merge_operator: Option<MergeOperator>,
+    /// Codec applied to stored values on [`DBImpl::put`] and undone on
+    /// [`DBImpl::get`] and raw cursor iteration. See
+    /// [`bufdb_storage::compression`].
+    compression: ValueCompression,
+    /// Serializes `merge`'s read-modify-write so concurrent merges on the
+    /// same database fold through the operator one at a time, in order,
+    /// instead of racing on a stale read.
+    merge_lock: Mutex<()>,
+    /// How long a value lives after being written, or `None` if entries
+    /// never expire. See [`bufdb_storage::DatabaseConfig::ttl`]. When set,
+    /// every value is stored with an 8-byte absolute expiry timestamp
+    /// prefix (written and checked by [`DBImpl::tag`]/[`DBImpl::untag`]),
+    /// the same scheme `bufdb::table::expiry` uses one layer up, at the
+    /// table rather than the storage engine.
+    ttl: Option<Duration>,
 }
 
 impl DBImpl {
-    fn new<C: bufdb_storage::KeyComparator>(name: &str, dir: PathBuf, readonly: bool, temporary: bool, unique: bool, comparator: C) -> Result<DBImpl> {
+    fn new<C: bufdb_storage::KeyComparator>(name: &str, dir: PathBuf, readonly: bool, temporary: bool, unique: bool, comparator: C, merge_operator: Option<MergeOperator>, compression: ValueCompression, ttl: Option<Duration>) -> Result<DBImpl> {
         let mut options = Options::new();
         options.create_if_missing = !readonly;
         options.compression = Compression::Snappy;
 
+        let comparator = Arc::new(comparator);
+        let erased = ErasedComparator::new(comparator.clone());
+
         let raw_db = if unique {
             Database::open_with_comparator(&dir, options, PKComparator::from(comparator))
         } else {
@@ -63,7 +102,7 @@ impl DBImpl {
 
         let db = match raw_db {
             Ok(db) => db,
-            Err(e) => return Err(db_error!(open => e)),
+            Err(e) => return Err(db_error!(open, IO => e)),
         };
 
         Ok(DBImpl {
@@ -72,38 +111,190 @@ impl DBImpl {
             readonly,
             temporary,
             unique,
-            db
+            comparator: erased,
+            db,
+            merge_operator,
+            compression,
+            merge_lock: Mutex::new(()),
+            ttl,
         })
     }
 
+    pub(crate) fn compression(&self) -> ValueCompression {
+        self.compression
+    }
+
     pub fn is_empty(&self) -> Result<bool> {
         let next = self.db.iter(read_options!(quick)).next();
         Ok(next.is_none())
     }
 
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Takes a `leveldb` snapshot of this database's current sequence
+    /// number, for reuse across every cursor opened from a single
+    /// [`crate::snapshot::LevelEnvSnapshot`].
+    pub(crate) fn snapshot(&self) -> LevelSnapshot<'_, BufferEntry> {
+        self.db.snapshot()
+    }
+
     pub fn unique(&self) -> bool {
         self.unique
     }
 
+    pub fn comparator(&self) -> &ErasedComparator {
+        &self.comparator
+    }
+
     pub fn count(&self) -> Result<usize> {
         let count = self.db.iter(read_options!(quick)).count();
         Ok(count)
     }
 
     pub fn put(&self, key: &BufferEntry, data: &BufferEntry) -> Result<()> {
+        let data = bufdb_storage::compression::compress(self.compression, data)?;
+        let data = self.tag(data)?;
+
         self.db.put(WriteOptions::new(), key, data.slice())
-            .map_err(|e| db_error!(write => e))
+            .map_err(|e| db_error!(write, IO => e))
     }
 
     pub fn get(&self, key: &BufferEntry) -> Result<Option<BufferEntry>> {
-        self.db.get(ReadOptions::new(), key)
-            .map(|data| data.map(|d| d.into()))
-            .map_err(|e| db_error!(read => e))
+        let data = self.db.get(ReadOptions::new(), key)
+            .map(|data| data.map(BufferEntry::from))
+            .map_err(|e| db_error!(read, IO => e))?;
+
+        let data = data.map(|data| self.untag(data)).transpose()?.flatten();
+
+        data.map(|data| bufdb_storage::compression::decompress(&data)).transpose()
+    }
+
+    /// Prefixes `data` with its absolute millisecond expiry timestamp if
+    /// this database has a [`Self::ttl`] configured, otherwise returns it
+    /// unchanged.
+    fn tag(&self, data: BufferEntry) -> Result<BufferEntry> {
+        match self.ttl {
+            Some(ttl) => {
+                let mut output = BufferOutput::new();
+                output.write_i64(now() + ttl.as_millis() as i64)?;
+                output.write_all(data.slice())?;
+                Ok(output.into())
+            },
+            None => Ok(data),
+        }
+    }
+
+    /// Strips `data`'s expiry prefix back off if this database has a
+    /// [`Self::ttl`] configured, returning `None` if it has already passed.
+    /// Returns `data` unchanged if no `ttl` is configured.
+    pub(crate) fn untag(&self, data: BufferEntry) -> Result<Option<BufferEntry>> {
+        match self.ttl {
+            Some(_) => {
+                let expires_at = data.as_input().read_i64()?;
+                if expires_at <= now() {
+                    Ok(None)
+                } else {
+                    let rest = data.slice()[TTL_PREFIX_LEN..].to_vec();
+                    let size = rest.len();
+                    Ok(Some(BufferEntry::new(rest, 0, size)))
+                }
+            },
+            None => Ok(Some(data)),
+        }
     }
 
     pub fn delete(&self, key: &BufferEntry) -> Result<()> {
         self.db.delete(WriteOptions::new(), key)
-            .map_err(|e| db_error!(write => e))
+            .map_err(|e| db_error!(write, IO => e))
+    }
+
+    /// Reads `key`'s current value, folds `operand` into it through the
+    /// configured [`MergeOperator`] and writes the result back. The read and
+    /// write are guarded by `merge_lock` so concurrent merges on this
+    /// database never interleave.
+    pub fn merge(&self, key: &BufferEntry, operand: &BufferEntry) -> Result<()> {
+        let operator = self.merge_operator.as_ref()
+            .ok_or_else(|| db_error_s!(write, Configuration => "No merge operator configured for this database"))?;
+
+        let _guard = self.merge_lock.lock().unwrap();
+
+        let existing = self.get(key)?;
+        let merged = operator(key, existing.as_ref(), std::slice::from_ref(operand))?;
+
+        self.put(key, &merged)
+    }
+
+    /// Applies a batch of staged mutations atomically, e.g. when a
+    /// [`crate::transaction::LevelTransaction`] commits.
+    pub fn write_batch(&self, batch: &Writebatch<BufferEntry>) -> Result<()> {
+        self.db.write(WriteOptions::new(), batch)
+            .map_err(|e| db_error!(write, IO => e))
+    }
+
+    /// Builds a `leveldb` `Writebatch` from `ops`, compressing each put's
+    /// value the same way [`Self::put`] would, and commits it in one call so
+    /// none of `ops` is left half-applied.
+    pub(crate) fn write_ops(&self, ops: Vec<bufdb_storage::WriteOp>) -> Result<()> {
+        if ops.is_empty() {
+            return Ok(());
+        }
+
+        let mut batch = Writebatch::new();
+        for op in ops {
+            match op {
+                bufdb_storage::WriteOp::Put(key, data) => {
+                    let data = bufdb_storage::compression::compress(self.compression, &data)?;
+                    let data = self.tag(data)?;
+                    batch.put(key, data.slice());
+                },
+                bufdb_storage::WriteOp::Delete(key) => batch.delete(key),
+            }
+        }
+
+        self.write_batch(&batch)
+    }
+
+    /// Purges entries whose [`Self::ttl`] has passed from `[from, to)` (the
+    /// whole database if both are `None`). A no-op if no `ttl` is
+    /// configured.
+    ///
+    /// RocksDB borrows this from a compaction filter installed at `Options`
+    /// build time, so expired entries are dropped for free as part of
+    /// LevelDB's own background compaction. The `leveldb` crate this
+    /// backend binds to doesn't expose that hook the way it exposes
+    /// [`crate::comparator`], so this instead walks the range itself and
+    /// deletes anything expired as one batch — callers that want the
+    /// RocksDB-like "compacts away in the background" behavior should call
+    /// this periodically (e.g. from the same daemon driving
+    /// `bufdb::table::TableImpl::maintain`) rather than relying on LevelDB
+    /// to do it unprompted.
+    pub fn compact(&self, from: Option<&BufferEntry>, to: Option<&BufferEntry>) -> Result<()> {
+        if self.ttl.is_none() {
+            return Ok(());
+        }
+
+        let mut iter = self.db.iter(ReadOptions::new());
+        if let Some(from) = from {
+            iter = iter.from(from);
+        }
+        if let Some(to) = to {
+            iter = iter.to(to);
+        }
+
+        let now = now();
+        let mut batch = Writebatch::new();
+        let mut any = false;
+        for (key, data) in iter {
+            let expires_at = BufferEntry::from(data).as_input().read_i64()?;
+            if expires_at <= now {
+                batch.delete(key);
+                any = true;
+            }
+        }
+
+        if any { self.write_batch(&batch) } else { Ok(()) }
     }
 
     pub fn iter<'a>(&'a self, options: ReadOptions<'a, BufferEntry>) -> Iterator<'a, BufferEntry> {
@@ -144,8 +335,8 @@ impl Debug for DBImpl {
 struct IndexListener<'a> {
     idb: Arc<DBImpl>,
     creator: Arc<dyn KeyCreator + 'a>,
-    on_put: fn (&Self, &BufferEntry, &BufferEntry) -> Result<()>,
-    on_delete: fn (&Self, &BufferEntry, &BufferEntry) -> Result<()>,
+    on_put_op: fn (&Self, &BufferEntry, &BufferEntry) -> Result<Option<bufdb_storage::WriteOp>>,
+    on_delete_op: fn (&Self, &BufferEntry, &BufferEntry) -> Result<Option<bufdb_storage::WriteOp>>,
 }
 
 impl <'a> IndexListener<'a> {
@@ -153,11 +344,11 @@ impl <'a> IndexListener<'a> {
         let unique = database.unique;
         let creator =  Arc::new(creator);
 
-        Self { 
-            idb: database, 
-            creator, 
-            on_put: if unique { Self::put_pk } else { Self::put_idx },
-            on_delete: if unique { Self::delete_pk } else { Self::delete_idx },
+        Self {
+            idb: database,
+            creator,
+            on_put_op: if unique { Self::put_pk_op } else { Self::put_idx_op },
+            on_delete_op: if unique { Self::delete_pk_op } else { Self::delete_idx_op },
         }
     }
 
@@ -175,7 +366,7 @@ impl <'a> IndexListener<'a> {
 
     fn init_pk(&self, pdb: &Arc<DBImpl>) -> Result<()> {
         for (key, data) in pdb.iter(read_options!(quick)) {
-            let data = BufferEntry::from(data);
+            let data = bufdb_storage::compression::decompress(&BufferEntry::from(data))?;
             if let Some(skey) = self.creator.create_key(&key, &data)? {
                 self.idb.put(&skey, &key)?;
             }
@@ -188,7 +379,7 @@ impl <'a> IndexListener<'a> {
         let mut id = 0u32;
 
         for (key, data) in pdb.iter(read_options!(quick)) {
-            let data = BufferEntry::from(data);
+            let data = bufdb_storage::compression::decompress(&BufferEntry::from(data))?;
             if let Some(skey) = self.creator.create_key(&key, &data)? {
                 id += 1;
                 let skey = append_suffix(skey, id)?;
@@ -200,19 +391,30 @@ impl <'a> IndexListener<'a> {
     }
 
     pub fn put(&self, key: &BufferEntry, data: &BufferEntry) -> Result<()> {
-        let put_fn = &self.on_put;
+        if let Some(op) = self.put_op(key, data)? {
+            self.apply(op)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Computes the op [`Self::put`] would apply, without applying it, so a
+    /// caller can fold it into a [`bufdb_storage::WriteBatch`] alongside a
+    /// sibling [`Self::delete_op`] for the same underlying key change.
+    fn put_op(&self, key: &BufferEntry, data: &BufferEntry) -> Result<Option<bufdb_storage::WriteOp>> {
+        let put_fn = &self.on_put_op;
         put_fn(self, key, data)
     }
 
-    fn put_pk(&self, key: &BufferEntry, data: &BufferEntry) -> Result<()> {
-        if let Some(ref skey) = self.creator.create_key(key, data)? {
-            self.idb.put(skey, key)
+    fn put_pk_op(&self, key: &BufferEntry, data: &BufferEntry) -> Result<Option<bufdb_storage::WriteOp>> {
+        if let Some(skey) = self.creator.create_key(key, data)? {
+            Ok(Some(bufdb_storage::WriteOp::Put(skey, key.clone())))
         } else {
-            Ok(())
+            Ok(None)
         }
     }
 
-    fn put_idx(&self, key: &BufferEntry, data: &BufferEntry) -> Result<()> {
+    fn put_idx_op(&self, key: &BufferEntry, data: &BufferEntry) -> Result<Option<bufdb_storage::WriteOp>> {
         if let Some(skey) = self.creator.create_key(key, data)? {
             let len = skey.size();
             let skey = append_suffix(skey, 0)?;
@@ -233,26 +435,36 @@ impl <'a> IndexListener<'a> {
             };
 
             let skey = reset_suffix(skey, order)?;
-            self.idb.put(&skey, key)
+            Ok(Some(bufdb_storage::WriteOp::Put(skey, key.clone())))
         } else {
-            Ok(())
+            Ok(None)
         }
     }
 
     pub fn delete(&self, key: &BufferEntry, data: &BufferEntry) -> Result<()> {
-        let del_fn = &self.on_delete;
+        if let Some(op) = self.delete_op(key, data)? {
+            self.apply(op)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Computes the op [`Self::delete`] would apply, without applying it. See
+    /// [`Self::put_op`].
+    fn delete_op(&self, key: &BufferEntry, data: &BufferEntry) -> Result<Option<bufdb_storage::WriteOp>> {
+        let del_fn = &self.on_delete_op;
         del_fn(self, key, data)
     }
 
-    fn delete_pk(&self, key: &BufferEntry, data: &BufferEntry) -> Result<()> {
+    fn delete_pk_op(&self, key: &BufferEntry, data: &BufferEntry) -> Result<Option<bufdb_storage::WriteOp>> {
         if let Some(skey) = self.creator.create_key(key, data)? {
-            self.idb.delete(&skey)
+            Ok(Some(bufdb_storage::WriteOp::Delete(skey)))
         } else {
-            Ok(())
+            Ok(None)
         }
     }
 
-    fn delete_idx(&self, key: &BufferEntry, data: &BufferEntry) -> Result<()> {
+    fn delete_idx_op(&self, key: &BufferEntry, data: &BufferEntry) -> Result<Option<bufdb_storage::WriteOp>> {
         if let Some(skey) = self.creator.create_key(key, data)? {
             let len = skey.size();
             let skey = append_suffix(skey, 0)?;
@@ -266,7 +478,7 @@ impl <'a> IndexListener<'a> {
                     break;
                 }
 
-                let n_data = BufferEntry::from(n_data);
+                let n_data = bufdb_storage::compression::decompress(&BufferEntry::from(n_data))?;
                 if *key == n_data {
                     found = Some(n_key);
                     break;
@@ -275,13 +487,16 @@ impl <'a> IndexListener<'a> {
                 order = n;
             }
 
-            if let Some(ref s_key) = found {
-                self.idb.delete(s_key)
-            } else {
-                Ok(())
-            }
+            Ok(found.map(bufdb_storage::WriteOp::Delete))
         } else {
-            Ok(())
+            Ok(None)
+        }
+    }
+
+    fn apply(&self, op: bufdb_storage::WriteOp) -> Result<()> {
+        match op {
+            bufdb_storage::WriteOp::Put(skey, data) => self.idb.put(&skey, &data),
+            bufdb_storage::WriteOp::Delete(skey) => self.idb.delete(&skey),
         }
     }
 }
@@ -308,8 +523,8 @@ macro_rules! lock_db {
 }
 
 impl <'a> PrimaryDatabase<'a> {
-    pub fn new<C: KeyComparator>(name: &str, dir: PathBuf, readonly: bool, temporary: bool, comparator: C) -> Result<Self> {
-        let database = DBImpl::new(name, dir, readonly, temporary, true, comparator)?;
+    pub fn new<C: KeyComparator>(name: &str, dir: PathBuf, readonly: bool, temporary: bool, comparator: C, merge_operator: Option<MergeOperator>, compression: ValueCompression, ttl: Option<Duration>) -> Result<Self> {
+        let database = DBImpl::new(name, dir, readonly, temporary, true, comparator, merge_operator, compression, ttl)?;
 
         Ok(Self { 
             database: Arc::new(database),
@@ -318,6 +533,10 @@ impl <'a> PrimaryDatabase<'a> {
     }
 
     fn register_listener<G: KeyCreator + 'a>(&self, idb: Arc<DBImpl>, creator: G) -> Result<()> {
+        if self.database.ttl.is_some() {
+            return Err(db_error_s!(write, Configuration => "cannot add a secondary index to a database with a TTL configured: expiring a primary entry on compaction would orphan its secondary entries"));
+        }
+
         let mut listeners = lock_db!(self => write);
 
         let listener = IndexListener::new(idb, creator);
@@ -327,9 +546,49 @@ impl <'a> PrimaryDatabase<'a> {
 
         Ok(())
     }
+
+    /// Returns an [`crate::asyncdb::AsyncDBImpl`] view of this database for use
+    /// from a tokio executor. Reads and writes still go through the same
+    /// underlying `leveldb` handle, just off the async task.
+    pub fn as_async(&self) -> crate::asyncdb::AsyncDBImpl {
+        crate::asyncdb::AsyncDBImpl::new(self.database.clone())
+    }
+
+    pub(crate) fn name(&self) -> &str {
+        self.database.name()
+    }
+
+    /// The underlying handle, so [`crate::env::LevelDBEnv`] can register it
+    /// for [`bufdb_storage::Environment::snapshot`] without keeping this
+    /// `PrimaryDatabase` itself alive.
+    pub(crate) fn handle(&self) -> Arc<DBImpl> {
+        self.database.clone()
+    }
+
+    /// Opens a point-in-time read transaction over just this database. See
+    /// [`crate::snapshot::DbSnapshot`]; use
+    /// [`bufdb_storage::Environment::snapshot`] instead for a view spanning
+    /// every database in the environment.
+    pub fn snapshot(&self) -> crate::snapshot::DbSnapshot<'a> {
+        crate::snapshot::DbSnapshot::new(self.database.clone())
+    }
+
+    /// Opens a cursor reading this database as it stood when `snapshot` was
+    /// taken, rather than the latest commit.
+    pub fn open_cursor_at(&'a self, snapshot: &'a crate::snapshot::DbSnapshot<'a>) -> Result<PKCursor<'a>> {
+        Ok(PKCursor::new_with_options(&self.database, snapshot.read_options(), Vec::new()))
+    }
+
+    /// Purges entries past their configured TTL from `[from, to)`. See
+    /// [`DBImpl::compact`]; a no-op if this database has no TTL configured.
+    pub fn compact(&self, from: Option<&BufferEntry>, to: Option<&BufferEntry>) -> Result<()> {
+        self.database.compact(from, to)
+    }
 }
 
 impl <'a> bufdb_storage::Database<'a, PKCursor<'a>> for PrimaryDatabase<'a> {
+    type TRANSACTION = crate::transaction::LevelTransaction<'a>;
+
     fn count(&self) -> bufdb_lib::error::Result<usize> {
         self.database.count()
     }
@@ -337,22 +596,29 @@ impl <'a> bufdb_storage::Database<'a, PKCursor<'a>> for PrimaryDatabase<'a> {
     fn put(&self, key: &bufdb_storage::entry::BufferEntry, data: &bufdb_storage::entry::BufferEntry) -> bufdb_lib::error::Result<()> {
         let listeners = lock_db!(self);
 
-        if !listeners.is_empty() {
-            if let Some(raw_data) = self.database.get(key)? {
-                if data != &raw_data {
-                    for listener in listeners.iter() {
-                        listener.delete(key, &raw_data)?;
-                    }
-                }
-            }
-        }
+        let raw_data = if listeners.is_empty() { None } else { self.database.get(key)? };
 
         self.database.put(key, data)?;
 
-        if !listeners.is_empty() {
-            for listener in listeners.iter() {
-                listener.put(key, data)?;
+        // Each listener's stale-entry delete and fresh-entry put target the
+        // same secondary `idb`, so fold them into one `write_ops` commit
+        // instead of two separate writes that could leave the index half
+        // updated if the process died in between.
+        for listener in listeners.iter() {
+            let mut ops = Vec::new();
+
+            if let Some(ref raw_data) = raw_data {
+                if data != raw_data {
+                    if let Some(op) = listener.delete_op(key, raw_data)? {
+                        ops.push(op);
+                    }
+                }
             }
+            if let Some(op) = listener.put_op(key, data)? {
+                ops.push(op);
+            }
+
+            listener.idb.write_ops(ops)?;
         }
 
         Ok(())
@@ -397,6 +663,64 @@ impl <'a> bufdb_storage::Database<'a, PKCursor<'a>> for PrimaryDatabase<'a> {
     fn open_cursor(&'a self) -> bufdb_lib::error::Result<PKCursor<'a>> {
         Ok(PKCursor::new(&self.database))
     }
+
+    /// Rejects opening a transaction while this database has secondary
+    /// indexes registered: [`LevelTransaction::commit`] flushes its staged
+    /// ops straight to the primary `DBImpl`, with no fan-out to
+    /// [`IndexListener`] (unlike [`Self::put`]/[`Self::delete`]/[`Self::merge`]
+    /// above), which would silently desync every secondary index on this
+    /// table. Mirrors the TTL-vs-index guard in [`Self::register_listener`].
+    fn begin_transaction(&'a self) -> bufdb_lib::error::Result<Self::TRANSACTION> {
+        if !lock_db!(self).is_empty() {
+            return Err(db_error_s!(write, Configuration => "cannot open a transaction on a database with secondary indexes: transaction commit does not yet fan writes out to them"));
+        }
+
+        Ok(crate::transaction::LevelTransaction::new(&self.database))
+    }
+
+    fn merge(&self, key: &BufferEntry, operand: &BufferEntry) -> Result<()> {
+        let listeners = lock_db!(self);
+
+        if listeners.is_empty() {
+            self.database.merge(key, operand)
+        } else {
+            let raw_data = self.database.get(key)?;
+            self.database.merge(key, operand)?;
+            let merged = self.database.get(key)?;
+
+            if raw_data != merged {
+                for listener in listeners.iter() {
+                    let mut ops = Vec::new();
+
+                    if let Some(ref raw_data) = raw_data {
+                        if let Some(op) = listener.delete_op(key, raw_data)? {
+                            ops.push(op);
+                        }
+                    }
+                    if let Some(ref merged) = merged {
+                        if let Some(op) = listener.put_op(key, merged)? {
+                            ops.push(op);
+                        }
+                    }
+
+                    listener.idb.write_ops(ops)?;
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    // No `write_batch` override here (unlike `SecondaryDatabase` below): the
+    // default implementation issues each op through `Self::put`/`Self::delete`,
+    // which is what keeps registered secondary indexes fanned out and in
+    // sync. It trades the single-`Writebatch` atomicity a raw `write_ops`
+    // call would give the primary keys for that correctness; see
+    // `put`/`delete`/`merge` above for the same trade-off.
+
+    fn compact(&self, from: Option<&BufferEntry>, to: Option<&BufferEntry>) -> Result<()> {
+        self.database.compact(from, to)
+    }
 }
 
 #[derive(Debug)]
@@ -413,17 +737,44 @@ impl <'a> SecondaryDatabase<'a> {
         let mut dir = parent.dir.clone();
         dir.push(name);
 
-        let db = DBImpl::new(name, dir, parent.readonly, config.temporary || parent.temporary, config.unique, config.comparator)?;
+        let db = DBImpl::new(name, dir, parent.readonly, config.temporary || parent.temporary, config.unique, config.comparator, config.merge_operator, config.compression, None)?;
         let database = Arc::new(db);
 
         p_database.register_listener(database.clone(), config.creator)?;
 
-        Ok(Self { 
-            database, 
-            parent, 
-            listeners: p_database.listeners.clone() 
+        Ok(Self {
+            database,
+            parent,
+            listeners: p_database.listeners.clone()
         })
     }
+
+    pub(crate) fn name(&self) -> &str {
+        self.database.name()
+    }
+
+    pub(crate) fn parent_name(&self) -> &str {
+        self.parent.name()
+    }
+
+    /// The underlying handle, so [`crate::env::LevelDBEnv`] can register it
+    /// for [`bufdb_storage::Environment::snapshot`] alongside the primary
+    /// databases it indexes.
+    pub(crate) fn handle(&self) -> Arc<DBImpl> {
+        self.database.clone()
+    }
+
+    /// Opens a point-in-time read transaction over just this index. See
+    /// [`PrimaryDatabase::snapshot`].
+    pub fn snapshot(&self) -> crate::snapshot::DbSnapshot<'a> {
+        crate::snapshot::DbSnapshot::new(self.database.clone())
+    }
+
+    /// Opens a secondary cursor reading this index as it stood when
+    /// `snapshot` was taken, rather than the latest commit.
+    pub fn open_cursor_at(&'a self, snapshot: &'a crate::snapshot::DbSnapshot<'a>) -> Result<IDXCursor<'a>> {
+        Ok(IDXCursor::new_with_options(&self.parent, &self.database, snapshot.read_options()))
+    }
 }
 
 impl <'a> Drop for SecondaryDatabase<'a> {
@@ -434,6 +785,8 @@ impl <'a> Drop for SecondaryDatabase<'a> {
 }
 
 impl <'a> bufdb_storage::Database<'a, IDXCursor<'a>> for SecondaryDatabase<'a> {
+    type TRANSACTION = crate::transaction::LevelTransaction<'a>;
+
     fn count(&self) -> bufdb_lib::error::Result<usize> {
         self.database.count()
     }
@@ -463,6 +816,18 @@ impl <'a> bufdb_storage::Database<'a, IDXCursor<'a>> for SecondaryDatabase<'a> {
     fn open_cursor(&self) -> bufdb_lib::error::Result<IDXCursor> {
         Ok(IDXCursor::new(&self.parent, &self.database))
     }
+
+    fn merge(&self, key: &BufferEntry, operand: &BufferEntry) -> Result<()> {
+        self.database.merge(key, operand)
+    }
+
+    fn write_batch(&self, batch: bufdb_storage::WriteBatch) -> Result<()> {
+        self.database.write_ops(batch.into_ops())
+    }
+
+    fn begin_transaction(&'a self) -> bufdb_lib::error::Result<Self::TRANSACTION> {
+        Ok(crate::transaction::LevelTransaction::new_secondary(&self.database, &self.parent))
+    }
 }
 
 #[cfg(test)]
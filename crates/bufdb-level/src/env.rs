@@ -1,9 +1,15 @@
+use std::collections::HashMap;
+use std::fs::create_dir_all;
+use std::fs::read_dir;
 use std::fs::remove_dir_all;
 use std::fs::rename;
 use std::path::Path;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::RwLock;
 
 use bufdb_api::error::Result;
+use bufdb_lib::db_error_s;
 use bufdb_storage::DatabaseConfig;
 use bufdb_storage::Environment;
 use bufdb_storage::EnvironmentConfig;
@@ -13,13 +19,21 @@ use bufdb_storage::SDatabaseConfig;
 
 use crate::cursor::IDXCursor;
 use crate::cursor::PKCursor;
+use crate::database::DBImpl;
 use crate::database::PrimaryDatabase;
 use crate::database::SecondaryDatabase;
+use crate::snapshot::LevelEnvSnapshot;
 
 pub struct LevelDBEnv {
     dir: PathBuf,
     readonly: bool,
     temporary: bool,
+    /// Every database opened through this environment, by name, so
+    /// [`Environment::snapshot`] can take a consistent read view across all
+    /// of them at once. Unlike [`crate::database::PrimaryDatabase`]'s own
+    /// `Arc<DBImpl>`, this keeps the handle alive for the life of the
+    /// environment, independent of the caller's database handles.
+    databases: RwLock<HashMap<String, Arc<DBImpl>>>,
 }
 
 impl LevelDBEnv {
@@ -46,6 +60,34 @@ impl LevelDBEnv {
         remove_dir_all(dir)?;
         Ok(())
     }
+
+    fn copy_database(&self, name: &str, target: &Path) -> Result<()> {
+        let src = self.get_data_dir(name);
+        let dst = {
+            let mut dst = target.to_path_buf();
+            dst.push(name);
+            dst
+        };
+
+        copy_dir_all(&src, &dst)
+    }
+}
+
+fn copy_dir_all(src: &Path, dst: &Path) -> Result<()> {
+    create_dir_all(dst)?;
+
+    for entry in read_dir(src)? {
+        let entry = entry?;
+        let dst = dst.join(entry.file_name());
+
+        if entry.file_type()?.is_dir() {
+            copy_dir_all(&entry.path(), &dst)?;
+        } else {
+            std::fs::copy(entry.path(), dst)?;
+        }
+    }
+
+    Ok(())
 }
 
 impl <'a> Environment<'a> for LevelDBEnv {
@@ -53,30 +95,44 @@ impl <'a> Environment<'a> for LevelDBEnv {
     type SCUROSR = IDXCursor<'a>;
     type DATABASE = PrimaryDatabase<'a>;
     type SDATABASE = SecondaryDatabase<'a>;
+    type SNAPSHOT = LevelEnvSnapshot<'a>;
 
     fn new(config: EnvironmentConfig) -> Result<Self> {
+        crate::version::check_version(&config.dir)?;
+
         Ok(Self {
             dir: config.dir,
             readonly: config.readonly,
             temporary: config.temporary,
+            databases: RwLock::new(HashMap::new()),
         })
     }
 
     fn create_database<C: KeyComparator>(&self, name: &str, config: DatabaseConfig<C>) -> bufdb_api::error::Result<Self::DATABASE> {
         let data_dir = self.get_data_dir(name);
 
-        PrimaryDatabase::new(name, data_dir, config.readonly, config.temporary, config.comparator)
+        let database = PrimaryDatabase::new(name, data_dir, config.readonly, config.temporary, config.comparator, config.merge_operator, config.compression, config.ttl)?;
+
+        self.databases.write().unwrap().insert(name.into(), database.handle());
+
+        Ok(database)
     }
 
     fn create_secondary_database<C: KeyComparator, G: KeyCreator + 'a>(&self, database: &Self::DATABASE, name: &str, config: SDatabaseConfig<C, G>) -> bufdb_api::error::Result<Self::SDATABASE> {
-        SecondaryDatabase::new(database, name, config)
+        let sdatabase = SecondaryDatabase::new(database, name, config)?;
+
+        self.databases.write().unwrap().insert(name.into(), sdatabase.handle());
+
+        Ok(sdatabase)
     }
 
     fn drop_database(&self, name: &str) -> bufdb_api::error::Result<()> {
+        self.databases.write().unwrap().remove(name);
         self.clear_database(name)
     }
 
     fn drop_secondary_database(&self, name: &str) -> bufdb_api::error::Result<()> {
+        self.databases.write().unwrap().remove(name);
         self.clear_database(name)
     }
 
@@ -88,6 +144,36 @@ impl <'a> Environment<'a> for LevelDBEnv {
         let raw_dir = self.get_data_dir(raw_name);
         let new_dir = self.get_data_dir(new_name);
         rename(raw_dir, new_dir)?;
+
+        let mut databases = self.databases.write().unwrap();
+        if let Some(database) = databases.remove(raw_name) {
+            databases.insert(new_name.into(), database);
+        }
+
+        Ok(())
+    }
+
+    fn snapshot(&'a self) -> Result<Self::SNAPSHOT> {
+        let databases = self.databases.read().unwrap().clone();
+        Ok(LevelEnvSnapshot::new(databases))
+    }
+
+    fn checkpoint(&self, target: &Path) -> Result<()> {
+        if target.exists() {
+            return Err(db_error_s!(write, CreateDuplicate => "checkpoint target already exists"));
+        }
+
+        create_dir_all(target)?;
+
+        for name in self.databases.read().unwrap().keys() {
+            // A secondary database lives under its primary's directory and is
+            // copied along with it; it has no directory of its own here.
+            if !self.get_data_dir(name).exists() {
+                continue;
+            }
+            self.copy_database(name, target)?;
+        }
+
         Ok(())
     }
 }
\ No newline at end of file
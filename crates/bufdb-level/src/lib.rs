@@ -8,9 +8,13 @@ use env::LevelDBEnv;
 pub mod env;
 #[macro_use]
 pub mod database;
+pub mod asyncdb;
 pub mod cursor;
+pub mod snapshot;
 pub(crate) mod comparator;
 pub(crate) mod suffix;
+pub(crate) mod transaction;
+pub(crate) mod version;
 
 #[derive(Debug, Clone, Copy)]
 pub struct LevelDBEngine {}
@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use bufdb_lib::error::Result;
+use bufdb_storage::entry::BufferEntry;
+use leveldb::options::ReadOptions;
+use leveldb::snapshots::Snapshot as LevelSnapshot;
+
+use crate::cursor::IDXCursor;
+use crate::cursor::PKCursor;
+use crate::database::DBImpl;
+use crate::database::PrimaryDatabase;
+use crate::database::SecondaryDatabase;
+use crate::env::LevelDBEnv;
+
+/// A point-in-time read view over every database open in a [`LevelDBEnv`],
+/// taken by [`bufdb_storage::Environment::snapshot`].
+///
+/// Every database's `leveldb` snapshot is taken once, up front, and reused
+/// for every cursor opened against it, so two cursors opened from the same
+/// `LevelEnvSnapshot` — even against different databases — always read as
+/// of the same instant rather than each picking up the latest commit.
+///
+/// A secondary cursor opened from it reads index entries as of the
+/// snapshot, but [`IDXCursor`] fetches each matching primary row through
+/// `DBImpl::get`, at the latest commit; a row deleted since the snapshot
+/// makes that fetch fail with an "Index mismatch" error.
+pub struct LevelEnvSnapshot<'a> {
+    // Kept alongside the snapshots borrowed from them below so the
+    // underlying `leveldb` handles outlive those borrows for as long as
+    // `self` does.
+    databases: HashMap<String, Arc<DBImpl>>,
+    snapshots: HashMap<String, LevelSnapshot<'a, BufferEntry>>,
+}
+
+impl <'a> LevelEnvSnapshot<'a> {
+    pub(crate) fn new(databases: HashMap<String, Arc<DBImpl>>) -> Self {
+        let snapshots = databases.iter()
+            .map(|(name, db)| {
+                // SAFETY: `db`'s `Arc<DBImpl>` is held in `databases` for
+                // exactly as long as `self`, so the snapshot it borrows from
+                // never outlives its data even once re-tied to `'a`.
+                let snapshot: LevelSnapshot<'a, BufferEntry> = unsafe {
+                    std::mem::transmute(db.snapshot())
+                };
+                (name.clone(), snapshot)
+            })
+            .collect();
+
+        Self { databases, snapshots }
+    }
+
+    fn read_options(&'a self, name: &str) -> ReadOptions<'a, BufferEntry> {
+        let mut options = ReadOptions::new();
+        options.snapshot = self.snapshots.get(name);
+        options
+    }
+}
+
+/// A point-in-time read transaction over a single database, obtained via
+/// [`PrimaryDatabase::snapshot`]/[`SecondaryDatabase::snapshot`].
+///
+/// Modeled on an LMDB read transaction: the `leveldb` snapshot is pinned for
+/// as long as this handle is alive and released (via `leveldb`'s own `Drop`
+/// impl) once it is dropped, and every cursor, `count`, or `is_empty` call
+/// made through it reads the same sequence number rather than the latest
+/// commit. Unlike [`LevelEnvSnapshot`], this only covers one database.
+pub struct DbSnapshot<'a> {
+    // Kept alongside `snapshot` for the same reason as `LevelEnvSnapshot`'s
+    // `databases` field: it must outlive the borrow `snapshot` holds on it.
+    db: Arc<DBImpl>,
+    snapshot: LevelSnapshot<'a, BufferEntry>,
+}
+
+impl <'a> DbSnapshot<'a> {
+    pub(crate) fn new(db: Arc<DBImpl>) -> Self {
+        // SAFETY: see `LevelEnvSnapshot::new` — `db` is held in this struct
+        // for exactly as long as `snapshot` borrows from it.
+        let snapshot: LevelSnapshot<'a, BufferEntry> = unsafe { std::mem::transmute(db.snapshot()) };
+        Self { db, snapshot }
+    }
+
+    pub(crate) fn read_options(&'a self) -> ReadOptions<'a, BufferEntry> {
+        let mut options = ReadOptions::new();
+        options.snapshot = Some(&self.snapshot);
+        options
+    }
+
+    /// Counts this database's entries as of when this snapshot was taken.
+    pub fn count(&'a self) -> Result<usize> {
+        Ok(self.db.iter(self.read_options()).count())
+    }
+
+    /// Whether this database held no entries as of when this snapshot was
+    /// taken.
+    pub fn is_empty(&'a self) -> Result<bool> {
+        Ok(self.db.iter(self.read_options()).next().is_none())
+    }
+}
+
+impl <'a> bufdb_storage::Snapshot<'a, LevelDBEnv> for LevelEnvSnapshot<'a> {
+    fn open_cursor(&'a self, database: &'a PrimaryDatabase<'a>) -> Result<PKCursor<'a>> {
+        let db = self.databases.get(database.name())
+            .unwrap_or_else(|| panic!("database '{}' was opened after this snapshot was taken", database.name()));
+
+        Ok(PKCursor::new_with_options(db, self.read_options(database.name()), Vec::new()))
+    }
+
+    fn open_secondary_cursor(&'a self, database: &'a SecondaryDatabase<'a>) -> Result<IDXCursor<'a>> {
+        let pdb = self.databases.get(database.parent_name())
+            .unwrap_or_else(|| panic!("database '{}' was opened after this snapshot was taken", database.parent_name()));
+        let idb = self.databases.get(database.name())
+            .unwrap_or_else(|| panic!("database '{}' was opened after this snapshot was taken", database.name()));
+
+        Ok(IDXCursor::new_with_options(pdb, idb, self.read_options(database.name())))
+    }
+}
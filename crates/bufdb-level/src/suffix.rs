@@ -2,7 +2,6 @@ use bufdb_api::error::ErrorKind;
 use bufdb_api::error::Result;
 use bufdb_storage::entry::BufferEntry;
 use bufdb_storage::entry::Entry;
-use bufdb_storage::entry::SliceEntry;
 use bufdb_storage::io::BufferOutput;
 use bufdb_storage::io::Output;
 
@@ -65,7 +64,7 @@ pub fn size_of_suffix<T: Entry>(entry: &T) -> usize {
     }
 }
 
-pub fn unwrap_suffix(buf: &BufferEntry) -> Result<(SliceEntry, u32)> {
+pub fn unwrap_suffix(buf: &BufferEntry) -> Result<(BufferEntry, u32)> {
     let mut iter = buf.slice().iter().rev();
 
     let sign = if let Some(&n) = iter.next() {
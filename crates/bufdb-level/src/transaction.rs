@@ -0,0 +1,231 @@
+//! Buffered read-write transactions over a [`DBImpl`].
+//!
+//! Writes are kept in an in-memory staging map until [`Transaction::commit`]
+//! flushes them through a single LevelDB `Writebatch`, so a failed commit
+//! never leaves the database partially updated. Savepoints are tracked as
+//! marks into the ordered mutation log rather than per-key undo records, so
+//! `rollback_to_savepoint` simply truncates the log and replays it.
+//!
+//! `commit` writes straight to the primary `DBImpl` with no secondary-index
+//! fan-out, unlike `PrimaryDatabase::put`/`delete`/`merge`; `PrimaryDatabase::
+//! begin_transaction` refuses to hand out a `LevelTransaction` while any
+//! secondary index is registered, rather than letting it silently desync.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::sync::RwLock;
+
+use bufdb_lib::error::ErrorKind;
+use bufdb_lib::error::Result;
+use bufdb_storage::Transaction;
+use bufdb_storage::entry::BufferEntry;
+use bufdb_storage::entry::Entry;
+
+use crate::cursor::IDXCursor;
+use crate::cursor::PKCursor;
+use crate::database::DBImpl;
+
+/// A single staged mutation: `None` stages a delete, `Some` stages a put.
+pub(crate) type StagedOp = (BufferEntry, Option<Vec<u8>>);
+
+struct TxState {
+    /// Every staged mutation in the order it was applied, used to replay the
+    /// staging map after a `rollback_to_savepoint`.
+    log: Vec<StagedOp>,
+    /// The merged, de-duplicated view of `log`, used for fast lookups and to
+    /// hand cursors a consistent snapshot.
+    staging: BTreeMap<BufferEntry, Option<Vec<u8>>>,
+    /// Log lengths recorded by `set_savepoint`, in the order they were taken.
+    savepoints: Vec<usize>,
+}
+
+impl TxState {
+    fn new() -> Self {
+        Self {
+            log: Vec::new(),
+            staging: BTreeMap::new(),
+            savepoints: Vec::new(),
+        }
+    }
+
+    fn stage(&mut self, key: BufferEntry, value: Option<Vec<u8>>) {
+        self.staging.insert(key.clone(), value.clone());
+        self.log.push((key, value));
+    }
+
+    fn rebuild_from_log(&mut self) {
+        self.staging.clear();
+        for (key, value) in self.log.iter() {
+            self.staging.insert(key.clone(), value.clone());
+        }
+    }
+}
+
+/// A buffered read-write [`Transaction`] over a primary or secondary
+/// [`DBImpl`]. `parent` is only set for a transaction opened over a secondary
+/// database, where it is needed to resolve [`IDXCursor`]'s primary lookups.
+pub struct LevelTransaction<'a> {
+    db: &'a Arc<DBImpl>,
+    parent: Option<&'a Arc<DBImpl>>,
+    state: RwLock<TxState>,
+}
+
+impl <'a> LevelTransaction<'a> {
+    pub(crate) fn new(db: &'a Arc<DBImpl>) -> Self {
+        Self { db, parent: None, state: RwLock::new(TxState::new()) }
+    }
+
+    pub(crate) fn new_secondary(db: &'a Arc<DBImpl>, parent: &'a Arc<DBImpl>) -> Self {
+        Self { db, parent: Some(parent), state: RwLock::new(TxState::new()) }
+    }
+
+    fn stage_put(&self, key: &BufferEntry, data: &BufferEntry) -> Result<()> {
+        let mut state = self.state.write().unwrap();
+        state.stage(key.clone(), Some(data.slice().to_vec()));
+        Ok(())
+    }
+
+    fn stage_delete(&self, key: &BufferEntry) -> Result<()> {
+        let mut state = self.state.write().unwrap();
+        state.stage(key.clone(), None);
+        Ok(())
+    }
+
+    fn staged_get(&self, key: &BufferEntry) -> Result<Option<BufferEntry>> {
+        {
+            let state = self.state.read().unwrap();
+            if let Some(staged) = state.staging.get(key) {
+                return Ok(staged.clone().map(BufferEntry::from));
+            }
+        }
+
+        self.db.get(key)
+    }
+
+    /// Snapshot of the staged mutations, sorted ascending by key, for handing
+    /// to a cursor so it can merge them on top of the committed data.
+    fn snapshot(&self) -> Vec<StagedOp> {
+        let state = self.state.read().unwrap();
+        state.staging.iter().map(|(k, v)| (k.clone(), v.clone())).collect()
+    }
+
+    fn flush(self) -> Result<()> {
+        let state = self.state.into_inner().unwrap();
+
+        let ops = state.staging.into_iter()
+            .map(|(key, value)| match value {
+                Some(data) => bufdb_storage::WriteOp::Put(key, BufferEntry::from(data)),
+                None => bufdb_storage::WriteOp::Delete(key),
+            })
+            .collect();
+
+        self.db.write_ops(ops)
+    }
+
+    fn mark_savepoint(&self) -> Result<usize> {
+        let mut state = self.state.write().unwrap();
+        let mark = state.log.len();
+        state.savepoints.push(mark);
+        Ok(mark)
+    }
+
+    fn undo_to(&self, savepoint: usize) -> Result<()> {
+        let mut state = self.state.write().unwrap();
+        if savepoint > state.log.len() {
+            return Err(ErrorKind::NotFound.into());
+        }
+
+        state.log.truncate(savepoint);
+        state.savepoints.retain(|&mark| mark < savepoint);
+        state.rebuild_from_log();
+
+        Ok(())
+    }
+
+    fn release_savepoint(&self, savepoint: usize) -> Result<()> {
+        let mut state = self.state.write().unwrap();
+        if let Some(pos) = state.savepoints.iter().rposition(|&mark| mark == savepoint) {
+            state.savepoints.remove(pos);
+            Ok(())
+        } else {
+            Err(ErrorKind::NotFound.into())
+        }
+    }
+}
+
+impl <'a> Transaction<'a, PKCursor<'a>> for LevelTransaction<'a> {
+    fn put(&self, key: &BufferEntry, data: &BufferEntry) -> Result<()> {
+        self.stage_put(key, data)
+    }
+
+    fn get(&self, key: &BufferEntry) -> Result<Option<BufferEntry>> {
+        self.staged_get(key)
+    }
+
+    fn delete(&self, key: &BufferEntry) -> Result<()> {
+        self.stage_delete(key)
+    }
+
+    fn open_cursor(&'a self) -> Result<PKCursor<'a>> {
+        Ok(PKCursor::new_staged(self.db, self.snapshot()))
+    }
+
+    fn commit(self) -> Result<()> {
+        self.flush()
+    }
+
+    fn rollback(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_savepoint(&self) -> Result<usize> {
+        self.mark_savepoint()
+    }
+
+    fn rollback_to_savepoint(&self, savepoint: usize) -> Result<()> {
+        self.undo_to(savepoint)
+    }
+
+    fn pop_savepoint(&self, savepoint: usize) -> Result<()> {
+        self.release_savepoint(savepoint)
+    }
+}
+
+impl <'a> Transaction<'a, IDXCursor<'a>> for LevelTransaction<'a> {
+    fn put(&self, key: &BufferEntry, data: &BufferEntry) -> Result<()> {
+        self.stage_put(key, data)
+    }
+
+    fn get(&self, key: &BufferEntry) -> Result<Option<BufferEntry>> {
+        self.staged_get(key)
+    }
+
+    fn delete(&self, key: &BufferEntry) -> Result<()> {
+        self.stage_delete(key)
+    }
+
+    fn open_cursor(&'a self) -> Result<IDXCursor<'a>> {
+        let parent = self.parent.expect("secondary transaction is missing its parent database");
+        Ok(IDXCursor::new(parent, self.db))
+    }
+
+    fn commit(self) -> Result<()> {
+        self.flush()
+    }
+
+    fn rollback(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_savepoint(&self) -> Result<usize> {
+        self.mark_savepoint()
+    }
+
+    fn rollback_to_savepoint(&self, savepoint: usize) -> Result<()> {
+        self.undo_to(savepoint)
+    }
+
+    fn pop_savepoint(&self, savepoint: usize) -> Result<()> {
+        self.release_savepoint(savepoint)
+    }
+}
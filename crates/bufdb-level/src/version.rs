@@ -0,0 +1,102 @@
+//! On-disk format version header.
+//!
+//! The serialized layout used by this engine (suffix scheme, packed-int
+//! scheme) is not guaranteed to stay byte-compatible forever. [`EngineVersion`]
+//! is written to a reserved file in the environment directory the first time
+//! it is opened, and is checked against [`EngineVersion::CURRENT`] on every
+//! later open so that an incompatible on-disk layout is rejected with
+//! [`ErrorKind::IncompatibleVersion`] instead of silently returning garbage.
+
+use std::fs;
+use std::path::Path;
+
+use bufdb_api::error::ErrorKind;
+use bufdb_api::error::Result;
+use bufdb_storage::entry::BufferEntry;
+use bufdb_storage::entry::Entry;
+use bufdb_storage::io::Input;
+use bufdb_storage::io::Inputable;
+use bufdb_storage::io::Output;
+use bufdb_storage::io::Outputable;
+
+/// Name of the reserved metadata file holding the [`EngineVersion`] header.
+const VERSION_FILE: &str = "ENGINE_VERSION";
+
+/// Identifies the on-disk format written by this engine.
+///
+/// `storage_version` packs a major version in the high byte and a minor
+/// version in the low byte, so two versions with the same major byte are
+/// considered readable by the same code even if the minor byte differs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct EngineVersion {
+    format_name: String,
+    storage_version: u16,
+    codec_version: u16,
+}
+
+impl EngineVersion {
+    /// The version written by this build of the engine.
+    pub(crate) fn current() -> Self {
+        Self {
+            format_name: "bufdb-level".into(),
+            storage_version: 0x0100,
+            codec_version: 1,
+        }
+    }
+
+    fn major(&self) -> u8 {
+        (self.storage_version >> 8) as u8
+    }
+
+    /// Decides whether a database written with `self` can be opened by the
+    /// code that produced `current`.
+    ///
+    /// Matching major versions are accepted regardless of minor version,
+    /// since minor bumps are required to stay backward readable. A
+    /// mismatched major version (in either direction) is refused rather than
+    /// guessed at.
+    pub(crate) fn supports(&self, current: &EngineVersion) -> bool {
+        self.format_name == current.format_name && self.major() == current.major()
+    }
+}
+
+impl Outputable for EngineVersion {
+    fn write_to<W: Output>(&self, writer: &mut W) -> std::io::Result<()> {
+        writer.write_string(Some(&self.format_name))?;
+        writer.write_u16(self.storage_version)?;
+        writer.write_u16(self.codec_version)
+    }
+}
+
+impl Inputable for EngineVersion {
+    fn read_from<R: Input>(reader: &mut R) -> std::io::Result<Self> {
+        let format_name = reader.read_string()?.unwrap_or_default();
+        let storage_version = reader.read_u16()?;
+        let codec_version = reader.read_u16()?;
+
+        Ok(Self { format_name, storage_version, codec_version })
+    }
+}
+
+/// Writes [`EngineVersion::current`] to `dir` on first open, or validates the
+/// version already stored there against it.
+pub(crate) fn check_version(dir: &Path) -> Result<()> {
+    let path = dir.join(VERSION_FILE);
+    let current = EngineVersion::current();
+
+    if path.exists() {
+        let data = fs::read(&path)?;
+        let entry = BufferEntry::from(data);
+        let stored = EngineVersion::read_from(&mut entry.as_input())?;
+
+        if !stored.supports(&current) {
+            return Err(ErrorKind::IncompatibleVersion.into());
+        }
+    } else {
+        fs::create_dir_all(dir)?;
+        let entry = current.to_entry()?;
+        fs::write(&path, entry.slice())?;
+    }
+
+    Ok(())
+}
@@ -29,6 +29,8 @@ pub enum ErrorKind {
     TooManyFiles,
     #[fail(display = "Object is already closed")]
     AlreadyClosed,
+    #[fail(display = "Incompatible on-disk format version")]
+    IncompatibleVersion,
     #[fail(display = "Format error")]
     Format(#[cause] std::fmt::Error),
     #[fail(display = "Parse float error")]
@@ -55,6 +57,42 @@ pub enum ErrorKind {
     DBOther(#[cause] PhantomError),
 }
 
+/// Stable, exhaustively-matchable classification of an [`Error`], decoupled
+/// from [`ErrorKind`]'s variant shape so downstream code (and any future
+/// wire protocol) can branch on failure category without matching on
+/// payload types that may grow a `#[cause]`. The `DB*` kinds carry their own
+/// code (see [`PhantomError::code`]); every other kind maps to a fixed one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ErrorCode {
+    #[default]
+    Unknown,
+    NotFound,
+    DataType,
+    OutOfBounds,
+    NullValue,
+    UndefinedExpr,
+    Configuration,
+    CloseUsing,
+    CreateDuplicate,
+    TooManyFiles,
+    AlreadyClosed,
+    IncompatibleVersion,
+    Format,
+    ParseFloat,
+    ParseInt,
+    ParseBool,
+    ParseDateTime,
+    IO,
+    JSON,
+    /// The stored or transmitted bytes don't decode to what they should.
+    Corruption,
+    /// A lock (file lock, row lock, merge lock) is held elsewhere.
+    LockContention,
+    /// The backend doesn't support the requested operation at all.
+    Unsupported,
+    Other,
+}
+
 /// Defines error type for bufdb lib.
 #[derive(Debug)]
 pub struct Error {
@@ -95,6 +133,34 @@ impl Error {
     pub fn kind(&self) -> &ErrorKind {
         self.inner.get_context()
     }
+
+    /// This error's stable [`ErrorCode`]. For the `DB*` kinds this is
+    /// whatever code the failing backend attached (see [`db_error!`]);
+    /// every other kind maps to a fixed code.
+    pub fn code(&self) -> ErrorCode {
+        match self.kind() {
+            ErrorKind::Unknown => ErrorCode::Unknown,
+            ErrorKind::NotFound => ErrorCode::NotFound,
+            ErrorKind::DataType => ErrorCode::DataType,
+            ErrorKind::OutOfBounds => ErrorCode::OutOfBounds,
+            ErrorKind::NullValue => ErrorCode::NullValue,
+            ErrorKind::UndefinedExpr => ErrorCode::UndefinedExpr,
+            ErrorKind::Configuration => ErrorCode::Configuration,
+            ErrorKind::CloseUsing => ErrorCode::CloseUsing,
+            ErrorKind::CreateDuplicate => ErrorCode::CreateDuplicate,
+            ErrorKind::TooManyFiles => ErrorCode::TooManyFiles,
+            ErrorKind::AlreadyClosed => ErrorCode::AlreadyClosed,
+            ErrorKind::IncompatibleVersion => ErrorCode::IncompatibleVersion,
+            ErrorKind::Format(_) => ErrorCode::Format,
+            ErrorKind::ParseFloat(_) => ErrorCode::ParseFloat,
+            ErrorKind::ParseInt(_) => ErrorCode::ParseInt,
+            ErrorKind::ParseBool(_) => ErrorCode::ParseBool,
+            ErrorKind::ParseDateTime(_) => ErrorCode::ParseDateTime,
+            ErrorKind::IO(_) => ErrorCode::IO,
+            ErrorKind::JSON(_) => ErrorCode::JSON,
+            ErrorKind::DBOpen(p) | ErrorKind::DBRead(p) | ErrorKind::DBWrite(p) | ErrorKind::DBClose(p) | ErrorKind::DBOther(p) => p.code(),
+        }
+    }
 }
 
 impl From<ErrorKind> for Error {
@@ -147,24 +213,51 @@ impl From<serde_json::Error> for Error {
     }
 }
 
-#[derive(Debug, Default, Clone, PartialEq, Eq)]
+/// Carries a backend failure into a `DB*` [`ErrorKind`] without flattening
+/// it to a string: a stable [`ErrorCode`] callers can match on, the
+/// backend's message, and — when the backend error was given to us, rather
+/// than just a literal — the original error kept alive as a [`Fail`] cause,
+/// so [`Fail::cause`] still walks into it.
+#[derive(Debug, Default)]
 pub struct PhantomError {
-    message: Option<String>
+    code: ErrorCode,
+    message: Option<String>,
+    cause: Option<Box<dyn Fail>>,
 }
 
 impl PhantomError {
-    pub fn from<T: std::error::Error>(err: T) -> Self {
+    pub fn from<T: Fail>(err: T) -> Self {
+        Self::with_code(ErrorCode::default(), err)
+    }
+
+    /// Like [`Self::from`], but tagging the failure with an explicit
+    /// [`ErrorCode`] instead of the default [`ErrorCode::Unknown`].
+    pub fn with_code<T: Fail>(code: ErrorCode, err: T) -> Self {
         let message = err.to_string();
-        Self { 
-            message: if message.is_empty() { None } else { Some(message) }
-        }        
+        Self {
+            code,
+            message: if message.is_empty() { None } else { Some(message) },
+            cause: Some(Box::new(err)),
+        }
     }
 
     pub fn from_str(msg: &str) -> Self {
+        Self::with_code_str(ErrorCode::default(), msg)
+    }
+
+    /// Like [`Self::from_str`], but tagging the failure with an explicit
+    /// [`ErrorCode`] instead of the default [`ErrorCode::Unknown`].
+    pub fn with_code_str(code: ErrorCode, msg: &str) -> Self {
         Self {
-            message: Some(msg.into())
+            code,
+            message: Some(msg.into()),
+            cause: None,
         }
     }
+
+    pub fn code(&self) -> ErrorCode {
+        self.code
+    }
 }
 
 impl Display for PhantomError {
@@ -177,49 +270,82 @@ impl Display for PhantomError {
     }
 }
 
-impl std::error::Error for PhantomError {
-    fn description(&self) -> &str {
-        if let Some(ref msg) = self.message {
-            msg.as_ref()
-        } else {
-            "unknown error"
-        }
+impl Fail for PhantomError {
+    fn cause(&self) -> Option<&dyn Fail> {
+        self.cause.as_deref()
     }
 }
 
+/// Builds a `DB*` [`Error`](crate::error::Error) from a caught error value.
+/// `open`/`read`/`write`/`close` pick which `DB*` kind to wrap it in
+/// (bare, it's `DBOther`); an optional leading code, e.g.
+/// `db_error!(read, NotFound => err)`, tags the resulting [`ErrorCode`]
+/// instead of leaving it at the default [`ErrorCode::Unknown`].
 #[macro_export]
 macro_rules! db_error {
+    (open, $code: ident => $err: expr) => {
+        bufdb_lib::error::Error::from(bufdb_lib::error::ErrorKind::DBOpen(bufdb_lib::error::PhantomError::with_code(bufdb_lib::error::ErrorCode::$code, $err)))
+    };
     (open => $err: expr) => {
         bufdb_lib::error::Error::from(bufdb_lib::error::ErrorKind::DBOpen(bufdb_lib::error::PhantomError::from($err)))
+    };
+    (read, $code: ident => $err: expr) => {
+        bufdb_lib::error::Error::from(bufdb_lib::error::ErrorKind::DBRead(bufdb_lib::error::PhantomError::with_code(bufdb_lib::error::ErrorCode::$code, $err)))
     };
      (read => $err: expr) => {
         bufdb_lib::error::Error::from(bufdb_lib::error::ErrorKind::DBRead(bufdb_lib::error::PhantomError::from($err)))
     };
+    (write, $code: ident => $err: expr) => {
+        bufdb_lib::error::Error::from(bufdb_lib::error::ErrorKind::DBWrite(bufdb_lib::error::PhantomError::with_code(bufdb_lib::error::ErrorCode::$code, $err)))
+    };
     (write => $err: expr) => {
         bufdb_lib::error::Error::from(bufdb_lib::error::ErrorKind::DBWrite(bufdb_lib::error::PhantomError::from($err)))
     };
+    (close, $code: ident => $err: expr) => {
+        bufdb_api::error::Error::from(bufdb_api::error::ErrorKind::DBClose(bufdb_api::error::PhantomError::with_code(bufdb_api::error::ErrorCode::$code, $err)))
+    };
     (close => $err: expr) => {
         bufdb_api::error::Error::from(bufdb_api::error::ErrorKind::DBClose(bufdb_api::error::PhantomError::from($err)))
     };
+    ($code: ident => $err: expr) => {
+        bufdb_api::error::Error::from(bufdb_api::error::ErrorKind::DBOther(bufdb_api::error::PhantomError::with_code(bufdb_api::error::ErrorCode::$code, $err)))
+    };
     ($err: expr) => {
         bufdb_api::error::Error::from(bufdb_api::error::ErrorKind::DBOther(bufdb_api::error::PhantomError::from($err)))
     };
 }
 
+/// Like [`db_error!`], but for a literal message with no underlying error
+/// value to carry as a cause.
 #[macro_export]
 macro_rules! db_error_s {
+    (open, $code: ident => $err: literal) => {
+        bufdb_api::error::Error::from(bufdb_api::error::ErrorKind::DBOpen(bufdb_api::error::PhantomError::with_code_str(bufdb_api::error::ErrorCode::$code, $err)))
+    };
     (open => $err: literal) => {
         bufdb_api::error::Error::from(bufdb_api::error::ErrorKind::DBOpen(bufdb_api::error::PhantomError::from_str($err)))
+    };
+    (read, $code: ident => $err: literal) => {
+        bufdb_lib::error::Error::from(bufdb_lib::error::ErrorKind::DBRead(bufdb_lib::error::PhantomError::with_code_str(bufdb_lib::error::ErrorCode::$code, $err)))
     };
      (read => $err: literal) => {
         bufdb_lib::error::Error::from(bufdb_lib::error::ErrorKind::DBRead(bufdb_lib::error::PhantomError::from_str($err)))
     };
+    (write, $code: ident => $err: literal) => {
+        bufdb_api::error::Error::from(bufdb_api::error::ErrorKind::DBWrite(bufdb_api::error::PhantomError::with_code_str(bufdb_api::error::ErrorCode::$code, $err)))
+    };
     (write => $err: literal) => {
         bufdb_api::error::Error::from(bufdb_api::error::ErrorKind::DBWrite(bufdb_api::error::PhantomError::from_str($err)))
     };
+    (close, $code: ident => $err: literal) => {
+        bufdb_api::error::Error::from(bufdb_api::error::ErrorKind::DBClose(bufdb_api::error::PhantomError::with_code_str(bufdb_api::error::ErrorCode::$code, $err)))
+    };
     (close => $err: literal) => {
         bufdb_api::error::Error::from(bufdb_api::error::ErrorKind::DBClose(bufdb_api::error::PhantomError::from_str($err)))
     };
+    ($code: ident => $err: literal) => {
+        bufdb_api::error::Error::from(bufdb_api::error::ErrorKind::DBOther(bufdb_api::error::PhantomError::with_code_str(bufdb_api::error::ErrorCode::$code, $err)))
+    };
     ($err: literal) => {
         bufdb_api::error::Error::from(bufdb_api::error::ErrorKind::DBOther(bufdb_api::error::PhantomError::from_str($err)))
     };
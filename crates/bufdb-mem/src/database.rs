@@ -0,0 +1,608 @@
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::RwLock;
+
+use bufdb_api::config::Compression;
+use bufdb_lib::db_error_s;
+use bufdb_lib::error::Result;
+use bufdb_storage::KeyComparator;
+use bufdb_storage::KeyCreator;
+use bufdb_storage::MergeOperator;
+use bufdb_storage::SDatabaseConfig;
+use bufdb_storage::entry::BufferEntry;
+use bufdb_storage::entry::Entry;
+use bufdb_storage::entry::compare;
+
+use crate::cursor::IDXCursor;
+use crate::cursor::PKCursor;
+use crate::suffix::append_suffix;
+use crate::suffix::reset_suffix;
+use crate::suffix::unwrap_suffix;
+
+pub(crate) struct MemImpl {
+    name: String,
+    readonly: bool,
+    temporary: bool,
+    unique: bool,
+    data: RwLock<BTreeMap<BufferEntry, Vec<u8>>>,
+    merge_operator: Option<MergeOperator>,
+    /// Codec applied to stored values on [`MemImpl::put`] and undone on
+    /// [`MemImpl::get`] and raw `range_*` iteration. See
+    /// [`bufdb_storage::compression`].
+    compression: Compression,
+    /// Serializes `merge`'s read-modify-write so concurrent merges on the
+    /// same database fold through the operator one at a time, in order.
+    merge_lock: Mutex<()>,
+}
+
+impl MemImpl {
+    fn new(name: &str, readonly: bool, temporary: bool, unique: bool, merge_operator: Option<MergeOperator>, compression: Compression) -> MemImpl {
+        MemImpl {
+            name: name.into(),
+            readonly,
+            temporary,
+            unique,
+            data: RwLock::new(BTreeMap::new()),
+            merge_operator,
+            compression,
+            merge_lock: Mutex::new(()),
+        }
+    }
+
+    pub fn is_empty(&self) -> Result<bool> {
+        Ok(self.data.read().unwrap().is_empty())
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn unique(&self) -> bool {
+        self.unique
+    }
+
+    pub(crate) fn compression(&self) -> Compression {
+        self.compression
+    }
+
+    /// Clones this database's current data into a frozen, read-only
+    /// `MemImpl`, for use by [`crate::snapshot::MemEnvSnapshot`]. Since the clone
+    /// is taken under a single `data` read lock, it reflects one consistent
+    /// instant even while this database keeps taking writes afterward.
+    pub(crate) fn snapshot(&self) -> MemImpl {
+        MemImpl {
+            name: self.name.clone(),
+            readonly: true,
+            temporary: self.temporary,
+            unique: self.unique,
+            data: RwLock::new(self.data.read().unwrap().clone()),
+            merge_operator: None,
+            compression: self.compression,
+            merge_lock: Mutex::new(()),
+        }
+    }
+
+    pub fn count(&self) -> Result<usize> {
+        Ok(self.data.read().unwrap().len())
+    }
+
+    pub fn put(&self, key: &BufferEntry, data: &BufferEntry) -> Result<()> {
+        let data = bufdb_storage::compression::compress(self.compression, data)?;
+        self.data.write().unwrap().insert(key.clone(), data.slice().to_vec());
+        Ok(())
+    }
+
+    pub fn get(&self, key: &BufferEntry) -> Result<Option<BufferEntry>> {
+        let data = self.data.read().unwrap().get(key).cloned().map(BufferEntry::from);
+        data.map(|data| bufdb_storage::compression::decompress(&data)).transpose()
+    }
+
+    pub fn delete(&self, key: &BufferEntry) -> Result<()> {
+        self.data.write().unwrap().remove(key);
+        Ok(())
+    }
+
+    /// Reads `key`'s current value, folds `operand` into it through the
+    /// configured [`MergeOperator`] and writes the result back, guarded by
+    /// `merge_lock` so concurrent merges never interleave.
+    pub fn merge(&self, key: &BufferEntry, operand: &BufferEntry) -> Result<()> {
+        let operator = self.merge_operator.as_ref()
+            .ok_or_else(|| db_error_s!(write, Configuration => "No merge operator configured for this database"))?;
+
+        let _guard = self.merge_lock.lock().unwrap();
+
+        let existing = self.get(key)?;
+        let merged = operator(key, existing.as_ref(), std::slice::from_ref(operand))?;
+
+        self.put(key, &merged)
+    }
+
+    pub fn clear(&self) {
+        self.data.write().unwrap().clear();
+    }
+
+    /// Applies a batch of staged mutations atomically, e.g. when a
+    /// [`crate::transaction::MemTransaction`] commits.
+    pub fn write_batch(&self, ops: &[(BufferEntry, Option<Vec<u8>>)]) -> Result<()> {
+        let mut data = self.data.write().unwrap();
+        for (key, value) in ops {
+            match value {
+                Some(value) => { data.insert(key.clone(), value.clone()); },
+                None => { data.remove(key); },
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The full contents, in key order, taken from a `BTreeMap::range(..)`.
+    pub fn range_all(&self) -> Vec<(BufferEntry, Vec<u8>)> {
+        self.data.read().unwrap().range(..).map(|(k, v)| (k.clone(), v.clone())).collect()
+    }
+
+    /// The contents from `key` onward, in key order, taken from a
+    /// `BTreeMap::range(key..)`.
+    pub fn range_from(&self, key: &BufferEntry) -> Vec<(BufferEntry, Vec<u8>)> {
+        self.data.read().unwrap().range(key.clone()..).map(|(k, v)| (k.clone(), v.clone())).collect()
+    }
+}
+
+impl Display for MemImpl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+impl PartialEq for MemImpl {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl Eq for MemImpl {}
+
+impl Debug for MemImpl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MemImpl")
+            .field("name", &self.name)
+            .field("readonly", &self.readonly)
+            .field("temporary", &self.temporary)
+            .field("unique", &self.unique)
+            .finish()
+    }
+}
+
+struct IndexListener<'a> {
+    idb: Arc<MemImpl>,
+    creator: Arc<dyn KeyCreator + 'a>,
+    on_put: fn (&Self, &BufferEntry, &BufferEntry) -> Result<()>,
+    on_delete: fn (&Self, &BufferEntry, &BufferEntry) -> Result<()>,
+}
+
+impl <'a> IndexListener<'a> {
+    pub fn new<G: KeyCreator + 'a>(database: Arc<MemImpl>, creator: G) -> Self {
+        let unique = database.unique;
+        let creator = Arc::new(creator);
+
+        Self {
+            idb: database,
+            creator,
+            on_put: if unique { Self::put_pk } else { Self::put_idx },
+            on_delete: if unique { Self::delete_pk } else { Self::delete_idx },
+        }
+    }
+
+    pub fn init(&self, pdb: &Arc<MemImpl>) -> Result<()> {
+        if self.idb.is_empty()? {
+            if self.idb.unique {
+                self.init_pk(pdb)
+            } else {
+                self.init_idx(pdb)
+            }
+        } else {
+            Ok(())
+        }
+    }
+
+    fn init_pk(&self, pdb: &Arc<MemImpl>) -> Result<()> {
+        for (key, data) in pdb.range_all() {
+            let data = bufdb_storage::compression::decompress(&BufferEntry::from(data))?;
+            if let Some(skey) = self.creator.create_key(&key, &data)? {
+                self.idb.put(&skey, &key)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn init_idx(&self, pdb: &Arc<MemImpl>) -> Result<()> {
+        let mut id = 0u32;
+
+        for (key, data) in pdb.range_all() {
+            let data = bufdb_storage::compression::decompress(&BufferEntry::from(data))?;
+            if let Some(skey) = self.creator.create_key(&key, &data)? {
+                id += 1;
+                let skey = append_suffix(skey, id)?;
+                self.idb.put(&skey, &key)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn put(&self, key: &BufferEntry, data: &BufferEntry) -> Result<()> {
+        let put_fn = &self.on_put;
+        put_fn(self, key, data)
+    }
+
+    fn put_pk(&self, key: &BufferEntry, data: &BufferEntry) -> Result<()> {
+        if let Some(ref skey) = self.creator.create_key(key, data)? {
+            self.idb.put(skey, key)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn put_idx(&self, key: &BufferEntry, data: &BufferEntry) -> Result<()> {
+        if let Some(skey) = self.creator.create_key(key, data)? {
+            let len = skey.size();
+            let skey = append_suffix(skey, 0)?;
+            let s_slice = skey.left(len)?;
+
+            let order = {
+                let mut found = 1u32;
+                for (n_skey, _) in self.idb.range_from(&skey) {
+                    let (n_slice, n) = unwrap_suffix(&n_skey)?;
+                    if n_slice == s_slice {
+                        found = n + 1;
+                    }
+                    break;
+                }
+                found
+            };
+
+            let skey = reset_suffix(skey, order)?;
+            self.idb.put(&skey, key)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn delete(&self, key: &BufferEntry, data: &BufferEntry) -> Result<()> {
+        let del_fn = &self.on_delete;
+        del_fn(self, key, data)
+    }
+
+    fn delete_pk(&self, key: &BufferEntry, data: &BufferEntry) -> Result<()> {
+        if let Some(skey) = self.creator.create_key(key, data)? {
+            self.idb.delete(&skey)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn delete_idx(&self, key: &BufferEntry, data: &BufferEntry) -> Result<()> {
+        if let Some(skey) = self.creator.create_key(key, data)? {
+            let len = skey.size();
+            let skey = append_suffix(skey, 0)?;
+            let slice = skey.left(len)?;
+
+            let mut found: Option<BufferEntry> = None;
+            let mut order = u32::MAX;
+            for (n_key, n_data) in self.idb.range_from(&skey) {
+                let (n_slice, n) = unwrap_suffix(&n_key)?;
+                if n >= order || slice != n_slice {
+                    break;
+                }
+
+                let n_data = bufdb_storage::compression::decompress(&BufferEntry::from(n_data))?;
+                if *key == n_data {
+                    found = Some(n_key);
+                    break;
+                }
+
+                order = n;
+            }
+
+            if let Some(ref s_key) = found {
+                self.idb.delete(s_key)
+            } else {
+                Ok(())
+            }
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl <'a> Debug for IndexListener<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IndexListener").field("idb", &self.idb).field("creator", &self.creator).finish()
+    }
+}
+
+#[derive(Debug)]
+pub struct PrimaryDatabase<'a> {
+    database: Arc<MemImpl>,
+    listeners: Arc<RwLock<Vec<IndexListener<'a>>>>,
+}
+
+macro_rules! lock_db {
+    ($db: ident) => {
+        $db.listeners.read().unwrap()
+    };
+    ($db: ident => write) => {
+        $db.listeners.write().unwrap()
+    }
+}
+
+impl <'a> PrimaryDatabase<'a> {
+    /// `comparator` is accepted for parity with the LevelDB backend but isn't
+    /// consulted: keys are ordered by the `BTreeMap`'s own byte ordering,
+    /// which every call site already constructs its keys to agree with.
+    pub fn new<C: KeyComparator>(name: &str, readonly: bool, temporary: bool, _comparator: C, merge_operator: Option<MergeOperator>, compression: Compression) -> Result<Self> {
+        let database = MemImpl::new(name, readonly, temporary, true, merge_operator, compression);
+
+        Ok(Self {
+            database: Arc::new(database),
+            listeners: Arc::new(RwLock::new(Vec::new())),
+        })
+    }
+
+    /// The backing store, handed to [`crate::env::MemEnv`] so it can clear or
+    /// forget this database on `truncate_database`/`drop_database`.
+    pub(crate) fn handle(&self) -> Arc<MemImpl> {
+        self.database.clone()
+    }
+
+    pub(crate) fn name(&self) -> &str {
+        self.database.name()
+    }
+
+    fn register_listener<G: KeyCreator + 'a>(&self, idb: Arc<MemImpl>, creator: G) -> Result<()> {
+        let mut listeners = lock_db!(self => write);
+
+        let listener = IndexListener::new(idb, creator);
+        listener.init(&self.database)?;
+
+        listeners.push(listener);
+
+        Ok(())
+    }
+}
+
+impl <'a> bufdb_storage::Database<'a, PKCursor<'a>> for PrimaryDatabase<'a> {
+    type TRANSACTION = crate::transaction::MemTransaction<'a>;
+
+    fn count(&self) -> bufdb_lib::error::Result<usize> {
+        self.database.count()
+    }
+
+    fn put(&self, key: &bufdb_storage::entry::BufferEntry, data: &bufdb_storage::entry::BufferEntry) -> bufdb_lib::error::Result<()> {
+        let listeners = lock_db!(self);
+
+        if !listeners.is_empty() {
+            if let Some(raw_data) = self.database.get(key)? {
+                if data != &raw_data {
+                    for listener in listeners.iter() {
+                        listener.delete(key, &raw_data)?;
+                    }
+                }
+            }
+        }
+
+        self.database.put(key, data)?;
+
+        if !listeners.is_empty() {
+            for listener in listeners.iter() {
+                listener.put(key, data)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn get(&self, key: &bufdb_storage::entry::BufferEntry) -> bufdb_lib::error::Result<Option<bufdb_storage::entry::BufferEntry>> {
+        self.database.get(key)
+    }
+
+    fn delete(&self, key: &BufferEntry) -> Result<()> {
+        let listeners = lock_db!(self);
+
+        if listeners.is_empty() {
+            self.database.delete(key)
+        } else if let Some(data) = self.database.get(key)? {
+            for listener in listeners.iter() {
+                listener.delete(key, &data)?;
+            }
+
+            self.database.delete(key)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn delete_exist(&self, key: &bufdb_storage::entry::BufferEntry) -> bufdb_lib::error::Result<bool> {
+        if let Some(data) = self.database.get(key)? {
+            let listeners = lock_db!(self);
+
+            for listener in listeners.iter() {
+                listener.delete(key, &data)?;
+            }
+
+            self.database.delete(key)?;
+
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn open_cursor(&'a self) -> bufdb_lib::error::Result<PKCursor<'a>> {
+        Ok(PKCursor::new(&self.database))
+    }
+
+    /// Rejects opening a transaction while this database has secondary
+    /// indexes registered: [`MemTransaction::commit`] flushes its staged ops
+    /// straight to the backing `BTreeMap`, with no fan-out to
+    /// [`IndexListener`] (unlike [`Self::put`]/[`Self::delete`]/[`Self::merge`]
+    /// above), which would silently desync every secondary index on this
+    /// table.
+    fn begin_transaction(&'a self) -> bufdb_lib::error::Result<Self::TRANSACTION> {
+        if !lock_db!(self).is_empty() {
+            return Err(db_error_s!(write, Configuration => "cannot open a transaction on a database with secondary indexes: transaction commit does not yet fan writes out to them"));
+        }
+
+        Ok(crate::transaction::MemTransaction::new(&self.database))
+    }
+
+    fn merge(&self, key: &BufferEntry, operand: &BufferEntry) -> Result<()> {
+        let listeners = lock_db!(self);
+
+        if listeners.is_empty() {
+            self.database.merge(key, operand)
+        } else {
+            let raw_data = self.database.get(key)?;
+            self.database.merge(key, operand)?;
+            let merged = self.database.get(key)?;
+
+            if raw_data != merged {
+                if let Some(ref raw_data) = raw_data {
+                    for listener in listeners.iter() {
+                        listener.delete(key, raw_data)?;
+                    }
+                }
+                if let Some(ref merged) = merged {
+                    for listener in listeners.iter() {
+                        listener.put(key, merged)?;
+                    }
+                }
+            }
+
+            Ok(())
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct SecondaryDatabase<'a> {
+    database: Arc<MemImpl>,
+    parent: Arc<MemImpl>,
+    listeners: Arc<RwLock<Vec<IndexListener<'a>>>>,
+}
+
+impl <'a> SecondaryDatabase<'a> {
+    pub fn new<C: KeyComparator, G: KeyCreator + 'a>(p_database: &PrimaryDatabase<'a>, name: &str, config: SDatabaseConfig<C, G>) -> Result<Self> {
+        let parent = p_database.database.clone();
+
+        let db = MemImpl::new(name, parent.readonly, config.temporary || parent.temporary, config.unique, config.merge_operator, config.compression);
+        let database = Arc::new(db);
+
+        p_database.register_listener(database.clone(), config.creator)?;
+
+        Ok(Self {
+            database,
+            parent,
+            listeners: p_database.listeners.clone(),
+        })
+    }
+
+    pub(crate) fn name(&self) -> &str {
+        self.database.name()
+    }
+
+    pub(crate) fn parent_name(&self) -> &str {
+        self.parent.name()
+    }
+
+    pub(crate) fn handle(&self) -> Arc<MemImpl> {
+        self.database.clone()
+    }
+}
+
+impl <'a> Drop for SecondaryDatabase<'a> {
+    fn drop(&mut self) {
+        let mut listeners = self.listeners.write().unwrap();
+        listeners.retain(|x| x.idb != self.database);
+    }
+}
+
+impl <'a> bufdb_storage::Database<'a, IDXCursor<'a>> for SecondaryDatabase<'a> {
+    type TRANSACTION = crate::transaction::MemTransaction<'a>;
+
+    fn count(&self) -> bufdb_lib::error::Result<usize> {
+        self.database.count()
+    }
+
+    fn put(&self, key: &bufdb_storage::entry::BufferEntry, data: &bufdb_storage::entry::BufferEntry) -> bufdb_lib::error::Result<()> {
+        self.database.put(key, data)
+    }
+
+    fn get(&self, key: &bufdb_storage::entry::BufferEntry) -> bufdb_lib::error::Result<Option<bufdb_storage::entry::BufferEntry>> {
+        self.database.get(key)
+    }
+
+    fn delete(&self, key: &BufferEntry) -> Result<()> {
+        self.database.delete(key)
+    }
+
+    fn delete_exist(&self, key: &bufdb_storage::entry::BufferEntry) -> bufdb_lib::error::Result<bool> {
+        let data = self.database.get(key)?;
+        if data.is_some() {
+            self.database.delete(key)?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn open_cursor(&self) -> bufdb_lib::error::Result<IDXCursor> {
+        Ok(IDXCursor::new(&self.parent, &self.database))
+    }
+
+    fn begin_transaction(&'a self) -> bufdb_lib::error::Result<Self::TRANSACTION> {
+        Ok(crate::transaction::MemTransaction::new_secondary(&self.database, &self.parent))
+    }
+
+    fn merge(&self, key: &BufferEntry, operand: &BufferEntry) -> Result<()> {
+        self.database.merge(key, operand)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bufdb_storage::Database;
+    use bufdb_storage::KeyComparator;
+    use bufdb_storage::entry::BufferEntry;
+    use bufdb_storage::entry::compare;
+
+    use super::PrimaryDatabase;
+
+    #[derive(Debug, Default, Clone, Copy)]
+    struct ByteOrderComparator {}
+
+    impl KeyComparator for ByteOrderComparator {
+        fn compare<T: bufdb_storage::entry::Entry>(&self, key1: &T, key2: &T) -> bufdb_lib::error::Result<std::cmp::Ordering> {
+            Ok(compare(key1, key2))
+        }
+    }
+
+    #[test]
+    fn test_put_get_delete() {
+        let db = PrimaryDatabase::new("t", false, true, ByteOrderComparator {}, None, bufdb_api::config::Compression::None).unwrap();
+
+        let key = BufferEntry::from(b"k1".to_vec());
+        let value = BufferEntry::from(b"v1".to_vec());
+
+        db.put(&key, &value).unwrap();
+        assert_eq!(1, db.count().unwrap());
+        assert_eq!(Some(value.clone()), db.get(&key).unwrap());
+
+        assert!(db.delete_exist(&key).unwrap());
+        assert_eq!(0, db.count().unwrap());
+        assert_eq!(None, db.get(&key).unwrap());
+    }
+}
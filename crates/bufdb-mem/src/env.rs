@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::RwLock;
+
+use bufdb_api::error::Result;
+use bufdb_storage::DatabaseConfig;
+use bufdb_storage::Environment;
+use bufdb_storage::EnvironmentConfig;
+use bufdb_storage::KeyComparator;
+use bufdb_storage::KeyCreator;
+use bufdb_storage::SDatabaseConfig;
+
+use crate::cursor::IDXCursor;
+use crate::cursor::PKCursor;
+use crate::database::MemImpl;
+use crate::database::PrimaryDatabase;
+use crate::database::SecondaryDatabase;
+use crate::snapshot::MemEnvSnapshot;
+
+/// An [`Environment`] whose databases live only in memory. `config.dir` is
+/// kept for diagnostic purposes (e.g. `Display` on the owning `Instance`)
+/// but nothing is ever read from or written to it.
+///
+/// `databases` tracks every primary and secondary database, by name, so
+/// `snapshot` can freeze them and
+/// `drop_database`/`truncate_database`/`rename_database` have something to
+/// act on even though the data itself lives on the `Arc<MemImpl>` that the
+/// caller's database handle already holds.
+pub struct MemEnv {
+    readonly: bool,
+    temporary: bool,
+    databases: RwLock<HashMap<String, Arc<MemImpl>>>,
+}
+
+impl MemEnv {
+    pub fn readonly(&self) -> bool {
+        self.readonly
+    }
+
+    pub fn temporary(&self) -> bool {
+        self.temporary
+    }
+}
+
+impl <'a> Environment<'a> for MemEnv {
+    type CURSOR = PKCursor<'a>;
+    type SCUROSR = IDXCursor<'a>;
+    type DATABASE = PrimaryDatabase<'a>;
+    type SDATABASE = SecondaryDatabase<'a>;
+    type SNAPSHOT = MemEnvSnapshot;
+
+    fn new(config: EnvironmentConfig) -> Result<Self> {
+        Ok(Self {
+            readonly: config.readonly,
+            temporary: config.temporary,
+            databases: RwLock::new(HashMap::new()),
+        })
+    }
+
+    fn create_database<C: KeyComparator>(&self, name: &str, config: DatabaseConfig<C>) -> Result<Self::DATABASE> {
+        let database = PrimaryDatabase::new(name, config.readonly, config.temporary, config.comparator, config.merge_operator, config.compression)?;
+
+        self.databases.write().unwrap().insert(name.into(), database.handle());
+
+        Ok(database)
+    }
+
+    fn create_secondary_database<C: KeyComparator, G: KeyCreator + 'a>(&self, database: &Self::DATABASE, name: &str, config: SDatabaseConfig<C, G>) -> Result<Self::SDATABASE> {
+        let sdatabase = SecondaryDatabase::new(database, name, config)?;
+
+        self.databases.write().unwrap().insert(name.into(), sdatabase.handle());
+
+        Ok(sdatabase)
+    }
+
+    fn drop_database(&self, name: &str) -> Result<()> {
+        self.databases.write().unwrap().remove(name);
+        Ok(())
+    }
+
+    fn drop_secondary_database(&self, _name: &str) -> Result<()> {
+        Ok(())
+    }
+
+    fn truncate_database(&self, name: &str) -> Result<()> {
+        if let Some(database) = self.databases.read().unwrap().get(name) {
+            database.clear();
+        }
+
+        Ok(())
+    }
+
+    fn rename_database(&self, raw_name: &str, new_name: &str) -> Result<()> {
+        let mut databases = self.databases.write().unwrap();
+        if let Some(database) = databases.remove(raw_name) {
+            databases.insert(new_name.into(), database);
+        }
+
+        Ok(())
+    }
+
+    fn snapshot(&'a self) -> Result<Self::SNAPSHOT> {
+        let databases = self.databases.read().unwrap().clone();
+        Ok(MemEnvSnapshot::new(databases))
+    }
+
+    /// Always fails: a [`MemEnv`] keeps no on-disk data for a checkpoint to
+    /// copy. Use [`Environment::snapshot`] for a consistent in-process read
+    /// view instead.
+    fn checkpoint(&self, _target: &std::path::Path) -> Result<()> {
+        Err(bufdb_lib::db_error_s!(write, Unsupported => "MemEnv has no on-disk data to checkpoint"))
+    }
+}
@@ -0,0 +1,38 @@
+//! In-memory `StorageEngine` backend.
+//!
+//! Every database is an ordered `BTreeMap<BufferEntry, Vec<u8>>` guarded by
+//! an `RwLock`, so nothing ever touches disk and the `leveldb` system
+//! dependency isn't needed. Meant for unit tests and
+//! `InstanceConfig::new_temp` style ephemeral databases where `bufdb_level`'s
+//! overhead is unwanted.
+
+use bufdb_storage::StorageEngine;
+use cursor::IDXCursor;
+use cursor::PKCursor;
+use database::PrimaryDatabase;
+use database::SecondaryDatabase;
+use env::MemEnv;
+
+pub mod env;
+pub mod database;
+pub mod cursor;
+pub mod snapshot;
+pub(crate) mod suffix;
+pub(crate) mod transaction;
+
+#[derive(Debug, Clone, Copy)]
+pub struct MemEngine {}
+
+impl <'a> StorageEngine<'a> for MemEngine {
+    type CURSOR = PKCursor<'a>;
+    type SCUROSR = IDXCursor<'a>;
+
+    type DATABASE = PrimaryDatabase<'a>;
+    type SDATABASE = SecondaryDatabase<'a>;
+
+    type ENVIRONMENT = MemEnv;
+
+    fn name(&self) -> &str {
+        "Memory Engine"
+    }
+}
@@ -0,0 +1,47 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use bufdb_lib::error::Result;
+
+use crate::cursor::IDXCursor;
+use crate::cursor::PKCursor;
+use crate::database::MemImpl;
+use crate::database::PrimaryDatabase;
+use crate::database::SecondaryDatabase;
+use crate::env::MemEnv;
+
+/// A point-in-time read view over every database open in a [`MemEnv`],
+/// taken by [`bufdb_storage::Environment::snapshot`].
+///
+/// Each database's data is cloned once, up front, into a frozen `MemImpl`,
+/// so every cursor opened from the same `MemEnvSnapshot` reads the data as
+/// it stood at that single instant, regardless of writes the live
+/// databases take afterward.
+pub struct MemEnvSnapshot {
+    databases: HashMap<String, Arc<MemImpl>>,
+}
+
+impl MemEnvSnapshot {
+    pub(crate) fn new(databases: HashMap<String, Arc<MemImpl>>) -> Self {
+        let databases = databases.iter()
+            .map(|(name, db)| (name.clone(), Arc::new(db.snapshot())))
+            .collect();
+
+        Self { databases }
+    }
+
+    fn frozen(&self, name: &str) -> &Arc<MemImpl> {
+        self.databases.get(name)
+            .unwrap_or_else(|| panic!("database '{}' was opened after this snapshot was taken", name))
+    }
+}
+
+impl <'a> bufdb_storage::Snapshot<'a, MemEnv> for MemEnvSnapshot {
+    fn open_cursor(&'a self, database: &'a PrimaryDatabase<'a>) -> Result<PKCursor<'a>> {
+        Ok(PKCursor::new(self.frozen(database.name())))
+    }
+
+    fn open_secondary_cursor(&'a self, database: &'a SecondaryDatabase<'a>) -> Result<IDXCursor<'a>> {
+        Ok(IDXCursor::new(self.frozen(database.parent_name()), self.frozen(database.name())))
+    }
+}
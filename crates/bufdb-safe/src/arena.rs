@@ -0,0 +1,306 @@
+//! `id-arena` backed registry of database stores for [`crate::env::SafeEnv`].
+//!
+//! Every primary and secondary database is a slot in one shared
+//! `Arena<DatabaseStore>`. `PrimaryDatabase`/`SecondaryDatabase` hold the
+//! slot's [`StoreId`] rather than a direct reference to it, so renaming a
+//! database is a pure name-to-id rebinding: the `Id` itself, and every
+//! handle built from it, stays valid.
+//!
+//! `id-arena` never frees a slot once allocated, so "dropping" a database
+//! clears its data and forgets its name rather than deallocating anything.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use id_arena::Arena;
+use id_arena::Id;
+
+use bufdb_api::config::Compression;
+use bufdb_lib::db_error_s;
+use bufdb_lib::error::Result;
+use bufdb_storage::MergeOperator;
+use bufdb_storage::entry::BufferEntry;
+
+pub(crate) type StoreId = Id<DatabaseStore>;
+
+#[derive(Clone)]
+pub(crate) struct DatabaseStore {
+    pub(crate) name: String,
+    pub(crate) readonly: bool,
+    pub(crate) temporary: bool,
+    pub(crate) unique: bool,
+    pub(crate) data: BTreeMap<BufferEntry, Vec<u8>>,
+    pub(crate) merge_operator: Option<MergeOperator>,
+    pub(crate) compression: Compression,
+    /// The primary this store indexes; `None` for a primary database.
+    pub(crate) parent: Option<StoreId>,
+    /// Secondaries registered against this store, so dropping it can
+    /// cascade to them.
+    pub(crate) secondaries: Vec<StoreId>,
+}
+
+impl DatabaseStore {
+    fn new(name: &str, readonly: bool, temporary: bool, unique: bool, merge_operator: Option<MergeOperator>, compression: Compression, parent: Option<StoreId>) -> Self {
+        Self {
+            name: name.into(),
+            readonly,
+            temporary,
+            unique,
+            data: BTreeMap::new(),
+            merge_operator,
+            compression,
+            parent,
+            secondaries: Vec::new(),
+        }
+    }
+}
+
+struct Inner {
+    arena: Arena<DatabaseStore>,
+    names: BTreeMap<String, StoreId>,
+}
+
+/// A cheaply-cloneable handle onto the environment's single arena. Every
+/// `PrimaryDatabase`, `SecondaryDatabase` and cursor holds a clone of this
+/// plus the [`StoreId`](s) it cares about, instead of borrowing the
+/// environment directly.
+#[derive(Clone)]
+pub(crate) struct SafeArena {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl SafeArena {
+    pub(crate) fn new() -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner {
+                arena: Arena::new(),
+                names: BTreeMap::new(),
+            })),
+        }
+    }
+
+    pub(crate) fn create(&self, name: &str, readonly: bool, temporary: bool, unique: bool, merge_operator: Option<MergeOperator>, compression: Compression) -> StoreId {
+        let store = DatabaseStore::new(name, readonly, temporary, unique, merge_operator, compression, None);
+        self.alloc(name, store)
+    }
+
+    /// Registers an already-built [`DatabaseStore`] (e.g. one produced by
+    /// [`Self::frozen_stores`]) under its own name, for
+    /// [`crate::snapshot::SafeEnvSnapshot`].
+    pub(crate) fn restore(&self, store: DatabaseStore) -> StoreId {
+        let name = store.name.clone();
+        self.alloc(&name, store)
+    }
+
+    pub(crate) fn create_secondary(&self, parent: StoreId, name: &str, readonly: bool, temporary: bool, unique: bool, merge_operator: Option<MergeOperator>, compression: Compression) -> StoreId {
+        let store = DatabaseStore::new(name, readonly, temporary, unique, merge_operator, compression, Some(parent));
+        let id = self.alloc(name, store);
+
+        let mut inner = self.inner.lock().unwrap();
+        if let Some(store) = inner.arena.get_mut(parent) {
+            store.secondaries.push(id);
+        }
+
+        id
+    }
+
+    fn alloc(&self, name: &str, store: DatabaseStore) -> StoreId {
+        let mut inner = self.inner.lock().unwrap();
+        let id = inner.arena.alloc(store);
+        inner.names.insert(name.into(), id);
+        id
+    }
+
+    pub(crate) fn lookup(&self, name: &str) -> Option<StoreId> {
+        self.inner.lock().unwrap().names.get(name).copied()
+    }
+
+    fn with<R>(&self, id: StoreId, f: impl FnOnce(&DatabaseStore) -> R) -> R {
+        let inner = self.inner.lock().unwrap();
+        f(inner.arena.get(id).expect("id-arena slots are never freed"))
+    }
+
+    fn with_mut<R>(&self, id: StoreId, f: impl FnOnce(&mut DatabaseStore) -> R) -> R {
+        let mut inner = self.inner.lock().unwrap();
+        f(inner.arena.get_mut(id).expect("id-arena slots are never freed"))
+    }
+
+    pub(crate) fn name(&self, id: StoreId) -> String {
+        self.with(id, |store| store.name.clone())
+    }
+
+    pub(crate) fn unique(&self, id: StoreId) -> bool {
+        self.with(id, |store| store.unique)
+    }
+
+    pub(crate) fn readonly(&self, id: StoreId) -> bool {
+        self.with(id, |store| store.readonly)
+    }
+
+    pub(crate) fn temporary(&self, id: StoreId) -> bool {
+        self.with(id, |store| store.temporary)
+    }
+
+    pub(crate) fn compression(&self, id: StoreId) -> Compression {
+        self.with(id, |store| store.compression)
+    }
+
+    pub(crate) fn is_empty(&self, id: StoreId) -> bool {
+        self.with(id, |store| store.data.is_empty())
+    }
+
+    pub(crate) fn count(&self, id: StoreId) -> usize {
+        self.with(id, |store| store.data.len())
+    }
+
+    pub(crate) fn get(&self, id: StoreId, key: &BufferEntry) -> Result<Option<BufferEntry>> {
+        let data = self.with(id, |store| store.data.get(key).cloned());
+        data.map(BufferEntry::from).map(|data| bufdb_storage::compression::decompress(&data)).transpose()
+    }
+
+    pub(crate) fn put(&self, id: StoreId, key: &BufferEntry, data: &BufferEntry) -> Result<()> {
+        let compression = self.with(id, |store| store.compression);
+        let data = bufdb_storage::compression::compress(compression, data)?;
+
+        self.with_mut(id, |store| { store.data.insert(key.clone(), data.slice().to_vec()); });
+
+        Ok(())
+    }
+
+    pub(crate) fn delete(&self, id: StoreId, key: &BufferEntry) {
+        self.with_mut(id, |store| { store.data.remove(key); });
+    }
+
+    /// Applies a transaction's staged mutations in one batch, under a single
+    /// lock acquisition, so concurrent readers never observe a partial
+    /// commit.
+    pub(crate) fn write_batch(&self, id: StoreId, ops: &[(BufferEntry, Option<Vec<u8>>)]) -> Result<()> {
+        self.with_mut(id, |store| {
+            for (key, value) in ops {
+                match value {
+                    Some(value) => { store.data.insert(key.clone(), value.clone()); },
+                    None => { store.data.remove(key); },
+                }
+            }
+        });
+
+        Ok(())
+    }
+
+    pub(crate) fn clear(&self, id: StoreId) {
+        self.with_mut(id, |store| store.data.clear());
+    }
+
+    /// Folds `operand` into `key`'s current value through the store's
+    /// configured [`MergeOperator`] and writes the result back, all under
+    /// one arena lock so concurrent merges on the same store never
+    /// interleave.
+    pub(crate) fn merge(&self, id: StoreId, key: &BufferEntry, operand: &BufferEntry) -> Result<()> {
+        self.with_mut(id, |store| {
+            let operator = store.merge_operator.as_ref()
+                .ok_or_else(|| db_error_s!(write, Configuration => "No merge operator configured for this database"))?
+                .clone();
+
+            let existing = store.data.get(key).cloned().map(BufferEntry::from);
+            let existing = existing.map(|data| bufdb_storage::compression::decompress(&data)).transpose()?;
+
+            let merged = operator(key, existing.as_ref(), std::slice::from_ref(operand))?;
+            let merged = bufdb_storage::compression::compress(store.compression, &merged)?;
+
+            store.data.insert(key.clone(), merged.slice().to_vec());
+
+            Ok(())
+        })
+    }
+
+    /// The full contents, in key order, with values already decompressed.
+    pub(crate) fn range_all(&self, id: StoreId) -> Result<Vec<(BufferEntry, Vec<u8>)>> {
+        let raw = self.with(id, |store| store.data.range::<BufferEntry, _>(..).map(|(k, v)| (k.clone(), v.clone())).collect::<Vec<_>>());
+        decompress_all(raw)
+    }
+
+    /// The contents from `key` onward, in key order, with values already
+    /// decompressed.
+    pub(crate) fn range_from(&self, id: StoreId, key: &BufferEntry) -> Result<Vec<(BufferEntry, Vec<u8>)>> {
+        let raw = self.with(id, |store| store.data.range(key.clone()..).map(|(k, v)| (k.clone(), v.clone())).collect::<Vec<_>>());
+        decompress_all(raw)
+    }
+
+    /// Clears `name`'s data, forgets its name binding, and cascades to every
+    /// secondary registered against it. Arena slots themselves stay
+    /// allocated.
+    pub(crate) fn drop_database(&self, name: &str) {
+        let mut inner = self.inner.lock().unwrap();
+        if let Some(id) = inner.names.remove(name) {
+            Self::clear_cascade(&mut inner.arena, &mut inner.names, id);
+        }
+    }
+
+    fn clear_cascade(arena: &mut Arena<DatabaseStore>, names: &mut BTreeMap<String, StoreId>, id: StoreId) {
+        let secondaries = match arena.get_mut(id) {
+            Some(store) => {
+                store.data.clear();
+                std::mem::take(&mut store.secondaries)
+            },
+            None => return,
+        };
+
+        for secondary in secondaries {
+            if let Some(store) = arena.get(secondary) {
+                let name = store.name.clone();
+                names.remove(&name);
+            }
+
+            Self::clear_cascade(arena, names, secondary);
+        }
+    }
+
+    pub(crate) fn truncate_database(&self, name: &str) {
+        let id = self.inner.lock().unwrap().names.get(name).copied();
+        if let Some(id) = id {
+            self.clear(id);
+        }
+    }
+
+    pub(crate) fn rename_database(&self, raw_name: &str, new_name: &str) {
+        let mut inner = self.inner.lock().unwrap();
+        if let Some(id) = inner.names.remove(raw_name) {
+            if let Some(store) = inner.arena.get_mut(id) {
+                store.name = new_name.into();
+            }
+
+            inner.names.insert(new_name.into(), id);
+        }
+    }
+
+    /// Deep-clones every store currently registered, dropping their
+    /// `parent`/`secondaries` links (meaningless outside the live arena),
+    /// for [`crate::snapshot::SafeEnvSnapshot`].
+    pub(crate) fn frozen_stores(&self) -> Vec<DatabaseStore> {
+        let inner = self.inner.lock().unwrap();
+        inner.names.values()
+            .filter_map(|&id| inner.arena.get(id))
+            .map(|store| DatabaseStore {
+                name: store.name.clone(),
+                readonly: true,
+                temporary: store.temporary,
+                unique: store.unique,
+                data: store.data.clone(),
+                merge_operator: None,
+                compression: store.compression,
+                parent: None,
+                secondaries: Vec::new(),
+            })
+            .collect()
+    }
+}
+
+fn decompress_all(raw: Vec<(BufferEntry, Vec<u8>)>) -> Result<Vec<(BufferEntry, Vec<u8>)>> {
+    raw.into_iter()
+        .map(|(key, data)| {
+            let data = bufdb_storage::compression::decompress(&BufferEntry::from(data))?;
+            Ok((key, data.slice().to_vec()))
+        })
+        .collect()
+}
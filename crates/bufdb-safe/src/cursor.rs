@@ -0,0 +1,375 @@
+use std::cmp::Ordering;
+use std::vec::IntoIter;
+
+use bufdb_lib::db_error_s;
+use bufdb_lib::error::Result;
+use bufdb_storage::PrimaryCursor;
+use bufdb_storage::SecondaryCursor;
+use bufdb_storage::entry::BufferEntry;
+use bufdb_storage::entry::Entry;
+use bufdb_storage::entry::compare;
+
+use crate::arena::SafeArena;
+use crate::arena::StoreId;
+use crate::suffix::append_suffix;
+use crate::suffix::size_of_suffix;
+use crate::suffix::trucate_suffix;
+use crate::transaction::StagedOp;
+
+macro_rules! vec_to_buf {
+    ($data: expr, $buf: ident) => {
+        if let Some(buf) = $buf {
+            buf.set_data($data);
+        }
+    };
+}
+
+macro_rules! buf_to_buf {
+    ($src: expr, $dst: ident) => {
+        if let Some(dest) = $dst {
+            dest.set_buffer($src)
+        }
+    };
+}
+
+pub struct PKCursor<'a> {
+    arena: SafeArena,
+    id: StoreId,
+    iter: IntoIter<(BufferEntry, Vec<u8>)>,
+    /// Item pulled from `iter` but not yet returned, cached so the merge with
+    /// `staged` can peek at it without consuming it.
+    base_peek: Option<(BufferEntry, Vec<u8>)>,
+    /// Snapshot of a transaction's staged mutations, sorted ascending by key,
+    /// taken when the cursor was opened. Empty for a cursor opened directly
+    /// on the database outside of a transaction.
+    staged: Vec<StagedOp>,
+    staged_pos: usize,
+    _marker: std::marker::PhantomData<&'a ()>,
+}
+
+impl <'a> PKCursor<'a> {
+    pub(crate) fn new(arena: SafeArena, id: StoreId) -> Result<Self> {
+        Self::new_staged(arena, id, Vec::new())
+    }
+
+    /// Creates a cursor that also merges in `staged`, the uncommitted writes
+    /// of the transaction it was opened from.
+    pub(crate) fn new_staged(arena: SafeArena, id: StoreId, staged: Vec<StagedOp>) -> Result<Self> {
+        let iter = arena.range_all(id)?.into_iter();
+
+        Ok(Self {
+            arena,
+            id,
+            iter,
+            base_peek: None,
+            staged,
+            staged_pos: 0,
+            _marker: std::marker::PhantomData,
+        })
+    }
+
+    fn fill_base_peek(&mut self) {
+        if self.base_peek.is_none() {
+            self.base_peek = self.iter.next();
+        }
+    }
+
+    /// Seeks the committed range and the staged snapshot to the first entry
+    /// `>= key`.
+    fn seek(&mut self, key: &BufferEntry) -> Result<()> {
+        self.iter = self.arena.range_from(self.id, key)?.into_iter();
+        self.base_peek = None;
+        self.staged_pos = self.staged.partition_point(|(k, _)| compare(k, key) == Ordering::Less);
+
+        Ok(())
+    }
+
+    /// Pulls the next `(key, data)` pair from the merge of the committed
+    /// range and the staged snapshot, in ascending key order. On a matching
+    /// key the staged entry shadows the committed one; a staged tombstone
+    /// (delete) is skipped entirely rather than returned.
+    fn advance(&mut self) -> Option<(BufferEntry, Vec<u8>)> {
+        loop {
+            self.fill_base_peek();
+
+            let take_staged = |this: &mut Self| {
+                let (key, value) = this.staged[this.staged_pos].clone();
+                this.staged_pos += 1;
+                value.map(|data| (key, data))
+            };
+
+            match (&self.base_peek, self.staged.get(self.staged_pos)) {
+                (None, None) => return None,
+                (Some(_), None) => return self.base_peek.take(),
+                (None, Some(_)) => {
+                    if let Some(entry) = take_staged(self) {
+                        return Some(entry);
+                    }
+                }
+                (Some((b_key, _)), Some((s_key, _))) => match compare(b_key, s_key) {
+                    Ordering::Less => return self.base_peek.take(),
+                    Ordering::Greater => {
+                        if let Some(entry) = take_staged(self) {
+                            return Some(entry);
+                        }
+                    }
+                    Ordering::Equal => {
+                        self.base_peek = None;
+                        if let Some(entry) = take_staged(self) {
+                            return Some(entry);
+                        }
+                    }
+                },
+            }
+        }
+    }
+}
+
+impl <'a> PrimaryCursor<'a> for PKCursor<'a> {
+    fn search(&mut self, key: &BufferEntry, data: Option<&mut BufferEntry>) -> Result<bool> {
+        self.seek(key)?;
+
+        if let Some((n_key, n_data)) = self.advance() {
+            if *key == n_key {
+                vec_to_buf!(n_data, data);
+
+                Ok(true)
+            } else {
+                Ok(false)
+            }
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn search_range(&mut self, key: &mut BufferEntry, data: Option<&mut BufferEntry>) -> Result<bool> {
+        self.seek(key)?;
+
+        self.next(Some(key), data)
+    }
+
+    fn next(&mut self, key: Option<&mut BufferEntry>, data: Option<&mut BufferEntry>) -> Result<bool> {
+        if let Some((n_key, n_data)) = self.advance() {
+            buf_to_buf!(n_key, key);
+            vec_to_buf!(n_data, data);
+
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn next_dup(&mut self, _key: Option<&mut BufferEntry>, _data: Option<&mut BufferEntry>) -> Result<bool> {
+        Ok(false)
+    }
+
+    fn skip(&mut self, count: usize, key: Option<&mut BufferEntry>, data: Option<&mut BufferEntry>) -> Result<bool> {
+        let mut count = count;
+        while let Some((n_key, n_data)) = self.advance() {
+            count -= 1;
+            if count == 0 {
+                buf_to_buf!(n_key, key);
+                vec_to_buf!(n_data, data);
+
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+}
+
+/// A cursor over a secondary index.
+///
+/// Unlike [`PKCursor`], this does not merge a transaction's staged writes:
+/// secondary index entries are only maintained by `IndexListener` when a
+/// primary `put`/`delete` actually commits, so a secondary cursor opened from
+/// a transaction only sees already-committed index state until that
+/// transaction commits.
+pub struct IDXCursor<'a> {
+    arena: SafeArena,
+    db: StoreId,
+    idb: StoreId,
+    iter: IntoIter<(BufferEntry, Vec<u8>)>,
+    last_key: Option<BufferEntry>,
+    unique: bool,
+    _marker: std::marker::PhantomData<&'a ()>,
+}
+
+impl <'a> IDXCursor<'a> {
+    pub(crate) fn new(arena: SafeArena, pdb: StoreId, idb: StoreId) -> Result<Self> {
+        let unique = arena.unique(idb);
+        let iter = arena.range_all(idb)?.into_iter();
+
+        Ok(Self {
+            arena,
+            db: pdb,
+            idb,
+            iter,
+            last_key: None,
+            unique,
+            _marker: std::marker::PhantomData,
+        })
+    }
+
+    fn seek(&mut self, key: &BufferEntry) -> Result<()> {
+        self.iter = if self.unique {
+            self.arena.range_from(self.idb, key)?.into_iter()
+        } else {
+            let skey = append_suffix(key.clone(), 0)?;
+            self.arena.range_from(self.idb, &skey)?.into_iter()
+        };
+
+        Ok(())
+    }
+
+    fn match_key(&self, key: &BufferEntry, skey: &BufferEntry) -> Result<bool> {
+        if self.unique {
+            Ok(key == skey)
+        } else {
+            let slice = skey.left(skey.size() - size_of_suffix(skey))?;
+            Ok(key.slice() == slice.slice())
+        }
+    }
+
+    fn rekey(&self, skey: BufferEntry) -> BufferEntry {
+        if self.unique {
+            skey
+        } else {
+            let mut skey = skey;
+            let n = size_of_suffix(&skey);
+            skey.set_len(skey.len() - n);
+            skey
+        }
+    }
+
+    fn to_next_dup(&mut self) -> Result<Option<(BufferEntry, Vec<u8>)>> {
+        if self.unique {
+            return Ok(None);
+        }
+
+        let key = match &self.last_key {
+            Some(key) => key.clone(),
+            None => return Ok(None),
+        };
+
+        if let Some((n_key, n_data)) = self.iter.next() {
+            let prev = trucate_suffix(&key)?;
+            let cur = trucate_suffix(&n_key)?;
+            if prev == cur {
+                self.last_key = Some(n_key.clone());
+                Ok(Some((n_key, n_data)))
+            } else {
+                Ok(None)
+            }
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn fetch(&self, p_data: Vec<u8>, p_key: Option<&mut BufferEntry>, data: Option<&mut BufferEntry>) -> Result<()> {
+        if let Some(key) = p_key {
+            key.set_data(p_data);
+
+            if let Some(data) = data {
+                if let Some(found) = self.arena.get(self.db, key)? {
+                    data.set_buffer(found);
+                } else {
+                    return Err(db_error_s!(read, Corruption => "Index mismatch"));
+                }
+            }
+        } else if let Some(data) = data {
+            let key = BufferEntry::from(p_data);
+            if let Some(found) = self.arena.get(self.db, &key)? {
+                data.set_buffer(found);
+            } else {
+                return Err(db_error_s!(read, Corruption => "Index mismatch"));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl <'a> PrimaryCursor<'a> for IDXCursor<'a> {
+    fn search(&mut self, key: &BufferEntry, data: Option<&mut BufferEntry>) -> Result<bool> {
+        self.s_search(key, data, None)
+    }
+
+    fn search_range(&mut self, key: &mut BufferEntry, data: Option<&mut BufferEntry>) -> Result<bool> {
+        self.s_search_range(key, data, None)
+    }
+
+    fn next(&mut self, key: Option<&mut BufferEntry>, data: Option<&mut BufferEntry>) -> Result<bool> {
+        self.s_next(key, data, None)
+    }
+
+    fn next_dup(&mut self, key: Option<&mut BufferEntry>, data: Option<&mut BufferEntry>) -> Result<bool> {
+        self.s_next_dup(key, data, None)
+    }
+
+    fn skip(&mut self, count: usize, key: Option<&mut BufferEntry>, data: Option<&mut BufferEntry>) -> Result<bool> {
+        self.s_skip(count, key, data, None)
+    }
+}
+
+impl <'a> SecondaryCursor<'a> for IDXCursor<'a> {
+    fn s_search(&mut self, key: &BufferEntry, p_key: Option<&mut BufferEntry>, data: Option<&mut BufferEntry>) -> Result<bool> {
+        self.seek(key)?;
+
+        if let Some((n_key, n_data)) = self.iter.next() {
+            self.last_key = Some(n_key.clone());
+            if self.match_key(key, &n_key)? {
+                self.fetch(n_data, p_key, data)?;
+
+                Ok(true)
+            } else {
+                Ok(false)
+            }
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn s_search_range(&mut self, key: &mut BufferEntry, p_key: Option<&mut BufferEntry>, data: Option<&mut BufferEntry>) -> Result<bool> {
+        self.seek(key)?;
+
+        self.s_next(Some(key), p_key, data)
+    }
+
+    fn s_next(&mut self, key: Option<&mut BufferEntry>, p_key: Option<&mut BufferEntry>, data: Option<&mut BufferEntry>) -> Result<bool> {
+        if let Some((n_key, n_data)) = self.iter.next() {
+            self.last_key = Some(n_key.clone());
+            buf_to_buf!(self.rekey(n_key), key);
+            self.fetch(n_data, p_key, data)?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn s_next_dup(&mut self, key: Option<&mut BufferEntry>, p_key: Option<&mut BufferEntry>, data: Option<&mut BufferEntry>) -> Result<bool> {
+        if let Some((n_key, n_data)) = self.to_next_dup()? {
+            buf_to_buf!(self.rekey(n_key), key);
+            self.fetch(n_data, p_key, data)?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn s_skip(&mut self, count: usize, key: Option<&mut BufferEntry>, p_key: Option<&mut BufferEntry>, data: Option<&mut BufferEntry>) -> Result<bool> {
+        let mut count = count;
+        while let Some((n_key, n_data)) = self.iter.next() {
+            self.last_key = Some(n_key.clone());
+            count -= 1;
+            if count == 0 {
+                buf_to_buf!(self.rekey(n_key), key);
+                self.fetch(n_data, p_key, data)?;
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+}
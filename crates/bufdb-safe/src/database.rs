@@ -0,0 +1,442 @@
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::marker::PhantomData;
+use std::sync::Arc;
+use std::sync::RwLock;
+
+use bufdb_api::config::Compression;
+use bufdb_lib::db_error_s;
+use bufdb_lib::error::Result;
+use bufdb_storage::KeyComparator;
+use bufdb_storage::KeyCreator;
+use bufdb_storage::MergeOperator;
+use bufdb_storage::SDatabaseConfig;
+use bufdb_storage::entry::BufferEntry;
+use bufdb_storage::entry::Entry;
+
+use crate::arena::SafeArena;
+use crate::arena::StoreId;
+use crate::cursor::IDXCursor;
+use crate::cursor::PKCursor;
+use crate::suffix::append_suffix;
+use crate::suffix::reset_suffix;
+use crate::suffix::unwrap_suffix;
+
+struct IndexListener<'a> {
+    arena: SafeArena,
+    idb: StoreId,
+    creator: Arc<dyn KeyCreator + 'a>,
+    on_put: fn (&Self, &BufferEntry, &BufferEntry) -> Result<()>,
+    on_delete: fn (&Self, &BufferEntry, &BufferEntry) -> Result<()>,
+}
+
+impl <'a> IndexListener<'a> {
+    fn new<G: KeyCreator + 'a>(arena: SafeArena, idb: StoreId, creator: G) -> Self {
+        let unique = arena.unique(idb);
+        let creator = Arc::new(creator);
+
+        Self {
+            arena,
+            idb,
+            creator,
+            on_put: if unique { Self::put_pk } else { Self::put_idx },
+            on_delete: if unique { Self::delete_pk } else { Self::delete_idx },
+        }
+    }
+
+    fn init(&self, pdb: StoreId) -> Result<()> {
+        if self.arena.is_empty(self.idb) {
+            if self.arena.unique(self.idb) {
+                self.init_pk(pdb)
+            } else {
+                self.init_idx(pdb)
+            }
+        } else {
+            Ok(())
+        }
+    }
+
+    fn init_pk(&self, pdb: StoreId) -> Result<()> {
+        for (key, data) in self.arena.range_all(pdb)? {
+            let data = BufferEntry::from(data);
+            if let Some(skey) = self.creator.create_key(&key, &data)? {
+                self.arena.put(self.idb, &skey, &key)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn init_idx(&self, pdb: StoreId) -> Result<()> {
+        let mut id = 0u32;
+
+        for (key, data) in self.arena.range_all(pdb)? {
+            let data = BufferEntry::from(data);
+            if let Some(skey) = self.creator.create_key(&key, &data)? {
+                id += 1;
+                let skey = append_suffix(skey, id)?;
+                self.arena.put(self.idb, &skey, &key)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn put(&self, key: &BufferEntry, data: &BufferEntry) -> Result<()> {
+        let put_fn = &self.on_put;
+        put_fn(self, key, data)
+    }
+
+    fn put_pk(&self, key: &BufferEntry, data: &BufferEntry) -> Result<()> {
+        if let Some(ref skey) = self.creator.create_key(key, data)? {
+            self.arena.put(self.idb, skey, key)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn put_idx(&self, key: &BufferEntry, data: &BufferEntry) -> Result<()> {
+        if let Some(skey) = self.creator.create_key(key, data)? {
+            let len = skey.size();
+            let skey = append_suffix(skey, 0)?;
+            let s_slice = skey.left(len)?;
+
+            let order = {
+                let mut found = 1u32;
+                for (n_skey, _) in self.arena.range_from(self.idb, &skey)? {
+                    let (n_slice, n) = unwrap_suffix(&n_skey)?;
+                    if n_slice == s_slice {
+                        found = n + 1;
+                    }
+                    break;
+                }
+                found
+            };
+
+            let skey = reset_suffix(skey, order)?;
+            self.arena.put(self.idb, &skey, key)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn delete(&self, key: &BufferEntry, data: &BufferEntry) -> Result<()> {
+        let del_fn = &self.on_delete;
+        del_fn(self, key, data)
+    }
+
+    fn delete_pk(&self, key: &BufferEntry, data: &BufferEntry) -> Result<()> {
+        if let Some(skey) = self.creator.create_key(key, data)? {
+            self.arena.delete(self.idb, &skey);
+        }
+
+        Ok(())
+    }
+
+    fn delete_idx(&self, key: &BufferEntry, data: &BufferEntry) -> Result<()> {
+        if let Some(skey) = self.creator.create_key(key, data)? {
+            let len = skey.size();
+            let skey = append_suffix(skey, 0)?;
+            let slice = skey.left(len)?;
+
+            let mut found: Option<BufferEntry> = None;
+            let mut order = u32::MAX;
+            for (n_key, n_data) in self.arena.range_from(self.idb, &skey)? {
+                let (n_slice, n) = unwrap_suffix(&n_key)?;
+                if n >= order || slice != n_slice {
+                    break;
+                }
+
+                if *key == BufferEntry::from(n_data) {
+                    found = Some(n_key);
+                    break;
+                }
+
+                order = n;
+            }
+
+            if let Some(ref s_key) = found {
+                self.arena.delete(self.idb, s_key);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl <'a> Debug for IndexListener<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IndexListener").field("idb", &self.arena.name(self.idb)).finish()
+    }
+}
+
+#[derive(Debug)]
+pub struct PrimaryDatabase<'a> {
+    arena: SafeArena,
+    id: StoreId,
+    listeners: Arc<RwLock<Vec<IndexListener<'a>>>>,
+    _marker: PhantomData<&'a ()>,
+}
+
+macro_rules! lock_db {
+    ($db: ident) => {
+        $db.listeners.read().unwrap()
+    };
+    ($db: ident => write) => {
+        $db.listeners.write().unwrap()
+    }
+}
+
+impl <'a> PrimaryDatabase<'a> {
+    /// `comparator` is accepted for parity with the other backends but isn't
+    /// consulted: keys are ordered by the arena store's own `BTreeMap`
+    /// ordering, which every call site already constructs its keys to
+    /// agree with.
+    pub fn new<C: KeyComparator>(arena: SafeArena, name: &str, readonly: bool, temporary: bool, _comparator: C, merge_operator: Option<MergeOperator>, compression: Compression) -> Result<Self> {
+        let id = arena.create(name, readonly, temporary, true, merge_operator, compression);
+
+        Ok(Self {
+            arena,
+            id,
+            listeners: Arc::new(RwLock::new(Vec::new())),
+            _marker: PhantomData,
+        })
+    }
+
+    pub(crate) fn id(&self) -> StoreId {
+        self.id
+    }
+
+    pub(crate) fn arena(&self) -> &SafeArena {
+        &self.arena
+    }
+
+    pub(crate) fn name(&self) -> String {
+        self.arena.name(self.id)
+    }
+
+    fn register_listener<G: KeyCreator + 'a>(&self, idb: StoreId, creator: G) -> Result<()> {
+        let mut listeners = lock_db!(self => write);
+
+        let listener = IndexListener::new(self.arena.clone(), idb, creator);
+        listener.init(self.id)?;
+
+        listeners.push(listener);
+
+        Ok(())
+    }
+}
+
+impl <'a> bufdb_storage::Database<'a, PKCursor<'a>> for PrimaryDatabase<'a> {
+    type TRANSACTION = crate::transaction::SafeTransaction<'a>;
+
+    fn count(&self) -> Result<usize> {
+        Ok(self.arena.count(self.id))
+    }
+
+    fn put(&self, key: &BufferEntry, data: &BufferEntry) -> Result<()> {
+        let listeners = lock_db!(self);
+
+        if !listeners.is_empty() {
+            if let Some(raw_data) = self.arena.get(self.id, key)? {
+                if data != &raw_data {
+                    for listener in listeners.iter() {
+                        listener.delete(key, &raw_data)?;
+                    }
+                }
+            }
+        }
+
+        self.arena.put(self.id, key, data)?;
+
+        if !listeners.is_empty() {
+            for listener in listeners.iter() {
+                listener.put(key, data)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn get(&self, key: &BufferEntry) -> Result<Option<BufferEntry>> {
+        self.arena.get(self.id, key)
+    }
+
+    fn delete(&self, key: &BufferEntry) -> Result<()> {
+        let listeners = lock_db!(self);
+
+        if listeners.is_empty() {
+            self.arena.delete(self.id, key);
+        } else if let Some(data) = self.arena.get(self.id, key)? {
+            for listener in listeners.iter() {
+                listener.delete(key, &data)?;
+            }
+
+            self.arena.delete(self.id, key);
+        }
+
+        Ok(())
+    }
+
+    fn delete_exist(&self, key: &BufferEntry) -> Result<bool> {
+        if let Some(data) = self.arena.get(self.id, key)? {
+            let listeners = lock_db!(self);
+
+            for listener in listeners.iter() {
+                listener.delete(key, &data)?;
+            }
+
+            self.arena.delete(self.id, key);
+
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn open_cursor(&'a self) -> Result<PKCursor<'a>> {
+        PKCursor::new(self.arena.clone(), self.id)
+    }
+
+    /// Rejects opening a transaction while this database has secondary
+    /// indexes registered: [`SafeTransaction::commit`] flushes its staged
+    /// ops straight to the backing arena, with no fan-out to
+    /// [`IndexListener`] (unlike [`Self::put`]/[`Self::delete`]/[`Self::merge`]
+    /// above), which would silently desync every secondary index on this
+    /// table.
+    fn begin_transaction(&'a self) -> Result<Self::TRANSACTION> {
+        if !lock_db!(self).is_empty() {
+            return Err(db_error_s!(write, Configuration => "cannot open a transaction on a database with secondary indexes: transaction commit does not yet fan writes out to them"));
+        }
+
+        Ok(crate::transaction::SafeTransaction::new(self.arena.clone(), self.id))
+    }
+
+    fn merge(&self, key: &BufferEntry, operand: &BufferEntry) -> Result<()> {
+        let listeners = lock_db!(self);
+
+        if listeners.is_empty() {
+            self.arena.merge(self.id, key, operand)
+        } else {
+            let raw_data = self.arena.get(self.id, key)?;
+            self.arena.merge(self.id, key, operand)?;
+            let merged = self.arena.get(self.id, key)?;
+
+            if raw_data != merged {
+                if let Some(ref raw_data) = raw_data {
+                    for listener in listeners.iter() {
+                        listener.delete(key, raw_data)?;
+                    }
+                }
+                if let Some(ref merged) = merged {
+                    for listener in listeners.iter() {
+                        listener.put(key, merged)?;
+                    }
+                }
+            }
+
+            Ok(())
+        }
+    }
+}
+
+impl <'a> Display for PrimaryDatabase<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Debug)]
+pub struct SecondaryDatabase<'a> {
+    arena: SafeArena,
+    id: StoreId,
+    parent: StoreId,
+    listeners: Arc<RwLock<Vec<IndexListener<'a>>>>,
+    _marker: PhantomData<&'a ()>,
+}
+
+impl <'a> SecondaryDatabase<'a> {
+    pub fn new<C: KeyComparator, G: KeyCreator + 'a>(p_database: &PrimaryDatabase<'a>, name: &str, config: SDatabaseConfig<C, G>) -> Result<Self> {
+        let arena = p_database.arena().clone();
+        let parent = p_database.id();
+
+        let readonly = arena.readonly(parent);
+        let temporary = config.temporary || arena.temporary(parent);
+
+        let id = arena.create_secondary(parent, name, readonly, temporary, config.unique, config.merge_operator, config.compression);
+
+        p_database.register_listener(id, config.creator)?;
+
+        Ok(Self {
+            arena,
+            id,
+            parent,
+            listeners: p_database.listeners.clone(),
+            _marker: PhantomData,
+        })
+    }
+
+    pub(crate) fn name(&self) -> String {
+        self.arena.name(self.id)
+    }
+
+    pub(crate) fn parent_name(&self) -> String {
+        self.arena.name(self.parent)
+    }
+}
+
+impl <'a> Drop for SecondaryDatabase<'a> {
+    fn drop(&mut self) {
+        let mut listeners = self.listeners.write().unwrap();
+        listeners.retain(|x| x.idb != self.id);
+    }
+}
+
+impl <'a> bufdb_storage::Database<'a, IDXCursor<'a>> for SecondaryDatabase<'a> {
+    type TRANSACTION = crate::transaction::SafeTransaction<'a>;
+
+    fn count(&self) -> Result<usize> {
+        Ok(self.arena.count(self.id))
+    }
+
+    fn put(&self, key: &BufferEntry, data: &BufferEntry) -> Result<()> {
+        self.arena.put(self.id, key, data)
+    }
+
+    fn get(&self, key: &BufferEntry) -> Result<Option<BufferEntry>> {
+        self.arena.get(self.id, key)
+    }
+
+    fn delete(&self, key: &BufferEntry) -> Result<()> {
+        self.arena.delete(self.id, key);
+        Ok(())
+    }
+
+    fn delete_exist(&self, key: &BufferEntry) -> Result<bool> {
+        let data = self.arena.get(self.id, key)?;
+        if data.is_some() {
+            self.arena.delete(self.id, key);
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn open_cursor(&'a self) -> Result<IDXCursor<'a>> {
+        IDXCursor::new(self.arena.clone(), self.parent, self.id)
+    }
+
+    fn begin_transaction(&'a self) -> Result<Self::TRANSACTION> {
+        Ok(crate::transaction::SafeTransaction::new_secondary(self.arena.clone(), self.id, self.parent))
+    }
+
+    fn merge(&self, key: &BufferEntry, operand: &BufferEntry) -> Result<()> {
+        self.arena.merge(self.id, key, operand)
+    }
+}
+
+impl <'a> Display for SecondaryDatabase<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
@@ -0,0 +1,88 @@
+use bufdb_api::error::Result;
+use bufdb_storage::DatabaseConfig;
+use bufdb_storage::Environment;
+use bufdb_storage::EnvironmentConfig;
+use bufdb_storage::KeyComparator;
+use bufdb_storage::KeyCreator;
+use bufdb_storage::SDatabaseConfig;
+
+use crate::arena::SafeArena;
+use crate::cursor::IDXCursor;
+use crate::cursor::PKCursor;
+use crate::database::PrimaryDatabase;
+use crate::database::SecondaryDatabase;
+use crate::snapshot::SafeEnvSnapshot;
+
+/// An [`Environment`] whose databases live only in memory, backed by one
+/// shared `id-arena` [`SafeArena`] rather than a `HashMap` of handles.
+/// `config.dir` is kept for diagnostic purposes (e.g. `Display` on the
+/// owning `Instance`) but nothing is ever read from or written to it.
+pub struct SafeEnv {
+    readonly: bool,
+    temporary: bool,
+    arena: SafeArena,
+}
+
+impl SafeEnv {
+    pub fn readonly(&self) -> bool {
+        self.readonly
+    }
+
+    pub fn temporary(&self) -> bool {
+        self.temporary
+    }
+}
+
+impl <'a> Environment<'a> for SafeEnv {
+    type CURSOR = PKCursor<'a>;
+    type SCUROSR = IDXCursor<'a>;
+    type DATABASE = PrimaryDatabase<'a>;
+    type SDATABASE = SecondaryDatabase<'a>;
+    type SNAPSHOT = SafeEnvSnapshot;
+
+    fn new(config: EnvironmentConfig) -> Result<Self> {
+        Ok(Self {
+            readonly: config.readonly,
+            temporary: config.temporary,
+            arena: SafeArena::new(),
+        })
+    }
+
+    fn create_database<C: KeyComparator>(&self, name: &str, config: DatabaseConfig<C>) -> Result<Self::DATABASE> {
+        PrimaryDatabase::new(self.arena.clone(), name, config.readonly, config.temporary, config.comparator, config.merge_operator, config.compression)
+    }
+
+    fn create_secondary_database<C: KeyComparator, G: KeyCreator + 'a>(&self, database: &Self::DATABASE, name: &str, config: SDatabaseConfig<C, G>) -> Result<Self::SDATABASE> {
+        SecondaryDatabase::new(database, name, config)
+    }
+
+    fn drop_database(&self, name: &str) -> Result<()> {
+        self.arena.drop_database(name);
+        Ok(())
+    }
+
+    fn drop_secondary_database(&self, _name: &str) -> Result<()> {
+        Ok(())
+    }
+
+    fn truncate_database(&self, name: &str) -> Result<()> {
+        self.arena.truncate_database(name);
+        Ok(())
+    }
+
+    fn rename_database(&self, raw_name: &str, new_name: &str) -> Result<()> {
+        self.arena.rename_database(raw_name, new_name);
+        Ok(())
+    }
+
+    fn snapshot(&'a self) -> Result<Self::SNAPSHOT> {
+        Ok(SafeEnvSnapshot::new(&self.arena))
+    }
+
+    /// Always fails: a [`SafeEnv`] keeps no on-disk data for a checkpoint to
+    /// copy. Use [`Environment::snapshot`] for a consistent in-process read
+    /// view instead.
+    fn checkpoint(&self, _target: &std::path::Path) -> Result<()> {
+        Err(bufdb_lib::db_error_s!(write, Unsupported => "SafeEnv has no on-disk data to checkpoint"))
+    }
+}
@@ -0,0 +1,43 @@
+//! Dependency-free, in-process `StorageEngine` backend.
+//!
+//! Every primary and secondary database is a slot in one `id-arena`
+//! `Arena<DatabaseStore>` shared by the owning [`env::SafeEnv`]. Unlike
+//! `bufdb_mem`, which keys databases by name in a `HashMap<String,
+//! Arc<MemImpl>>`, this backend hands databases a stable arena `Id`:
+//! `rename_database` becomes a pure name-to-id rebinding rather than a
+//! `HashMap` move, and outstanding handles built from an `Id` stay valid
+//! across a rename. Meant for embedders who want `bufdb_mem`'s zero
+//! system-dependency footprint but need rename/drop semantics that don't
+//! invalidate handles held elsewhere.
+
+use bufdb_storage::StorageEngine;
+use cursor::IDXCursor;
+use cursor::PKCursor;
+use database::PrimaryDatabase;
+use database::SecondaryDatabase;
+use env::SafeEnv;
+
+pub mod env;
+pub mod database;
+pub mod cursor;
+pub mod snapshot;
+pub(crate) mod arena;
+pub(crate) mod suffix;
+pub(crate) mod transaction;
+
+#[derive(Debug, Clone, Copy)]
+pub struct SafeEngine {}
+
+impl <'a> StorageEngine<'a> for SafeEngine {
+    type CURSOR = PKCursor<'a>;
+    type SCUROSR = IDXCursor<'a>;
+
+    type DATABASE = PrimaryDatabase<'a>;
+    type SDATABASE = SecondaryDatabase<'a>;
+
+    type ENVIRONMENT = SafeEnv;
+
+    fn name(&self) -> &str {
+        "Safe Engine"
+    }
+}
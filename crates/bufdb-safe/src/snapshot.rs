@@ -0,0 +1,46 @@
+use bufdb_lib::error::Result;
+
+use crate::arena::SafeArena;
+use crate::cursor::IDXCursor;
+use crate::cursor::PKCursor;
+use crate::database::PrimaryDatabase;
+use crate::database::SecondaryDatabase;
+use crate::env::SafeEnv;
+
+/// A point-in-time read view over every database open in a [`SafeEnv`],
+/// taken by [`bufdb_storage::Environment::snapshot`].
+///
+/// Every store is cloned once, up front, into a fresh [`SafeArena`], so
+/// every cursor opened from the same `SafeEnvSnapshot` reads the data as it
+/// stood at that single instant, regardless of writes the live environment
+/// takes afterward.
+pub struct SafeEnvSnapshot {
+    arena: SafeArena,
+}
+
+impl SafeEnvSnapshot {
+    pub(crate) fn new(source: &SafeArena) -> Self {
+        let arena = SafeArena::new();
+
+        for store in source.frozen_stores() {
+            arena.restore(store);
+        }
+
+        Self { arena }
+    }
+
+    fn frozen(&self, name: &str) -> crate::arena::StoreId {
+        self.arena.lookup(name)
+            .unwrap_or_else(|| panic!("database '{}' was opened after this snapshot was taken", name))
+    }
+}
+
+impl <'a> bufdb_storage::Snapshot<'a, SafeEnv> for SafeEnvSnapshot {
+    fn open_cursor(&'a self, database: &'a PrimaryDatabase<'a>) -> Result<PKCursor<'a>> {
+        PKCursor::new(self.arena.clone(), self.frozen(&database.name()))
+    }
+
+    fn open_secondary_cursor(&'a self, database: &'a SecondaryDatabase<'a>) -> Result<IDXCursor<'a>> {
+        IDXCursor::new(self.arena.clone(), self.frozen(&database.parent_name()), self.frozen(&database.name()))
+    }
+}
@@ -0,0 +1,31 @@
+use std::future::Future;
+
+use bufdb_api::error::Result;
+
+use crate::entry::BufferEntry;
+
+/// Async counterpart of [`crate::Database`].
+///
+/// Mirrors the primary/secondary cursor split of the synchronous API, but every
+/// operation returns a future instead of blocking the calling thread.
+pub trait AsyncDatabase {
+    fn count(&self) -> impl Future<Output = Result<usize>> + Send;
+    fn put(&self, key: &BufferEntry, data: &BufferEntry) -> impl Future<Output = Result<()>> + Send;
+    fn get(&self, key: &BufferEntry) -> impl Future<Output = Result<Option<BufferEntry>>> + Send;
+    fn delete(&self, key: &BufferEntry) -> impl Future<Output = Result<()>> + Send;
+    fn delete_exist(&self, key: &BufferEntry) -> impl Future<Output = Result<bool>> + Send;
+}
+
+/// Async counterpart of [`crate::StorageEngine`].
+///
+/// Following the split used by network clients elsewhere (a `SyncClient` that
+/// sends-and-confirms versus an `AsyncClient` that fires without blocking),
+/// this trait lets an engine offer a non-blocking facade over the same
+/// on-disk format, so bufdb can be embedded in tokio-based services without
+/// stalling the executor on blocking FFI calls.
+pub trait AsyncStorageEngine : Copy + Clone {
+    type DATABASE: AsyncDatabase;
+    type SDATABASE: AsyncDatabase;
+
+    fn name(&self) -> &str;
+}
@@ -3,10 +3,13 @@ use std::sync::Arc;
 use std::sync::RwLock;
 
 use bufdb_lib::config::CacheConfig;
-use chrono::Local;
 
+/// Wall-clock timestamp helper, only available with the `std` feature since it
+/// relies on `chrono`. `no_std` consumers of the buffer codecs in
+/// [`crate::packed_int`] never need it.
+#[cfg(feature = "std")]
 pub fn now() -> i64 {
-    Local::now().timestamp_millis()
+    chrono::Local::now().timestamp_millis()
 }
 
 #[macro_export]
@@ -0,0 +1,362 @@
+//! Type-erased wrapper around a [`KeyComparator`].
+//!
+//! A database is opened with a concrete `C: KeyComparator`, but its cursors
+//! are plain (non-generic) structs, so they can't hold that `C` directly.
+//! [`ErasedComparator`] captures the comparator's behavior behind a closure
+//! once, at open time, so a cursor can still ask it whether two keys are
+//! equal.
+
+use std::cmp::Ordering;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use bufdb_api::error::ErrorKind;
+use bufdb_api::error::Result;
+
+use crate::KeyComparator;
+use crate::entry::BufferEntry;
+use crate::entry::Entry;
+use crate::io::Input;
+
+#[derive(Clone)]
+pub struct ErasedComparator {
+    can_differ_bytes_equal: bool,
+    compare_fn: Arc<dyn Fn(&BufferEntry, &BufferEntry) -> Result<Ordering> + Send + Sync>,
+}
+
+impl ErasedComparator {
+    pub fn new<C: KeyComparator + Send + Sync + 'static>(comparator: Arc<C>) -> Self {
+        let can_differ_bytes_equal = comparator.can_differ_bytes_equal();
+        let compare_fn = Arc::new(move |a: &BufferEntry, b: &BufferEntry| comparator.compare(a, b));
+
+        Self { can_differ_bytes_equal, compare_fn }
+    }
+
+    pub fn can_differ_bytes_equal(&self) -> bool {
+        self.can_differ_bytes_equal
+    }
+
+    pub fn compare(&self, a: &BufferEntry, b: &BufferEntry) -> Result<Ordering> {
+        (self.compare_fn)(a, b)
+    }
+
+    /// Whether `a` and `b` are equal under this comparator. Falls back to a
+    /// raw byte comparison unless the comparator opted into
+    /// [`KeyComparator::can_differ_bytes_equal`], so the common case stays on
+    /// the cheap path.
+    pub fn eq(&self, a: &BufferEntry, b: &BufferEntry) -> bool {
+        if self.can_differ_bytes_equal {
+            self.compare(a, b).map(|ord| ord == Ordering::Equal).unwrap_or(false)
+        } else {
+            a == b
+        }
+    }
+}
+
+/// Orders keys as unsigned 64-bit integers, encoded big-endian (see
+/// [`crate::io::Output::write_u64`]). Analogous to LMDB's `compare_uint64`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct U64KeyComparator;
+
+impl KeyComparator for U64KeyComparator {
+    fn compare<T: Entry>(&self, key1: &T, key2: &T) -> Result<Ordering> {
+        let v1 = key1.as_input().read_u64()?;
+        let v2 = key2.as_input().read_u64()?;
+
+        Ok(v1.cmp(&v2))
+    }
+}
+
+/// Orders keys as signed 64-bit integers, encoded big-endian. Unlike a plain
+/// byte-wise compare of the two's-complement bytes, this decodes the value
+/// first so negative keys sort before positive ones.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct I64KeyComparator;
+
+impl KeyComparator for I64KeyComparator {
+    fn compare<T: Entry>(&self, key1: &T, key2: &T) -> Result<Ordering> {
+        let v1 = key1.as_input().read_i64()?;
+        let v2 = key2.as_input().read_i64()?;
+
+        Ok(v1.cmp(&v2))
+    }
+}
+
+/// Orders keys by length first, then lexicographically within equal
+/// lengths, so variable-width textual encodings of numbers (`"9"` vs `"10"`)
+/// sort the way the numbers do rather than the way a plain byte-wise compare
+/// (as used by `bufdb::table::comparator::StringKeyComparator`) would.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LengthAwareKeyComparator;
+
+impl KeyComparator for LengthAwareKeyComparator {
+    fn compare<T: Entry>(&self, key1: &T, key2: &T) -> Result<Ordering> {
+        let (s1, s2) = (key1.slice(), key2.slice());
+
+        Ok(s1.len().cmp(&s2.len()).then_with(|| s1.cmp(s2)))
+    }
+}
+
+/// Orders fixed-width 32-byte keys (e.g. SHA-256 digests) byte-wise.
+/// Analogous to LMDB's `compare_hash32`; mainly useful to name the key shape
+/// at the call site rather than passing a bare byte-wise comparator.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Hash32KeyComparator;
+
+impl KeyComparator for Hash32KeyComparator {
+    fn compare<T: Entry>(&self, key1: &T, key2: &T) -> Result<Ordering> {
+        Ok(key1.slice().cmp(key2.slice()))
+    }
+}
+
+/// A reversible, order-preserving key encoding that a backend's
+/// `PKComparator`/`IDXComparator` can hold alongside their `KeyComparator`,
+/// applying it consistently at insert time (`encode`) and inside
+/// `Comparator::compare` (`decode`/`compare_encoded`). See
+/// [`BigEndianU64Transform`], [`SignFlipI64Transform`] and
+/// [`DescendingTransform`] for the built-in collations.
+///
+/// Implementors must be a pure function of their two arguments:
+/// `Comparator::compare` is invoked by leveldb on arbitrary, non-sequential
+/// pairs of keys (binary search, compaction), from possibly multiple
+/// threads, never as a single ordered pass. [`PrefixCompressTransform`] is a
+/// related utility that depends on sequential-pass state and therefore
+/// deliberately does *not* implement this trait.
+pub trait KeyTransform: Send + Sync {
+    /// Encodes `key` into its on-disk, sort-order-preserving form.
+    fn encode(&self, key: &BufferEntry) -> Result<BufferEntry>;
+
+    /// Decodes an on-disk key back into its original form.
+    fn decode(&self, encoded: &BufferEntry) -> Result<BufferEntry>;
+
+    /// Compares two already-encoded keys. The default assumes `encode`'s
+    /// output sorts the same as a raw byte-wise compare, which holds for
+    /// every transform below; override it only if a custom encoding needs
+    /// something smarter than that.
+    fn compare_encoded(&self, a: &BufferEntry, b: &BufferEntry) -> Result<Ordering> {
+        Ok(a.slice().cmp(b.slice()))
+    }
+}
+
+/// Declares a key as a big-endian, fixed-width `u64` (see
+/// [`crate::io::Output::write_u64`]). Big-endian bytes already sort the
+/// same as the integer they encode, so this transform is the identity; it
+/// exists to name the key shape at the call site and to catch a
+/// wrong-width key early, rather than to actually reorder bytes.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct BigEndianU64Transform;
+
+impl KeyTransform for BigEndianU64Transform {
+    fn encode(&self, key: &BufferEntry) -> Result<BufferEntry> {
+        if key.size() != 8 {
+            return Err(ErrorKind::DataType.into());
+        }
+
+        Ok(key.clone())
+    }
+
+    fn decode(&self, encoded: &BufferEntry) -> Result<BufferEntry> {
+        Ok(encoded.clone())
+    }
+}
+
+/// Declares a key as a big-endian, fixed-width `i64`. Unlike an unsigned
+/// integer, the raw two's-complement bytes of a negative value compare
+/// greater than a positive one (the sign bit is the top bit), so this flips
+/// that bit on the way in and out, mapping the signed range onto the
+/// unsigned one its bytes already sort correctly for.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SignFlipI64Transform;
+
+impl SignFlipI64Transform {
+    const SIGN_BIT: u8 = 0x80;
+
+    fn flip(key: &BufferEntry) -> Result<BufferEntry> {
+        if key.size() != 8 {
+            return Err(ErrorKind::DataType.into());
+        }
+
+        let mut bytes = key.slice().to_vec();
+        bytes[0] ^= Self::SIGN_BIT;
+        Ok(BufferEntry::from(bytes))
+    }
+}
+
+impl KeyTransform for SignFlipI64Transform {
+    fn encode(&self, key: &BufferEntry) -> Result<BufferEntry> {
+        Self::flip(key)
+    }
+
+    fn decode(&self, encoded: &BufferEntry) -> Result<BufferEntry> {
+        Self::flip(encoded)
+    }
+}
+
+/// Reverses another transform's sort order by bitwise-complementing its
+/// encoded bytes, so descending keys can reuse an ascending encoding (e.g.
+/// [`BigEndianU64Transform`]) instead of a hand-written mirror of it.
+pub struct DescendingTransform<T>(pub T);
+
+impl <T: KeyTransform> KeyTransform for DescendingTransform<T> {
+    fn encode(&self, key: &BufferEntry) -> Result<BufferEntry> {
+        let encoded = self.0.encode(key)?;
+        Ok(BufferEntry::from(encoded.slice().iter().map(|b| !b).collect::<Vec<u8>>()))
+    }
+
+    fn decode(&self, encoded: &BufferEntry) -> Result<BufferEntry> {
+        let flipped = BufferEntry::from(encoded.slice().iter().map(|b| !b).collect::<Vec<u8>>());
+        self.0.decode(&flipped)
+    }
+}
+
+/// Front-codes a sequence of sorted keys, storing only the length of the
+/// prefix shared with the previously encoded (or decoded) key plus the
+/// differing suffix, restoring the full key from that prefix on read.
+///
+/// This deliberately does **not** implement [`KeyTransform`], and so can't
+/// be passed to `PKComparator::with_transform`/`IDXComparator::with_transform`:
+/// those feed a backend's `leveldb::comparator::Comparator::compare`, which
+/// is invoked on arbitrary, non-sequential pairs of keys (binary search,
+/// compaction) from possibly multiple threads. `encode`/`decode` here are
+/// only correct when driven, single-threaded, through the same ascending
+/// sequence of keys they were written in — each side tracks its own
+/// "previous key" state, so an out-of-order or concurrent call reconstructs
+/// garbage from whatever unrelated key happened to come before it. Use this
+/// directly for a one-shot sequential pass (e.g. compacting a sorted batch
+/// to disk or streaming it back out), not as a general key collation.
+pub struct PrefixCompressTransform {
+    prev_encode: Mutex<Option<BufferEntry>>,
+    prev_decode: Mutex<Option<BufferEntry>>,
+}
+
+impl PrefixCompressTransform {
+    pub fn new() -> Self {
+        Self { prev_encode: Mutex::new(None), prev_decode: Mutex::new(None) }
+    }
+
+    fn common_prefix(a: &[u8], b: &[u8]) -> usize {
+        a.iter().zip(b.iter()).take_while(|(x, y)| x == y).count()
+    }
+
+    /// Writes `[prefix_len: u32][suffix bytes]`. Must be called with keys in
+    /// the same ascending order they'll later be [`Self::decode`]d in.
+    pub fn encode(&self, key: &BufferEntry) -> Result<BufferEntry> {
+        let mut prev = self.prev_encode.lock().unwrap();
+        let prefix_len = prev.as_ref().map(|p| Self::common_prefix(p.slice(), key.slice())).unwrap_or(0);
+
+        let mut encoded = Vec::with_capacity(4 + key.size() - prefix_len);
+        encoded.extend_from_slice(&(prefix_len as u32).to_be_bytes());
+        encoded.extend_from_slice(&key.slice()[prefix_len..]);
+
+        *prev = Some(key.clone());
+        Ok(BufferEntry::from(encoded))
+    }
+
+    /// Reverses [`Self::encode`]. Must be called with entries in the same
+    /// order they were encoded in.
+    pub fn decode(&self, encoded: &BufferEntry) -> Result<BufferEntry> {
+        let mut prev = self.prev_decode.lock().unwrap();
+
+        let bytes = encoded.slice();
+        if bytes.len() < 4 {
+            return Err(ErrorKind::DataType.into());
+        }
+
+        let prefix_len = u32::from_be_bytes(bytes[..4].try_into().unwrap()) as usize;
+        let suffix = &bytes[4..];
+
+        let mut key = match prev.as_ref() {
+            Some(p) if prefix_len <= p.size() => p.slice()[..prefix_len].to_vec(),
+            Some(_) => return Err(ErrorKind::DataType.into()),
+            None if prefix_len == 0 => Vec::new(),
+            None => return Err(ErrorKind::DataType.into()),
+        };
+        key.extend_from_slice(suffix);
+
+        let key = BufferEntry::from(key);
+        *prev = Some(key.clone());
+        Ok(key)
+    }
+}
+
+impl Default for PrefixCompressTransform {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use super::BigEndianU64Transform;
+    use super::DescendingTransform;
+    use super::KeyTransform;
+    use super::PrefixCompressTransform;
+    use super::SignFlipI64Transform;
+    use crate::entry::BufferEntry;
+
+    #[test]
+    fn test_sign_flip_orders_negative_before_positive() {
+        let transform = SignFlipI64Transform;
+
+        let neg = BufferEntry::from((-1i64).to_be_bytes().to_vec());
+        let pos = BufferEntry::from(1i64.to_be_bytes().to_vec());
+
+        let enc_neg = transform.encode(&neg).unwrap();
+        let enc_pos = transform.encode(&pos).unwrap();
+
+        assert_eq!(Ordering::Less, transform.compare_encoded(&enc_neg, &enc_pos).unwrap());
+        assert_eq!(neg, transform.decode(&enc_neg).unwrap());
+    }
+
+    #[test]
+    fn test_big_endian_u64_is_identity() {
+        let transform = BigEndianU64Transform;
+        let key = BufferEntry::from(42u64.to_be_bytes().to_vec());
+
+        let encoded = transform.encode(&key).unwrap();
+        assert_eq!(key, encoded);
+        assert_eq!(key, transform.decode(&encoded).unwrap());
+    }
+
+    #[test]
+    fn test_descending_reverses_order() {
+        let transform = DescendingTransform(BigEndianU64Transform);
+
+        let low = BufferEntry::from(1u64.to_be_bytes().to_vec());
+        let high = BufferEntry::from(2u64.to_be_bytes().to_vec());
+
+        let enc_low = transform.encode(&low).unwrap();
+        let enc_high = transform.encode(&high).unwrap();
+
+        assert_eq!(Ordering::Greater, transform.compare_encoded(&enc_low, &enc_high).unwrap());
+        assert_eq!(low, transform.decode(&enc_low).unwrap());
+    }
+
+    #[test]
+    fn test_prefix_compress_round_trips_a_sorted_sequence() {
+        let encoder = PrefixCompressTransform::new();
+        let decoder = PrefixCompressTransform::new();
+
+        let keys = vec!["apple", "applesauce", "banana"]
+            .into_iter()
+            .map(|s| BufferEntry::from(s.as_bytes().to_vec()))
+            .collect::<Vec<_>>();
+
+        let mut second_encoded = None;
+        for (i, key) in keys.iter().enumerate() {
+            let encoded = encoder.encode(key).unwrap();
+            let decoded = decoder.decode(&encoded).unwrap();
+            assert_eq!(key, &decoded);
+
+            if i == 1 {
+                second_encoded = Some(encoded);
+            }
+        }
+
+        // The shared "apple" prefix means the second key's encoding is
+        // shorter than the key itself.
+        assert!(second_encoded.unwrap().size() < keys[1].size());
+    }
+}
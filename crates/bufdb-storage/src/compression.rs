@@ -0,0 +1,54 @@
+use bufdb_api::config::Compression;
+use bufdb_lib::db_error;
+use bufdb_lib::db_error_s;
+use bufdb_lib::error::Result;
+
+use crate::entry::BufferEntry;
+use crate::entry::Entry;
+
+/// One-byte tag prepended to every stored value identifying the codec it
+/// was written with, so values written under a different [`Compression`]
+/// setting stay readable. A value stored without this tag is not
+/// recognized as such: [`decompress`] reads its first byte as the tag.
+fn tag(codec: Compression) -> u8 {
+    match codec {
+        Compression::None => 0,
+        Compression::Snappy => 1,
+    }
+}
+
+/// Tags and, if `codec` calls for it, compresses `value` for storage.
+/// Applied to a database's value bytes only; keys are never compressed.
+pub fn compress(codec: Compression, value: &BufferEntry) -> Result<BufferEntry> {
+    let payload = match codec {
+        Compression::None => value.slice().to_vec(),
+        Compression::Snappy => snap::raw::Encoder::new().compress_vec(value.slice())
+            .map_err(|e| db_error!(write, Corruption => e))?,
+    };
+
+    let mut tagged = Vec::with_capacity(payload.len() + 1);
+    tagged.push(tag(codec));
+    tagged.extend_from_slice(&payload);
+
+    Ok(BufferEntry::from(tagged))
+}
+
+/// Reads the codec tag off `value` and decompresses the rest if needed,
+/// regardless of the database's current [`Compression`] setting — so a
+/// database that switches codecs can still read values written under the
+/// old one.
+pub fn decompress(value: &BufferEntry) -> Result<BufferEntry> {
+    let bytes = value.slice();
+    let (tag, payload) = bytes.split_first()
+        .ok_or_else(|| db_error_s!(read, Corruption => "compressed value is missing its codec tag"))?;
+
+    match *tag {
+        0 => Ok(BufferEntry::from(payload.to_vec())),
+        1 => {
+            let decompressed = snap::raw::Decoder::new().decompress_vec(payload)
+                .map_err(|e| db_error!(read, Corruption => e))?;
+            Ok(BufferEntry::from(decompressed))
+        },
+        _ => Err(db_error_s!(read, Corruption => "value has an unrecognized compression tag")),
+    }
+}
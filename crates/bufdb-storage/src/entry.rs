@@ -1,4 +1,5 @@
 use std::cmp::Ordering;
+use std::sync::Arc;
 
 use bufdb_api::error::ErrorKind;
 use bufdb_api::error::Result;
@@ -18,21 +19,62 @@ pub trait Entry : AsRef<[u8]> {
         self.off() == self.len()
     }
 
+    /// This entry's absolute starting offset within the larger buffer it
+    /// was decoded from, e.g. via [`crate::io::Located`]. An alias for
+    /// [`Self::off`] under streaming-decode terminology, so index builders
+    /// can record a decoded key's exact span without separately
+    /// bookkeeping offsets.
+    fn position(&self) -> usize {
+        self.off()
+    }
+
     fn slice(&self) -> &[u8];
 
     fn as_input(&self) -> BufferInput {
         BufferInput::new(self.slice())
     }
 
-    fn left(&self, n: usize) -> Result<SliceEntry> {
+    /// The leading `n` bytes of this entry, as an owned [`BufferEntry`].
+    /// [`BufferEntry::left`] overrides this to share its backing allocation
+    /// (an `Arc` bump), so prefer calling it on a `BufferEntry` directly on
+    /// the hot key-comparison path; this default falls back to copying
+    /// `self`'s bytes, since a generic `Entry` has no refcounted buffer to
+    /// share.
+    fn left(&self, n: usize) -> Result<BufferEntry> {
         if n > self.size() {
             Err(ErrorKind::OutOfBounds.into())
         } else {
-            Ok(SliceEntry::new_off(self.as_ref(), self.off(), n))
+            Ok(BufferEntry::new(self.slice().to_vec(), 0, n))
         }
     }
 }
 
+/// Cursor over a buffer that can be advanced without copying, along the
+/// lines of the `bytes` crate's `Buf`. Implemented by the read side of the
+/// `Entry` types; see [`BufMut`] for the write side.
+pub trait Buf {
+    /// Bytes left to read.
+    fn remaining(&self) -> usize;
+
+    /// The currently readable slice, starting at the cursor position.
+    fn chunk(&self) -> &[u8];
+
+    /// Advances the read cursor by `n` bytes. Panics if `n` exceeds
+    /// [`Self::remaining`].
+    fn advance(&mut self, n: usize);
+}
+
+/// A growable byte sink, along the lines of the `bytes` crate's `BufMut`.
+/// See [`Buf`] for the read side.
+pub trait BufMut {
+    /// Appends `src` to the end of the buffer.
+    fn put_slice(&mut self, src: &[u8]);
+
+    /// Ensures at least `additional` more bytes can be appended without
+    /// reallocating.
+    fn reserve(&mut self, additional: usize);
+}
+
 pub fn compare<K1: Entry, K2: Entry>(key1: &K1, key2: &K2) -> Ordering {
     let data1 = key1.slice();
     let data2 = key2.slice();
@@ -56,18 +98,27 @@ pub fn compare<K1: Entry, K2: Entry>(key1: &K1, key2: &K2) -> Ordering {
     }
 }
 
+/// Immutable, reference-counted byte buffer with an `off`/`len` window onto
+/// it, along the lines of the `bytes` crate's `Bytes`. Backed by an
+/// `Arc<[u8]>` rather than an owned `Vec<u8>`, so [`Clone`] and sub-range
+/// operations ([`Self::left`], [`Self::as_slice_entry`]) are O(1) pointer
+/// bumps that share the same allocation instead of copying it — the hot
+/// path for key comparisons and cursor iteration, which clone entries far
+/// more often than they mutate them. A mutation that can't be done in
+/// place (the buffer is shared, or must grow) falls back to copying, same
+/// as `Arc::make_mut`.
 #[derive(Debug, Default, Clone, Eq, Ord)]
 pub struct BufferEntry {
-    data: Vec<u8>,
+    data: Arc<[u8]>,
     off: usize,
     len: usize
 }
 
 impl BufferEntry {
     pub fn new<T: Into<Vec<u8>>>(data: T, off: usize, size: usize) -> BufferEntry {
-        BufferEntry { 
-            data: data.into(), 
-            off, 
+        BufferEntry {
+            data: Arc::from(data.into().into_boxed_slice()),
+            off,
             len: off + size
         }
     }
@@ -75,24 +126,32 @@ impl BufferEntry {
     pub fn set_data(&mut self, data: Vec<u8>) {
         self.off = 0;
         self.len = data.len();
-        self.data = data;
+        self.data = Arc::from(data.into_boxed_slice());
     }
 
     pub fn set_data_offset(&mut self, data: Vec<u8>, off: usize, size: usize) {
         self.off = off;
         self.len = off + size;
-        self.data = data;
+        self.data = Arc::from(data.into_boxed_slice());
     }
 
+    /// Adopts `buffer`'s backing allocation directly (an `Arc` clone), so
+    /// two `BufferEntry`s can share the same buffer without either copying
+    /// it.
     pub fn set_buffer(&mut self, buffer: BufferEntry) {
         self.data = buffer.data;
         self.off = buffer.off;
         self.len = buffer.len;
     }
 
+    /// Grows or shrinks the window's end. Growing past the backing
+    /// allocation's length copies it into a larger one, zero-padding the
+    /// new bytes, same as `Vec::resize`; shrinking is always in place.
     pub fn set_len(&mut self, len: usize) {
         if len > self.data.len() {
-            self.data.resize(len, 0);
+            let mut owned = self.data.to_vec();
+            owned.resize(len, 0);
+            self.data = Arc::from(owned.into_boxed_slice());
         }
 
         self.len = len;
@@ -119,11 +178,31 @@ impl Entry for BufferEntry {
     fn slice(&self) -> &[u8] {
         &self.data[self.off..self.len]
     }
+
+    /// Overrides the default [`Entry::left`] to share `self`'s backing
+    /// `Arc` rather than copying: the window narrows, but the allocation
+    /// underneath is the same one, bumping only the refcount.
+    fn left(&self, n: usize) -> Result<BufferEntry> {
+        if n > self.size() {
+            Err(ErrorKind::OutOfBounds.into())
+        } else {
+            Ok(BufferEntry { data: self.data.clone(), off: self.off, len: self.off + n })
+        }
+    }
 }
 
-impl AsRef<Vec<u8>> for BufferEntry {
-    fn as_ref(&self) -> &Vec<u8> {
-        &self.data
+impl Buf for BufferEntry {
+    fn remaining(&self) -> usize {
+        self.size()
+    }
+
+    fn chunk(&self) -> &[u8] {
+        self.slice()
+    }
+
+    fn advance(&mut self, n: usize) {
+        assert!(n <= self.remaining(), "advance past the end of the buffer");
+        self.off += n;
     }
 }
 
@@ -134,25 +213,35 @@ impl AsRef<[u8]> for BufferEntry {
 }
 
 impl AsMut<[u8]> for BufferEntry {
+    /// Returns a mutable view of the full backing allocation, copy-on-write:
+    /// if it's currently shared with another `BufferEntry`, it's cloned
+    /// first so this one's edits don't leak into the other's.
     fn as_mut(&mut self) -> &mut [u8] {
-        &mut self.data
+        if Arc::get_mut(&mut self.data).is_none() {
+            self.data = Arc::from(self.data.to_vec().into_boxed_slice());
+        }
+
+        Arc::get_mut(&mut self.data).expect("uniquely owned immediately after copy-on-write")
     }
 }
 
 impl From<Vec<u8>> for BufferEntry {
     fn from(data: Vec<u8>) -> Self {
         let len = data.len();
-        BufferEntry { 
-            data, 
-            off: 0, 
+        BufferEntry {
+            data: Arc::from(data.into_boxed_slice()),
+            off: 0,
             len
         }
     }
 }
 
 impl Into<Vec<u8>> for BufferEntry {
+    /// Copies the whole backing allocation into a new `Vec`, whether or not
+    /// it is shared with a clone: an `Arc<[u8]>` cannot hand its buffer
+    /// over to a `Vec` without copying.
     fn into(self) -> Vec<u8> {
-        self.data
+        self.data.to_vec()
     }
 }
 
@@ -164,7 +253,9 @@ impl <'a> Into<BufferInput<'a>> for &'a BufferEntry {
 
 impl Into<BufferOutput> for BufferEntry {
     fn into(self) -> BufferOutput {
-        BufferOutput::new_from_vec(self.data, self.off, self.off)
+        let off = self.off;
+        let data: Vec<u8> = self.into();
+        BufferOutput::new_from_vec(data, off, off)
     }
 }
 
@@ -269,4 +360,57 @@ impl <'a> PartialOrd for SliceEntry<'a> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(compare(self, other))
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::Buf;
+    use super::BufferEntry;
+    use super::Entry;
+
+    #[test]
+    fn test_left_shares_backing_buffer() {
+        let entry = BufferEntry::new(vec![1, 2, 3, 4, 5], 0, 5);
+        let left = entry.left(3).unwrap();
+
+        assert_eq!(left.slice(), &[1, 2, 3]);
+        assert!(Arc::ptr_eq(&entry.data, &left.data));
+    }
+
+    #[test]
+    fn test_left_out_of_bounds() {
+        let entry = BufferEntry::new(vec![1, 2, 3], 0, 3);
+        assert!(entry.left(4).is_err());
+    }
+
+    #[test]
+    fn test_buf_advance() {
+        let mut entry = BufferEntry::new(vec![1, 2, 3, 4], 0, 4);
+        assert_eq!(entry.remaining(), 4);
+
+        entry.advance(2);
+        assert_eq!(entry.chunk(), &[3, 4]);
+        assert_eq!(entry.remaining(), 2);
+    }
+
+    #[test]
+    fn test_as_mut_copies_only_when_shared() {
+        let mut entry = BufferEntry::new(vec![1, 2, 3], 0, 3);
+        let clone = entry.clone();
+
+        entry.as_mut()[0] = 9;
+
+        assert_eq!(entry.slice(), &[9, 2, 3]);
+        assert_eq!(clone.slice(), &[1, 2, 3]);
+        assert!(!Arc::ptr_eq(&entry.data, &clone.data));
+    }
+
+    #[test]
+    fn test_into_vec_copies_backing_bytes() {
+        let entry = BufferEntry::new(vec![1, 2, 3], 0, 3);
+        let data: Vec<u8> = entry.into();
+        assert_eq!(data, vec![1, 2, 3]);
+    }
 }
\ No newline at end of file
@@ -13,13 +13,17 @@
 //! |<------------------- capacity ---------------------->|
 //! ```
 
+use std::fs::File;
 use std::io::Error;
 use std::io::ErrorKind;
 use std::io::Read;
 use std::io::Result;
 use std::io::Seek;
+use std::io::SeekFrom;
 use std::io::Write;
+use std::path::Path;
 
+use crate::entry::BufMut;
 use crate::entry::BufferEntry;
 use crate::entry::Entry;
 use crate::packed_int::PackedI32;
@@ -28,7 +32,17 @@ use crate::packed_int::PackedI64;
 /// Null strings are UTF encoded as `0xFF`, which is not allowed in a standard UTF encoding.
 const UTF_NULL: u8 = 0xff;
 
-/// `Input` trait 
+macro_rules! peek_fixed {
+    ($self: expr, $t: ty) => {
+        {
+            let mut buf = [0u8; std::mem::size_of::<$t>()];
+            $self.peek_buf(&mut buf)?;
+            Ok(<$t>::from_be_bytes(buf))
+        }
+    };
+}
+
+/// `Input` trait
 pub trait Input {
     fn read_string(&mut self) -> Result<Option<String>>;
     fn read_u8(&mut self) -> Result<u8>;
@@ -42,6 +56,85 @@ pub trait Input {
     fn read_f64(&mut self) -> Result<f64>;
     fn read_packed_i32(&mut self) -> Result<i32>;
     fn read_packed_i64(&mut self) -> Result<i64>;
+
+    /// Reads a `write_bytes`-encoded blob: a `PackedI32` length followed by that many raw bytes.
+    /// Unlike `read_string`, the bytes may contain embedded `NUL`s, so this is the path for
+    /// nested entries, hashes, or other opaque payloads.
+    fn read_bytes(&mut self) -> Result<Vec<u8>>;
+
+    /// Peeks a string without consuming it, checking the `UTF_NULL`/terminator byte the same way `read_string` would.
+    fn peek_string(&mut self) -> Result<Option<String>>;
+
+    /// Copies the next bytes into `buf` without advancing the reader position.
+    ///
+    /// Returns the number of bytes actually available and copied, which may be less than `buf.len()` near the end of the buffer.
+    fn peek_buf(&mut self, buf: &mut [u8]) -> Result<usize>;
+
+    fn peek_u8(&mut self) -> Result<u8> {
+        peek_fixed!(self, u8)
+    }
+
+    fn peek_u16(&mut self) -> Result<u16> {
+        peek_fixed!(self, u16)
+    }
+
+    fn peek_u32(&mut self) -> Result<u32> {
+        peek_fixed!(self, u32)
+    }
+
+    fn peek_u64(&mut self) -> Result<u64> {
+        peek_fixed!(self, u64)
+    }
+
+    fn peek_i8(&mut self) -> Result<i8> {
+        peek_fixed!(self, i8)
+    }
+
+    fn peek_i16(&mut self) -> Result<i16> {
+        peek_fixed!(self, i16)
+    }
+
+    fn peek_i32(&mut self) -> Result<i32> {
+        peek_fixed!(self, i32)
+    }
+
+    fn peek_i64(&mut self) -> Result<i64> {
+        peek_fixed!(self, i64)
+    }
+
+    fn peek_f64(&mut self) -> Result<f64> {
+        peek_fixed!(self, f64)
+    }
+
+    /// Reads a little-endian `u16`. The crate's native reads (`read_u16` and friends) are big-endian;
+    /// this is for wire/on-disk formats that aren't.
+    fn read_u16_le(&mut self) -> Result<u16> {
+        self.read_u16().map(u16::swap_bytes)
+    }
+
+    fn read_u32_le(&mut self) -> Result<u32> {
+        self.read_u32().map(u32::swap_bytes)
+    }
+
+    fn read_u64_le(&mut self) -> Result<u64> {
+        self.read_u64().map(u64::swap_bytes)
+    }
+
+    fn read_i16_le(&mut self) -> Result<i16> {
+        self.read_i16().map(i16::swap_bytes)
+    }
+
+    fn read_i32_le(&mut self) -> Result<i32> {
+        self.read_i32().map(i32::swap_bytes)
+    }
+
+    fn read_i64_le(&mut self) -> Result<i64> {
+        self.read_i64().map(i64::swap_bytes)
+    }
+
+    fn read_f64_le(&mut self) -> Result<f64> {
+        self.read_f64().map(|v| f64::from_bits(v.to_bits().swap_bytes()))
+    }
 }
 
 pub trait Inputable : Sized {
@@ -70,6 +163,40 @@ pub trait Output : Sized {
     fn write_f64(&mut self, v: f64) -> Result<()>;
     fn write_packed_i32(&mut self, v: i32) -> Result<()>;
     fn write_packed_i64(&mut self, v: i64) -> Result<()>;
+
+    /// Writes `bytes` as a `PackedI32` length followed by the raw bytes, so it round-trips through
+    /// [`Input::read_bytes`] even if it contains embedded `NUL`s (which `write_str` cannot represent).
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<()>;
+
+    /// Writes a little-endian `u16`. The crate's native writes (`write_u16` and friends) are big-endian;
+    /// this is for wire/on-disk formats that aren't.
+    fn write_u16_le(&mut self, v: u16) -> Result<()> {
+        self.write_u16(v.swap_bytes())
+    }
+
+    fn write_u32_le(&mut self, v: u32) -> Result<()> {
+        self.write_u32(v.swap_bytes())
+    }
+
+    fn write_u64_le(&mut self, v: u64) -> Result<()> {
+        self.write_u64(v.swap_bytes())
+    }
+
+    fn write_i16_le(&mut self, v: i16) -> Result<()> {
+        self.write_i16(v.swap_bytes())
+    }
+
+    fn write_i32_le(&mut self, v: i32) -> Result<()> {
+        self.write_i32(v.swap_bytes())
+    }
+
+    fn write_i64_le(&mut self, v: i64) -> Result<()> {
+        self.write_i64(v.swap_bytes())
+    }
+
+    fn write_f64_le(&mut self, v: f64) -> Result<()> {
+        self.write_f64(f64::from_bits(v.to_bits().swap_bytes()))
+    }
 }
 
 pub trait Outputable {
@@ -149,12 +276,16 @@ impl <'a> BufferInput<'a> {
     pub fn eof(&self) -> bool {
         self.pos >= self.len
     }
-}
 
-impl <'a> Read for BufferInput<'a> {
-    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+    /// Fills `buf` with as many bytes as are available, advancing `pos` by that count.
+    ///
+    /// Unlike the strict `read_*` helpers, this never errors on a short buffer: it returns `Ok(0)`
+    /// at EOF and `Ok(n)` with `n < buf.len()` when fewer bytes remain than requested. This matches
+    /// the usual `std::io::Read` contract, letting bulk consumers drain the buffer incrementally
+    /// instead of hitting a hard error the moment they overrun it.
+    pub fn read_some(&mut self, buf: &mut [u8]) -> Result<usize> {
         if self.eof() {
-            Err(Error::new(ErrorKind::UnexpectedEof, "read end of buffer"))
+            Ok(0)
         } else {
             let count = if buf.len() + self.pos <= self.len {
                 buf.copy_from_slice(&self.data[self.pos..self.pos + buf.len()]);
@@ -171,6 +302,12 @@ impl <'a> Read for BufferInput<'a> {
     }
 }
 
+impl <'a> Read for BufferInput<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        self.read_some(buf)
+    }
+}
+
 impl <'a> Seek for BufferInput<'a> {
     fn seek(&mut self, pos: std::io::SeekFrom) -> Result<u64> {
         let position: isize = match pos {
@@ -201,7 +338,7 @@ macro_rules! io_read {
     ($r: expr, $t: ty) => {
         {
             let mut buf = [0u8; std::mem::size_of::<$t>()];
-            $r.read(&mut buf)?;
+            $r.read_exact(&mut buf)?;
             let v = <$t>::from_be_bytes(buf);
             Ok(v)
         }
@@ -283,6 +420,136 @@ impl <'a> Input for BufferInput<'a> {
         Ok(v.into())
     }
 
+    fn read_bytes(&mut self) -> Result<Vec<u8>> {
+        let len = self.read_packed_i32()?;
+        if len < 0 {
+            return Err(Error::new(ErrorKind::InvalidData, "negative byte length"));
+        }
+
+        let len = len as usize;
+        if len > self.available() {
+            return Err(Error::new(ErrorKind::UnexpectedEof, "read bytes out of bounds"));
+        }
+
+        let mut buf = vec![0u8; len];
+        self.read_exact(&mut buf)?;
+        Ok(buf)
+    }
+
+    fn peek_string(&mut self) -> Result<Option<String>> {
+        if let Some(&n) = self.data.get(self.pos) {
+            if n == UTF_NULL {
+                Ok(None)
+            } else {
+                let buffer = &self.data[self.pos..];
+                if let Some(p) = buffer.iter().position(|&b| b == 0u8) {
+                    match String::from_utf8(buffer[..p].into()) {
+                        Ok(s) => Ok(Some(s)),
+                        Err(e) => Err(Error::new(ErrorKind::InvalidData, e.to_string()))
+                    }
+                } else {
+                    Err(Error::new(ErrorKind::InvalidData, "error read string"))
+                }
+            }
+        } else {
+            Err(Error::new(ErrorKind::UnexpectedEof, "read string out of bounds"))
+        }
+    }
+
+    fn peek_buf(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let available = self.len.saturating_sub(self.pos);
+        let count = buf.len().min(available);
+        buf[..count].copy_from_slice(&self.data[self.pos..self.pos + count]);
+        Ok(count)
+    }
+
+}
+
+/// How many more bytes a [`Located`] read needs to complete, returned inside
+/// [`Incomplete::Incomplete`] when the wrapped buffer runs dry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Needed {
+    /// The caller must supply at least this many additional bytes.
+    Size(usize),
+    /// The read ran out of bytes before it could tell how many more it
+    /// needs (e.g. a terminator that hasn't arrived yet).
+    Unknown
+}
+
+/// The outcome of a streaming read: either it completed with `T`, or the
+/// buffer ran dry and the same read must be retried once more bytes land,
+/// à la winnow's `Partial` stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Incomplete<T> {
+    Done(T),
+    Incomplete(Needed)
+}
+
+/// Wraps a [`BufferInput`] to track the absolute number of bytes consumed
+/// so far and to turn an out-of-bounds read into a recoverable
+/// [`Incomplete::Incomplete`] instead of an `UnexpectedEof` error, so a
+/// caller feeding records from a socket or a partially-read page can
+/// re-drive the same parse once more data arrives. Modeled on winnow's
+/// `Located` stream wrapper.
+pub struct Located<'a> {
+    input: BufferInput<'a>,
+    consumed: usize
+}
+
+impl <'a> Located<'a> {
+    pub fn new(input: BufferInput<'a>) -> Self {
+        Self { input, consumed: 0 }
+    }
+
+    /// Absolute number of bytes consumed since this `Located` was created.
+    /// Index builders can record this before and after decoding a key to
+    /// get its exact span within the larger buffer, without manually
+    /// tracking offsets themselves.
+    pub fn position(&self) -> usize {
+        self.consumed
+    }
+
+    pub fn into_inner(self) -> BufferInput<'a> {
+        self.input
+    }
+
+    /// Reads exactly `buf.len()` bytes, or leaves `self` untouched and
+    /// returns `Incomplete(Needed::Size(n))` if fewer are currently
+    /// available.
+    pub fn read_located(&mut self, buf: &mut [u8]) -> Incomplete<()> {
+        let available = self.input.available();
+        if available < buf.len() {
+            return Incomplete::Incomplete(Needed::Size(buf.len() - available));
+        }
+
+        self.input.read_some(buf).expect("already checked buf.len() bytes are available");
+        self.consumed += buf.len();
+        Incomplete::Done(())
+    }
+
+    /// Runs `f` against the wrapped buffer, rewinding to the starting
+    /// position and reporting `Incomplete(Needed::Unknown)` instead of
+    /// propagating an `UnexpectedEof` if `f` ran out of bytes partway
+    /// through (e.g. a `read_string`/`read_bytes` whose terminator or
+    /// payload hasn't fully arrived).
+    pub fn read_located_with<T>(&mut self, f: impl FnOnce(&mut BufferInput<'a>) -> Result<T>) -> Result<Incomplete<T>> {
+        let start = self.input.pos();
+
+        match f(&mut self.input) {
+            Ok(value) => {
+                self.consumed += self.input.pos() - start;
+                Ok(Incomplete::Done(value))
+            },
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => {
+                // Restore the absolute position directly: `seek` rejects a
+                // position equal to `len`, which is where `start` sits once
+                // the buffer has been read to its end.
+                self.input.pos = start;
+                Ok(Incomplete::Incomplete(Needed::Unknown))
+            },
+            Err(e) => Err(e)
+        }
+    }
 }
 
 impl Inputable for Option<String> {
@@ -352,6 +619,12 @@ impl Inputable for f64 {
     }
 }
 
+impl Inputable for Vec<u8> {
+    fn read_from<R: Input>(reader: &mut R) -> Result<Self> {
+        reader.read_bytes()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct BufferOutput {
     data: Vec<u8>,
@@ -423,6 +696,16 @@ impl Default for BufferOutput {
     }
 }
 
+impl BufMut for BufferOutput {
+    fn put_slice(&mut self, src: &[u8]) {
+        self.write(src).expect("writing into an in-memory buffer cannot fail");
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.require(additional);
+    }
+}
+
 impl Write for BufferOutput {
     fn write(&mut self, buf: &[u8]) -> Result<usize> {
         if buf.len() <= self.data.len() - self.pos {
@@ -545,6 +828,12 @@ impl Output for BufferOutput {
         self.pos = self.pos + len;
         Ok(())
     }
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<()> {
+        self.write_packed_i32(bytes.len() as i32)?;
+        self.write(bytes)?;
+        Ok(())
+    }
 }
 
 impl Outputable for Option<&str> {
@@ -619,6 +908,12 @@ impl Outputable for f64 {
     }
 }
 
+impl Outputable for Vec<u8> {
+    fn write_to<W: Output>(&self, writer: &mut W) -> Result<()> {
+        writer.write_bytes(self)
+    }
+}
+
 impl Into<BufferEntry> for BufferOutput {
     fn into(self) -> BufferEntry {
         let size = self.size();
@@ -626,8 +921,412 @@ impl Into<BufferEntry> for BufferOutput {
     }
 }
 
+/// A reader over any [`Read`] source (a file, a socket, ...) that implements [`Input`], so the
+/// codec can target a stream directly instead of forcing every caller through a `BufferEntry`.
+///
+/// Unlike `BufferInput`, a plain stream doesn't know its own length, so [`StreamInput::size`] and
+/// [`StreamInput::available`] only answer once the source was opened seekable (see
+/// [`StreamInput::is_seekable`], [`StreamInput::new_seekable`]). Since a stream can't be rewound
+/// to look ahead, peeking (see [`Input::peek_buf`]) is backed by a small internal lookahead
+/// buffer that ordinary reads drain from first.
+pub struct StreamInput<R> {
+    reader: R,
+    pos: u64,
+    len: Option<u64>,
+    peeked: Vec<u8>,
+}
+
+impl <R: Read> StreamInput<R> {
+    /// Wraps a reader whose length isn't known up front.
+    pub fn new(reader: R) -> Self {
+        StreamInput {
+            reader,
+            pos: 0,
+            len: None,
+            peeked: Vec::new(),
+        }
+    }
+
+    /// Retrieves the current read position.
+    pub fn pos(&self) -> u64 {
+        self.pos
+    }
+
+    /// Whether this stream's total length is known, i.e. it was opened via [`StreamInput::new_seekable`].
+    pub fn is_seekable(&self) -> bool {
+        self.len.is_some()
+    }
+
+    /// Retrieves the full length of the stream, if seekable.
+    pub fn size(&self) -> Option<u64> {
+        self.len
+    }
+
+    /// Retrieves the number of bytes that can still be read, if seekable.
+    pub fn available(&self) -> Option<u64> {
+        self.len.map(|len| len - self.pos)
+    }
+
+    /// Tops up the lookahead buffer to at least `want` bytes, short of an early EOF.
+    fn fill_peek(&mut self, want: usize) -> Result<()> {
+        while self.peeked.len() < want {
+            let mut chunk = [0u8; 64];
+            let n = self.reader.read(&mut chunk)?;
+            if n == 0 {
+                break;
+            }
+            self.peeked.extend_from_slice(&chunk[..n]);
+        }
+
+        Ok(())
+    }
+
+    /// Strictly fills `buf`, draining the lookahead buffer first, erroring if the stream runs dry.
+    fn take(&mut self, buf: &mut [u8]) -> Result<()> {
+        self.fill_peek(buf.len())?;
+
+        if self.peeked.len() < buf.len() {
+            return Err(Error::new(ErrorKind::UnexpectedEof, "read end of stream"));
+        }
+
+        buf.copy_from_slice(&self.peeked[..buf.len()]);
+        self.peeked.drain(..buf.len());
+        self.pos = self.pos + buf.len() as u64;
+
+        Ok(())
+    }
+}
+
+impl <R: Read + Seek> StreamInput<R> {
+    /// Wraps a seekable reader, querying its length up front so [`StreamInput::size`]/
+    /// [`StreamInput::available`] can answer without reaching back into the stream.
+    pub fn new_seekable(mut reader: R) -> Result<Self> {
+        let len = reader.seek(SeekFrom::End(0))?;
+        reader.seek(SeekFrom::Start(0))?;
+
+        Ok(StreamInput {
+            reader,
+            pos: 0,
+            len: Some(len),
+            peeked: Vec::new(),
+        })
+    }
+}
+
+impl StreamInput<File> {
+    /// Opens `path` for reading as a seekable stream.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::new_seekable(File::open(path)?)
+    }
+}
+
+/// A stream-backed [`Input`] over a file, seeked up front so its length is known.
+pub type FileInput = StreamInput<File>;
+
+impl <R: Read> Read for StreamInput<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if !self.peeked.is_empty() {
+            let count = buf.len().min(self.peeked.len());
+            buf[..count].copy_from_slice(&self.peeked[..count]);
+            self.peeked.drain(..count);
+            self.pos = self.pos + count as u64;
+            Ok(count)
+        } else {
+            let count = self.reader.read(buf)?;
+            self.pos = self.pos + count as u64;
+            Ok(count)
+        }
+    }
+}
+
+impl <R: Read> Input for StreamInput<R> {
+    fn read_string(&mut self) -> Result<Option<String>> {
+        let mut tag = [0u8; 1];
+        self.take(&mut tag)?;
+
+        if tag[0] == UTF_NULL {
+            Ok(None)
+        } else {
+            let mut bytes = vec![tag[0]];
+            loop {
+                let mut b = [0u8; 1];
+                self.take(&mut b)?;
+                if b[0] == 0u8 {
+                    break;
+                }
+                bytes.push(b[0]);
+            }
+
+            String::from_utf8(bytes)
+                .map(Some)
+                .map_err(|e| Error::new(ErrorKind::InvalidData, e.to_string()))
+        }
+    }
+
+    fn read_u8(&mut self) -> Result<u8> {
+        io_read!(self, u8)
+    }
+
+    fn read_u16(&mut self) -> Result<u16> {
+        io_read!(self, u16)
+    }
+
+    fn read_u32(&mut self) -> Result<u32> {
+        io_read!(self, u32)
+    }
+
+    fn read_u64(&mut self) -> Result<u64> {
+        io_read!(self, u64)
+    }
+
+    fn read_i8(&mut self) -> Result<i8> {
+        io_read!(self, i8)
+    }
+
+    fn read_i16(&mut self) -> Result<i16> {
+        io_read!(self, i16)
+    }
+
+    fn read_i32(&mut self) -> Result<i32> {
+        io_read!(self, i32)
+    }
+
+    fn read_i64(&mut self) -> Result<i64> {
+        io_read!(self, i64)
+    }
+
+    fn read_f64(&mut self) -> Result<f64> {
+        io_read!(self, f64)
+    }
+
+    fn read_packed_i32(&mut self) -> Result<i32> {
+        let mut v = PackedI32::default();
+        let mut buf = [0u8; PackedI32::MAX_LENGTH];
+        self.fill_peek(buf.len())?;
+        let avail = self.peeked.len().min(buf.len());
+        buf[..avail].copy_from_slice(&self.peeked[..avail]);
+
+        let len = v.read(&buf[..avail])?;
+        self.peeked.drain(..len);
+        self.pos = self.pos + len as u64;
+
+        Ok(v.into())
+    }
+
+    fn read_packed_i64(&mut self) -> Result<i64> {
+        let mut v = PackedI64::default();
+        let mut buf = [0u8; PackedI64::MAX_LENGTH];
+        self.fill_peek(buf.len())?;
+        let avail = self.peeked.len().min(buf.len());
+        buf[..avail].copy_from_slice(&self.peeked[..avail]);
+
+        let len = v.read(&buf[..avail])?;
+        self.peeked.drain(..len);
+        self.pos = self.pos + len as u64;
+
+        Ok(v.into())
+    }
+
+    fn read_bytes(&mut self) -> Result<Vec<u8>> {
+        let len = self.read_packed_i32()?;
+        if len < 0 {
+            return Err(Error::new(ErrorKind::InvalidData, "negative byte length"));
+        }
+
+        let len = len as usize;
+        if let Some(available) = self.available() {
+            if len as u64 > available {
+                return Err(Error::new(ErrorKind::UnexpectedEof, "read bytes out of bounds"));
+            }
+        }
+
+        let mut buf = vec![0u8; len];
+        self.take(&mut buf)?;
+        Ok(buf)
+    }
+
+    fn peek_string(&mut self) -> Result<Option<String>> {
+        self.fill_peek(1)?;
+
+        if self.peeked.is_empty() {
+            return Err(Error::new(ErrorKind::UnexpectedEof, "read string out of bounds"));
+        }
+
+        if self.peeked[0] == UTF_NULL {
+            return Ok(None);
+        }
+
+        let mut idx = 0;
+        loop {
+            if idx >= self.peeked.len() {
+                self.fill_peek(self.peeked.len() + 1)?;
+                if idx >= self.peeked.len() {
+                    return Err(Error::new(ErrorKind::InvalidData, "error read string"));
+                }
+            }
+
+            if self.peeked[idx] == 0u8 {
+                break;
+            }
+            idx = idx + 1;
+        }
+
+        String::from_utf8(self.peeked[..idx].to_vec())
+            .map(Some)
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e.to_string()))
+    }
+
+    fn peek_buf(&mut self, buf: &mut [u8]) -> Result<usize> {
+        self.fill_peek(buf.len())?;
+        let count = buf.len().min(self.peeked.len());
+        buf[..count].copy_from_slice(&self.peeked[..count]);
+        Ok(count)
+    }
+}
+
+/// A writer over any [`Write`] sink (a file, a socket, ...) that implements [`Output`], so the
+/// codec can target a stream directly instead of forcing every caller through a `BufferEntry`.
+pub struct StreamOutput<W> {
+    writer: W,
+    pos: u64,
+    seekable: bool,
+}
+
+impl <W: Write> StreamOutput<W> {
+    /// Wraps a writer whose length isn't known up front.
+    pub fn new(writer: W) -> Self {
+        StreamOutput {
+            writer,
+            pos: 0,
+            seekable: false,
+        }
+    }
+
+    /// Retrieves the current write position.
+    pub fn pos(&self) -> u64 {
+        self.pos
+    }
+
+    /// Whether this writer supports random access, i.e. it was created via
+    /// [`StreamOutput::new_seekable`].
+    pub fn is_seekable(&self) -> bool {
+        self.seekable
+    }
+
+    /// Unwraps this `StreamOutput`, returning the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl <W: Write + Seek> StreamOutput<W> {
+    /// Wraps a seekable writer.
+    pub fn new_seekable(writer: W) -> Self {
+        StreamOutput {
+            writer,
+            pos: 0,
+            seekable: true,
+        }
+    }
+}
+
+impl StreamOutput<File> {
+    /// Creates (or truncates) `path` for writing as a seekable stream.
+    pub fn create(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self::new_seekable(File::create(path)?))
+    }
+}
+
+/// A stream-backed [`Output`] over a file.
+pub type FileOutput = StreamOutput<File>;
+
+impl <W: Write> Write for StreamOutput<W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        let count = self.writer.write(buf)?;
+        self.pos = self.pos + count as u64;
+        Ok(count)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.writer.flush()
+    }
+}
+
+impl <W: Write> Output for StreamOutput<W> {
+    fn write_str(&mut self, s: Option<&str>) -> Result<()> {
+        if let Some(s) = s {
+            self.write_all(s.as_bytes())?;
+            self.write_all(&[0u8])?;
+        } else {
+            self.write_all(&[UTF_NULL])?;
+        }
+
+        Ok(())
+    }
+
+    fn write_u8(&mut self, v: u8) -> Result<()> {
+        io_write!(self, v)
+    }
+
+    fn write_u16(&mut self, v: u16) -> Result<()> {
+        io_write!(self, v)
+    }
+
+    fn write_u32(&mut self, v: u32) -> Result<()> {
+        io_write!(self, v)
+    }
+
+    fn write_u64(&mut self, v: u64) -> Result<()> {
+        io_write!(self, v)
+    }
+
+    fn write_i8(&mut self, v: i8) -> Result<()> {
+        io_write!(self, v)
+    }
+
+    fn write_i16(&mut self, v: i16) -> Result<()> {
+        io_write!(self, v)
+    }
+
+    fn write_i32(&mut self, v: i32) -> Result<()> {
+        io_write!(self, v)
+    }
+
+    fn write_i64(&mut self, v: i64) -> Result<()> {
+        io_write!(self, v)
+    }
+
+    fn write_f64(&mut self, v: f64) -> Result<()> {
+        io_write!(self, v)
+    }
+
+    fn write_packed_i32(&mut self, v: i32) -> Result<()> {
+        let mut buf = [0u8; PackedI32::MAX_LENGTH];
+        let val = PackedI32::from(v);
+        let len = val.write(&mut buf)?;
+        self.write_all(&buf[..len])?;
+        Ok(())
+    }
+
+    fn write_packed_i64(&mut self, v: i64) -> Result<()> {
+        let mut buf = [0u8; PackedI64::MAX_LENGTH];
+        let val = PackedI64::from(v);
+        let len = val.write(&mut buf)?;
+        self.write_all(&buf[..len])?;
+        Ok(())
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<()> {
+        self.write_packed_i32(bytes.len() as i32)?;
+        self.write_all(bytes)?;
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use std::io::Read;
+
     use crate::entry::BufferEntry;
     use crate::io::Input;
 
@@ -673,4 +1372,188 @@ mod tests {
 
         assert!(input.eof());
     }
+
+    #[test]
+    fn test_bytes() {
+        let mut output = BufferOutput::new();
+
+        output.write_bytes(&[]).unwrap();
+        output.write_bytes(&[0u8, 1u8, 0u8, 255u8]).unwrap();
+
+        let buffer: BufferEntry = output.into();
+
+        let mut input: BufferInput = (&buffer).into();
+
+        assert_eq!(Vec::<u8>::new(), input.read_bytes().unwrap());
+        assert_eq!(vec![0u8, 1u8, 0u8, 255u8], input.read_bytes().unwrap());
+
+        assert!(input.eof());
+    }
+
+    #[test]
+    fn test_peek() {
+        let mut output = BufferOutput::new();
+
+        output.write_str(Some("Hello")).unwrap();
+        output.write_u32(1234567u32).unwrap();
+
+        let buffer: BufferEntry = output.into();
+
+        let mut input: BufferInput = (&buffer).into();
+
+        assert_eq!(Some(String::from("Hello")), input.peek_string().unwrap());
+        assert_eq!(Some(String::from("Hello")), input.peek_string().unwrap());
+        assert_eq!(Some(String::from("Hello")), input.read_string().unwrap());
+
+        assert_eq!(1234567u32, input.peek_u32().unwrap());
+        assert_eq!(1234567u32, input.peek_u32().unwrap());
+        assert_eq!(1234567u32, input.read_u32().unwrap());
+
+        assert!(input.eof());
+
+        let mut buf = [0u8; 4];
+        assert_eq!(0, input.peek_buf(&mut buf).unwrap());
+    }
+
+    #[test]
+    fn test_endian() {
+        let mut output = BufferOutput::new();
+
+        output.write_u16_le(12345u16).unwrap();
+        output.write_u32_le(1234567u32).unwrap();
+        output.write_u64_le(1234567890u64).unwrap();
+        output.write_i16_le(-12345i16).unwrap();
+        output.write_i32_le(-1234567i32).unwrap();
+        output.write_i64_le(-1234567890i64).unwrap();
+        output.write_f64_le(1234567.89f64).unwrap();
+
+        let buffer: BufferEntry = output.into();
+
+        let mut input: BufferInput = (&buffer).into();
+
+        assert_eq!(12345u16, input.read_u16_le().unwrap());
+        assert_eq!(1234567u32, input.read_u32_le().unwrap());
+        assert_eq!(1234567890u64, input.read_u64_le().unwrap());
+        assert_eq!(-12345i16, input.read_i16_le().unwrap());
+        assert_eq!(-1234567i32, input.read_i32_le().unwrap());
+        assert_eq!(-1234567890i64, input.read_i64_le().unwrap());
+        assert_eq!(1234567.89f64, input.read_f64_le().unwrap());
+
+        assert!(input.eof());
+    }
+
+    #[test]
+    fn test_read_some() {
+        let mut output = BufferOutput::new();
+        output.write_u8(1u8).unwrap();
+        output.write_u8(2u8).unwrap();
+        output.write_u8(3u8).unwrap();
+
+        let buffer: BufferEntry = output.into();
+
+        let mut input: BufferInput = (&buffer).into();
+
+        let mut buf = [0u8; 2];
+        assert_eq!(2, input.read_some(&mut buf).unwrap());
+        assert_eq!([1u8, 2u8], buf);
+
+        let mut buf = [0u8; 2];
+        assert_eq!(1, input.read_some(&mut buf).unwrap());
+        assert_eq!([3u8, 0u8], buf);
+
+        assert!(input.eof());
+        let mut buf = [0u8; 2];
+        assert_eq!(0, input.read_some(&mut buf).unwrap());
+
+        let mut input: BufferInput = (&buffer).into();
+        let mut buf = [0u8; 4];
+        assert!(input.read_exact(&mut buf).is_err());
+    }
+
+    #[test]
+    fn test_located() {
+        use super::Incomplete;
+        use super::Needed;
+        use super::Located;
+
+        let mut output = BufferOutput::new();
+        output.write_u8(1u8).unwrap();
+        output.write_u8(2u8).unwrap();
+        output.write_u8(3u8).unwrap();
+
+        let buffer: BufferEntry = output.into();
+        let input: BufferInput = (&buffer).into();
+        let mut located = Located::new(input);
+
+        let mut buf = [0u8; 2];
+        assert_eq!(Incomplete::Done(()), located.read_located(&mut buf));
+        assert_eq!([1u8, 2u8], buf);
+        assert_eq!(2, located.position());
+
+        let mut buf = [0u8; 2];
+        assert_eq!(Incomplete::Incomplete(Needed::Size(1)), located.read_located(&mut buf));
+        // A short read leaves the position untouched, so the same read can be retried.
+        assert_eq!(2, located.position());
+
+        assert_eq!(Incomplete::Done(3u8), located.read_located_with(|input| input.read_u8()).unwrap());
+        assert_eq!(3, located.position());
+
+        assert_eq!(Incomplete::Incomplete(Needed::Unknown), located.read_located_with(|input| input.read_u8()).unwrap());
+        assert_eq!(3, located.position());
+    }
+
+    #[test]
+    fn test_located_with_nonzero_offset_input_rewinds_on_incomplete() {
+        use super::Incomplete;
+        use super::Needed;
+        use super::Located;
+
+        // A window well past the start of its backing buffer: `off` alone
+        // already exceeds what's left for the short read below, so naively
+        // re-adding `off` to an already-absolute position would seek past
+        // `len` and return an `Err` instead of rewinding.
+        let data = [0u8, 0u8, 0u8, 9u8];
+        let input = BufferInput::new_offset(&data, 3, 1);
+        let mut located = Located::new(input);
+
+        assert_eq!(Incomplete::Incomplete(Needed::Unknown), located.read_located_with(|input| input.read_u16()).unwrap());
+        assert_eq!(0, located.position());
+
+        assert_eq!(Incomplete::Done(9u8), located.read_located_with(|input| input.read_u8()).unwrap());
+        assert_eq!(1, located.position());
+    }
+
+    #[test]
+    fn test_stream() {
+        use std::io::Cursor;
+
+        use super::StreamInput;
+        use super::StreamOutput;
+
+        let mut output = StreamOutput::new(Vec::new());
+
+        output.write_str(None).unwrap();
+        output.write_str(Some("Hello")).unwrap();
+        output.write_u32(1234567u32).unwrap();
+        output.write_packed_i32(7654321i32).unwrap();
+        assert!(!output.is_seekable());
+
+        let data = output.into_inner();
+        let len = data.len() as u64;
+
+        let mut input = StreamInput::new(Cursor::new(data.clone()));
+        assert!(!input.is_seekable());
+
+        assert_eq!(None, input.read_string().unwrap());
+        assert_eq!(Some(String::from("Hello")), input.peek_string().unwrap());
+        assert_eq!(Some(String::from("Hello")), input.read_string().unwrap());
+        assert_eq!(1234567u32, input.peek_u32().unwrap());
+        assert_eq!(1234567u32, input.read_u32().unwrap());
+        assert_eq!(7654321i32, input.read_packed_i32().unwrap());
+
+        let seekable = StreamInput::new_seekable(Cursor::new(data)).unwrap();
+        assert!(seekable.is_seekable());
+        assert_eq!(Some(len), seekable.size());
+        assert_eq!(Some(len), seekable.available());
+    }
 }
\ No newline at end of file
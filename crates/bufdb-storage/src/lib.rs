@@ -1,13 +1,38 @@
+//! Storage-level abstractions shared by every backend engine.
+//!
+//! This crate is `std`-only end to end (file paths, threads, the
+//! `failure`-based error type it gets from [`bufdb_lib`]/`bufdb_api`), and
+//! there is no `#![no_std]` attribute or `std` Cargo feature anywhere in it
+//! or its dependencies — so none of that is actually gated out today.
+//! [`packed_int`]'s own error type (`PackError`) is written against `core`
+//! and its handful of `#[cfg(feature = "std")]` impls are a first step
+//! toward a `no_std` + `alloc` build of that one module, not a compatibility
+//! guarantee for the crate as a whole. Making the rest of the codec usable
+//! without `std` (and verifying it with a real `no_std` build) is still
+//! unfinished work.
+
 use std::cmp::Ordering;
 use std::fmt::Debug;
+use std::path::Path;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
 
+use bufdb_api::config::ColumnEncoding;
+use bufdb_api::config::Compression;
+use bufdb_api::datatype::DataType;
 use bufdb_api::error::Result;
 use entry::BufferEntry;
 use entry::Entry;
 
+pub mod async_engine;
+pub mod column_codec;
+pub mod comparator;
+pub mod compression;
 pub mod entry;
 pub mod io;
+pub mod ordered_key;
+pub mod pool;
 pub(crate) mod packed_int;
 
 pub trait PrimaryCursor<'a> {
@@ -27,12 +52,126 @@ pub trait SecondaryCursor<'a> : PrimaryCursor<'a> {
 }
 
 pub trait Database<'a, C: PrimaryCursor<'a>> {
+    type TRANSACTION: Transaction<'a, C>;
+
     fn count(&self) -> Result<usize>;
+
+    /// Whether this database currently holds no live entries. The default
+    /// implementation goes through [`Self::count`]; a backend that can
+    /// answer without a full scan (e.g. checking its first key) should
+    /// override it.
+    fn is_empty(&self) -> Result<bool> {
+        Ok(self.count()? == 0)
+    }
+
     fn put(&self, key: &BufferEntry, data: &BufferEntry) -> Result<()>;
     fn get(&self, key: &BufferEntry) -> Result<Option<BufferEntry>>;
     fn delete(&self, key: &BufferEntry) -> Result<()>;
     fn delete_exist(&self, key: &BufferEntry) -> Result<bool>;
     fn open_cursor(&'a self) -> Result<C>;
+
+    /// Begins a buffered read-write transaction over this database.
+    fn begin_transaction(&'a self) -> Result<Self::TRANSACTION>;
+
+    /// Folds `operand` into `key`'s current value through this database's
+    /// [`MergeOperator`], writing back the result. Fails if the database was
+    /// opened without one.
+    fn merge(&self, key: &BufferEntry, operand: &BufferEntry) -> Result<()>;
+
+    /// Applies every op staged in `batch` as a single commit. The default
+    /// implementation just issues each op through [`Self::put`]/[`Self::delete`]
+    /// in turn, which is *not* atomic; a backend able to commit a batch as one
+    /// unit (e.g. LevelDB's `Writebatch`) should override it.
+    fn write_batch(&self, batch: WriteBatch) -> Result<()> {
+        for op in batch.ops {
+            match op {
+                WriteOp::Put(key, data) => self.put(&key, &data)?,
+                WriteOp::Delete(key) => self.delete(&key)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Purges entries that have passed their [`DatabaseConfig::ttl`] from
+    /// `[from, to)` (the whole database if both are `None`), reclaiming
+    /// their space instead of waiting for them to be overwritten. The
+    /// default implementation is a no-op, since `ttl` only has meaning for
+    /// a backend that implements expiry; a backend that does should
+    /// override this.
+    fn compact(&self, from: Option<&BufferEntry>, to: Option<&BufferEntry>) -> Result<()> {
+        let _ = (from, to);
+        Ok(())
+    }
+}
+
+/// One mutation staged in a [`WriteBatch`].
+pub enum WriteOp {
+    Put(BufferEntry, BufferEntry),
+    Delete(BufferEntry),
+}
+
+/// A queue of [`WriteOp`]s to apply to a single [`Database`] as one commit
+/// via [`Database::write_batch`].
+#[derive(Default)]
+pub struct WriteBatch {
+    ops: Vec<WriteOp>,
+}
+
+impl WriteBatch {
+    pub fn new() -> Self {
+        Self { ops: Vec::new() }
+    }
+
+    pub fn put(&mut self, key: BufferEntry, data: BufferEntry) -> &mut Self {
+        self.ops.push(WriteOp::Put(key, data));
+        self
+    }
+
+    pub fn delete(&mut self, key: BufferEntry) -> &mut Self {
+        self.ops.push(WriteOp::Delete(key));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    pub fn into_ops(self) -> Vec<WriteOp> {
+        self.ops
+    }
+}
+
+/// A buffered read-write transaction over a single [`Database`].
+///
+/// Mutations are staged locally and only become visible to other readers of
+/// the underlying database once [`Transaction::commit`] is called. A cursor
+/// opened through [`Transaction::open_cursor`] sees the transaction's own
+/// uncommitted writes layered on top of the committed data.
+pub trait Transaction<'a, C: PrimaryCursor<'a>> {
+    fn put(&self, key: &BufferEntry, data: &BufferEntry) -> Result<()>;
+    fn get(&self, key: &BufferEntry) -> Result<Option<BufferEntry>>;
+    fn delete(&self, key: &BufferEntry) -> Result<()>;
+    fn open_cursor(&'a self) -> Result<C>;
+
+    /// Flushes every staged mutation to the underlying database atomically.
+    /// A failed commit should be surfaced as a conflict rather than leaving
+    /// the database partially updated.
+    fn commit(self) -> Result<()>;
+
+    /// Discards every staged mutation.
+    fn rollback(self) -> Result<()>;
+
+    /// Marks the current point in the staged mutation log, returning a handle
+    /// that can later be passed to [`Transaction::rollback_to_savepoint`] or
+    /// [`Transaction::pop_savepoint`].
+    fn set_savepoint(&self) -> Result<usize>;
+
+    /// Undoes every mutation staged since `savepoint` was taken.
+    fn rollback_to_savepoint(&self, savepoint: usize) -> Result<()>;
+
+    /// Releases `savepoint` without undoing the mutations staged since it was taken.
+    fn pop_savepoint(&self, savepoint: usize) -> Result<()>;
 }
 
 pub trait Environment<'a> : Sized {
@@ -40,6 +179,7 @@ pub trait Environment<'a> : Sized {
     type SCUROSR: SecondaryCursor<'a>;
     type DATABASE: Database<'a, Self::CURSOR>;
     type SDATABASE: Database<'a, Self::SCUROSR>;
+    type SNAPSHOT: Snapshot<'a, Self>;
 
     fn new(config: EnvironmentConfig) -> Result<Self>;
     fn create_database<C: KeyComparator>(&mut self, name: &str, config: DatabaseConfig<C>) -> Result<Self::DATABASE>;
@@ -48,20 +188,155 @@ pub trait Environment<'a> : Sized {
     fn drop_secondary_database(&mut self, name: &str) -> Result<()>;
     fn truncate_database(&mut self, name: &str) -> Result<()>;
     fn rename_database(&mut self, raw_name: &str, new_name: &str) -> Result<()>;
+
+    /// Opens a consistent, point-in-time read view over every database
+    /// currently open in this environment. Writes that commit after this
+    /// call are invisible to cursors opened through the returned handle,
+    /// and every such cursor — across every database — sees the same
+    /// instant, not one snapshot per database opened separately.
+    fn snapshot(&'a self) -> Result<Self::SNAPSHOT>;
+
+    /// Writes a consistent copy of this environment's on-disk data to
+    /// `target`, which must not already exist. Safe to call while other
+    /// threads are reading and writing the environment; the copy reflects
+    /// a single point in time, as if taken by [`Environment::snapshot`].
+    fn checkpoint(&self, target: &Path) -> Result<()>;
 }
 
-pub trait KeyComparator : Debug {
+/// A point-in-time read view over an [`Environment`]'s databases, opened by
+/// [`Environment::snapshot`]. Outlives the call that created it so long as
+/// the environment itself does, letting callers hold one snapshot open
+/// across several cursor opens.
+pub trait Snapshot<'a, E: Environment<'a> + ?Sized> {
+    /// Opens a primary cursor reading `database` as it stood when this
+    /// snapshot was taken, ignoring writes committed since.
+    fn open_cursor(&'a self, database: &'a E::DATABASE) -> Result<E::CURSOR>;
+
+    /// Opens a secondary-index cursor reading `database` as it stood when
+    /// this snapshot was taken.
+    fn open_secondary_cursor(&'a self, database: &'a E::SDATABASE) -> Result<E::SCUROSR>;
+}
+
+pub trait KeyComparator : Debug + Send + Sync + 'static {
     fn compare<T: Entry>(&self, key1: &T, key2: &T) -> Result<Ordering>;
+
+    /// Whether two distinct byte sequences may compare equal under this
+    /// comparator (e.g. a case-insensitive or locale-aware string key).
+    /// Cursors consult this to decide whether a duplicate/equality check must
+    /// go through [`KeyComparator::compare`] instead of a raw byte `==`, which
+    /// otherwise silently disagrees with a comparator whose ordering isn't a
+    /// refinement of byte identity.
+    fn can_differ_bytes_equal(&self) -> bool {
+        false
+    }
+
+    /// Whether this comparator's ordering is plain byte-wise comparison of
+    /// the raw key bytes. [`Self::find_shortest_separator`]/
+    /// [`Self::find_shortest_successor`]'s shortening only preserves a
+    /// comparator's ordering when this holds — a comparator whose ordering
+    /// isn't byte-wise (e.g. one that decodes the key before comparing, like
+    /// [`comparator::U64KeyComparator`], which reads a big-endian `u64`) must leave this
+    /// `false` to keep their identity behavior.
+    fn is_bytewise(&self) -> bool {
+        false
+    }
+
+    /// Shortens `start` to the shortest byte string that is still `>= start`
+    /// and `< limit`, so index blocks can store a smaller separator key
+    /// instead of the full `start`. A no-op unless [`Self::is_bytewise`] is
+    /// `true`, since shortening a non-byte-wise key isn't guaranteed to
+    /// preserve its ordering.
+    ///
+    /// Mirrors the classic log-structured-engine shortening: walk `start`
+    /// and `limit` to their first differing byte; if one is a prefix of the
+    /// other, `start` can't be shortened. Otherwise, if that byte can be
+    /// incremented without reaching or passing `limit`'s byte, truncating
+    /// just past it yields a shorter key that still sorts `>= start` and
+    /// `< limit`.
+    fn find_shortest_separator(&self, start: &BufferEntry, limit: &BufferEntry) -> BufferEntry {
+        if !self.is_bytewise() {
+            return start.clone();
+        }
+
+        let (a, b) = (start.slice(), limit.slice());
+        let min_len = a.len().min(b.len());
+
+        let mut diff_at = 0;
+        while diff_at < min_len && a[diff_at] == b[diff_at] {
+            diff_at += 1;
+        }
+
+        if diff_at >= min_len {
+            start.clone()
+        } else {
+            let byte = a[diff_at];
+            if byte < 0xff && byte + 1 < b[diff_at] {
+                let mut shortened = a[..=diff_at].to_vec();
+                shortened[diff_at] += 1;
+                BufferEntry::from(shortened)
+            } else {
+                start.clone()
+            }
+        }
+    }
+
+    /// Shortens `key` to the shortest byte string that is still `>= key`,
+    /// for use as an index block's last separator. A no-op unless
+    /// [`Self::is_bytewise`] is `true`.
+    ///
+    /// Walks `key` left to right for the first byte that isn't `0xff`,
+    /// increments it and truncates there; if every byte is `0xff`, `key`
+    /// can't be shortened and is returned unchanged.
+    fn find_shortest_successor(&self, key: &BufferEntry) -> BufferEntry {
+        if !self.is_bytewise() {
+            return key.clone();
+        }
+
+        let bytes = key.slice();
+        for (i, &b) in bytes.iter().enumerate() {
+            if b != 0xff {
+                let mut shortened = bytes[..=i].to_vec();
+                shortened[i] += 1;
+                return BufferEntry::from(shortened);
+            }
+        }
+
+        key.clone()
+    }
 }
 
 pub trait KeyCreator : Debug {
     fn create_key(&self, key: &BufferEntry, data: &BufferEntry) -> Result<Option<BufferEntry>>;
 }
 
+/// Folds one or more staged `operands` into a key's `existing` value (`None`
+/// if the key is absent), returning the value to store.
+pub type MergeOperator = Arc<dyn Fn(&BufferEntry, Option<&BufferEntry>, &[BufferEntry]) -> Result<BufferEntry> + Send + Sync>;
+
 pub struct DatabaseConfig<C: KeyComparator> {
     pub readonly: bool,
     pub temporary: bool,
-    pub comparator: C
+    pub comparator: C,
+    pub merge_operator: Option<MergeOperator>,
+    /// Codec backends apply to stored values on `put` and undo on `get`,
+    /// `fetch`, and cursor iteration. See [`compression`].
+    pub compression: Compression,
+    /// How long an entry lives after being written before a backend that
+    /// implements expiry (see [`Database::compact`]) may reclaim it.
+    /// `None` means entries never expire. Only meaningful for a database
+    /// with no secondary indexes: a backend that supports this is expected
+    /// to refuse to register a listener against one that has it set, since
+    /// reclaiming a primary entry on expiry without also dropping its
+    /// secondary entries would orphan them.
+    ///
+    /// A backend is only required to honor this on [`Database::get`] and
+    /// [`Database::compact`]; it need not filter expired entries out of
+    /// cursor iteration.
+    pub ttl: Option<Duration>,
+    /// Per-`DataType` [`ColumnEncoding`] overrides applied to a column's
+    /// values (see [`column_codec`]) before `compression` wraps the result.
+    /// Empty means every column uses [`ColumnEncoding::Plain`].
+    pub column_encodings: Vec<(DataType, ColumnEncoding)>,
 }
 
 pub struct SDatabaseConfig<C: KeyComparator, G: KeyCreator> {
@@ -69,7 +344,11 @@ pub struct SDatabaseConfig<C: KeyComparator, G: KeyCreator> {
     pub temporary: bool,
     pub unique: bool,
     pub comparator: C,
-    pub creator: G
+    pub creator: G,
+    pub merge_operator: Option<MergeOperator>,
+    pub compression: Compression,
+    /// See [`DatabaseConfig::column_encodings`].
+    pub column_encodings: Vec<(DataType, ColumnEncoding)>,
 }
 
 pub struct EnvironmentConfig {
@@ -87,3 +366,90 @@ pub trait StorageEngine<'a> : Copy + Clone {
 
     fn name(&self) -> &str;
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use bufdb_api::error::Result;
+
+    use crate::KeyComparator;
+    use crate::entry::BufferEntry;
+    use crate::entry::Entry;
+
+    #[derive(Debug)]
+    struct BytewiseComparator;
+
+    impl KeyComparator for BytewiseComparator {
+        fn compare<T: Entry>(&self, key1: &T, key2: &T) -> Result<Ordering> {
+            Ok(key1.slice().cmp(key2.slice()))
+        }
+
+        fn is_bytewise(&self) -> bool {
+            true
+        }
+    }
+
+    fn entry(bytes: &[u8]) -> BufferEntry {
+        BufferEntry::from(bytes.to_vec())
+    }
+
+    #[test]
+    fn test_find_shortest_separator_shortens() {
+        let comparator = BytewiseComparator;
+        let start = entry(b"abc1");
+        let limit = entry(b"abe");
+
+        let shortened = comparator.find_shortest_separator(&start, &limit);
+
+        assert_eq!(shortened.slice(), b"abd");
+        assert!(shortened.slice() >= start.slice());
+        assert!(shortened.slice() < limit.slice());
+    }
+
+    #[test]
+    fn test_find_shortest_separator_prefix_is_noop() {
+        let comparator = BytewiseComparator;
+        let start = entry(b"abc");
+        let limit = entry(b"abcde");
+
+        let shortened = comparator.find_shortest_separator(&start, &limit);
+
+        assert_eq!(shortened.slice(), start.slice());
+    }
+
+    #[test]
+    fn test_find_shortest_separator_non_bytewise_is_noop() {
+        struct Identity;
+        impl KeyComparator for Identity {
+            fn compare<T: Entry>(&self, key1: &T, key2: &T) -> Result<Ordering> {
+                Ok(key1.slice().cmp(key2.slice()))
+            }
+        }
+        impl std::fmt::Debug for Identity {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_struct("Identity").finish()
+            }
+        }
+
+        let comparator = Identity;
+        let start = entry(b"abc1");
+        let limit = entry(b"abd");
+
+        let shortened = comparator.find_shortest_separator(&start, &limit);
+
+        assert_eq!(shortened.slice(), start.slice());
+    }
+
+    #[test]
+    fn test_find_shortest_successor() {
+        let comparator = BytewiseComparator;
+
+        let successor = comparator.find_shortest_successor(&entry(b"abc"));
+        assert_eq!(successor.slice(), b"b");
+
+        let all_ff = entry(&[0xff, 0xff]);
+        let successor = comparator.find_shortest_successor(&all_ff);
+        assert_eq!(successor.slice(), all_ff.slice());
+    }
+}
@@ -0,0 +1,226 @@
+//! Order-preserving ("memcomparable") binary encoding of a [`Value`], so a
+//! raw byte-wise comparison of two encoded keys agrees with the logical
+//! order of the values they came from. This is what lets [`OrderedKeyComparator`]
+//! opt into [`KeyComparator::is_bytewise`] and hand [`KeyComparator::find_shortest_separator`]/
+//! [`KeyComparator::find_shortest_successor`] real shortening instead of their
+//! identity no-ops.
+//!
+//! Every encoding is prefixed with a one-byte [`DataType`] tag (`0` for
+//! [`Value::NULL`], otherwise the type's discriminant), so a `NULL` key
+//! always sorts before any typed value and mismatched types still sort
+//! deterministically against each other.
+
+use std::cmp::Ordering;
+
+use bufdb_api::datatype::DataType;
+use bufdb_api::datatype::Value;
+use bufdb_api::error::ErrorKind;
+use bufdb_api::error::Result;
+
+use crate::KeyComparator;
+use crate::entry::BufferEntry;
+use crate::entry::Entry;
+
+const NULL_TAG: u8 = 0;
+
+/// Flips the sign bit of `bytes`' big-endian two's-complement representation,
+/// so a negative value's encoding sorts before a non-negative one's under a
+/// plain byte-wise compare.
+fn flip_sign_bit(mut bytes: [u8; 8]) -> [u8; 8] {
+    bytes[0] ^= 0x80;
+    bytes
+}
+
+fn encode_long(v: i64) -> [u8; 8] {
+    flip_sign_bit(v.to_be_bytes())
+}
+
+fn decode_long(bytes: &[u8]) -> Result<i64> {
+    let raw: [u8; 8] = bytes.try_into().map_err(|_| ErrorKind::OutOfBounds)?;
+    Ok(i64::from_be_bytes(flip_sign_bit(raw)))
+}
+
+/// Encodes a `f64` so its IEEE-754 bit pattern sorts the same way the value
+/// itself orders: non-negative values have their sign bit flipped (so they
+/// sort after every negative value), negative values have every bit inverted
+/// (so a more negative value, which has a larger raw bit pattern, sorts
+/// before a less negative one).
+fn encode_double(v: f64) -> [u8; 8] {
+    let bits = v.to_bits();
+    let flipped = if v.is_sign_negative() { !bits } else { bits | (1u64 << 63) };
+    flipped.to_be_bytes()
+}
+
+fn decode_double(bytes: &[u8]) -> Result<f64> {
+    let raw: [u8; 8] = bytes.try_into().map_err(|_| ErrorKind::OutOfBounds)?;
+    let bits = u64::from_be_bytes(raw);
+    let original = if bits & (1u64 << 63) != 0 { bits & !(1u64 << 63) } else { !bits };
+    Ok(f64::from_bits(original))
+}
+
+/// Encodes `value` into a [`BufferEntry`] whose raw byte order matches
+/// `value`'s logical order, prefixed with its [`DataType`] tag byte.
+pub fn encode(value: &Value) -> BufferEntry {
+    let mut bytes = Vec::new();
+
+    match value {
+        Value::NULL => bytes.push(NULL_TAG),
+        Value::STRING(v) => {
+            bytes.push(DataType::STRING as u8);
+            bytes.extend_from_slice(v.as_bytes());
+        },
+        Value::DOUBLE(v) => {
+            bytes.push(DataType::DOUBLE as u8);
+            bytes.extend_from_slice(&encode_double(*v));
+        },
+        Value::INT(v) => {
+            bytes.push(DataType::INT as u8);
+            bytes.extend_from_slice(&encode_long(*v as i64));
+        },
+        Value::LONG(v) => {
+            bytes.push(DataType::LONG as u8);
+            bytes.extend_from_slice(&encode_long(*v));
+        },
+        Value::DATETIME(v) => {
+            bytes.push(DataType::DATETIME as u8);
+            bytes.extend_from_slice(&encode_long(v.millis()));
+        },
+        Value::BOOL(v) => {
+            bytes.push(DataType::BOOL as u8);
+            bytes.push(if *v { 1 } else { 0 });
+        },
+        Value::BLOB(v) => {
+            bytes.push(DataType::BLOB as u8);
+            bytes.extend_from_slice(v);
+        },
+    }
+
+    BufferEntry::from(bytes)
+}
+
+/// Decodes an [`encode`]d `entry` back into a [`Value`], checking its tag
+/// byte against the expected `datatype` (a mismatch is [`ErrorKind::DataType`]).
+pub fn decode(datatype: DataType, entry: &BufferEntry) -> Result<Value> {
+    let (tag, payload) = entry.slice().split_first().ok_or(ErrorKind::OutOfBounds)?;
+
+    if *tag == NULL_TAG {
+        return Ok(Value::NULL);
+    }
+
+    let found = DataType::from_repr(*tag).ok_or(ErrorKind::DataType)?;
+    if found != datatype {
+        return Err(ErrorKind::DataType.into());
+    }
+
+    match datatype {
+        DataType::STRING => {
+            let s = String::from_utf8(payload.to_vec()).map_err(|_| ErrorKind::DataType)?;
+            Ok(Value::STRING(Box::new(s)))
+        },
+        DataType::DOUBLE => Ok(Value::DOUBLE(decode_double(payload)?)),
+        DataType::INT => Ok(Value::INT(decode_long(payload)? as i32)),
+        DataType::LONG => Ok(Value::LONG(decode_long(payload)?)),
+        DataType::DATETIME => Ok(Value::DATETIME(decode_long(payload)?.into())),
+        DataType::BOOL => {
+            let b = *payload.first().ok_or(ErrorKind::OutOfBounds)?;
+            Ok(Value::BOOL(b != 0))
+        },
+        DataType::BLOB => Ok(Value::BLOB(Box::new(payload.to_vec()))),
+    }
+}
+
+/// Orders keys produced by [`encode`] byte-wise, which — by construction —
+/// agrees with the logical order of the [`Value`]s they came from. Unlike
+/// [`crate::comparator::Hash32KeyComparator`]'s fixed-width byte-wise compare,
+/// this opts into [`KeyComparator::is_bytewise`] so index blocks can shorten
+/// separator/successor keys against it.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct OrderedKeyComparator;
+
+impl KeyComparator for OrderedKeyComparator {
+    fn compare<T: Entry>(&self, key1: &T, key2: &T) -> Result<Ordering> {
+        Ok(key1.slice().cmp(key2.slice()))
+    }
+
+    fn is_bytewise(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::decode;
+    use super::encode;
+    use bufdb_api::datatype::DataType;
+    use bufdb_api::datatype::Value;
+
+    #[test]
+    fn test_round_trip() {
+        let values = vec![
+            Value::NULL,
+            Value::STRING(Box::new("hello".into())),
+            Value::DOUBLE(3.14),
+            Value::DOUBLE(-3.14),
+            Value::INT(-42),
+            Value::INT(42),
+            Value::LONG(i64::MIN),
+            Value::LONG(i64::MAX),
+            Value::DATETIME(123456789i64.into()),
+            Value::BOOL(true),
+            Value::BOOL(false),
+            Value::BLOB(Box::new(vec![1u8, 2, 3])),
+        ];
+
+        for value in values {
+            let entry = encode(&value);
+            let datatype = value.datatype().unwrap_or(DataType::STRING);
+            if value.is_null() {
+                assert_eq!(Value::NULL, decode(datatype, &entry).unwrap());
+            } else {
+                assert_eq!(value, decode(datatype, &entry).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_nan_round_trip() {
+        let value = Value::DOUBLE(f64::NAN);
+        let entry = encode(&value);
+        let decoded = decode(DataType::DOUBLE, &entry).unwrap();
+
+        match decoded {
+            Value::DOUBLE(v) => assert_eq!(v.to_bits(), f64::NAN.to_bits()),
+            _ => panic!("expected DOUBLE"),
+        }
+    }
+
+    #[test]
+    fn test_null_sorts_first() {
+        let null = encode(&Value::NULL);
+        let zero = encode(&Value::INT(0));
+        let negative = encode(&Value::INT(-1));
+
+        assert!(null.slice() < negative.slice());
+        assert!(negative.slice() < zero.slice());
+    }
+
+    #[test]
+    fn test_int_order_preserved() {
+        let values = [-100i32, -1, 0, 1, 100];
+        let encoded: Vec<_> = values.iter().map(|v| encode(&Value::INT(*v))).collect();
+
+        for pair in encoded.windows(2) {
+            assert!(pair[0].slice() < pair[1].slice());
+        }
+    }
+
+    #[test]
+    fn test_double_order_preserved() {
+        let values = [-100.5f64, -1.0, -0.0, 0.0, 1.0, 100.5];
+        let encoded: Vec<_> = values.iter().map(|v| encode(&Value::DOUBLE(*v))).collect();
+
+        for pair in encoded.windows(2) {
+            assert!(pair[0].slice() <= pair[1].slice());
+        }
+    }
+}
@@ -1,5 +1,44 @@
-use std::fmt::Display;
-use std::io::Result;
+use core::fmt::Display;
+
+/// Error type of the packed-integer codecs. The codecs index `buf` directly
+/// and never return it for a short buffer: a buffer shorter than the
+/// encoding panics.
+///
+/// This type only depends on `core`: it's a first step toward a `no_std` +
+/// `alloc` build of this module (embedded/WASM), not a finished one — there
+/// is no `#![no_std]` attribute or `std` Cargo feature anywhere in this
+/// crate yet, so `VarInt`'s `write`/`read` below still only ever get
+/// compiled against `std`, and the claim is untested.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PackError;
+
+impl Display for PackError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "packed integer buffer too small")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PackError {}
+
+#[cfg(feature = "std")]
+impl From<PackError> for std::io::Error {
+    fn from(err: PackError) -> Self {
+        std::io::Error::new(std::io::ErrorKind::UnexpectedEof, err.to_string())
+    }
+}
+
+pub type Result<T> = core::result::Result<T, PackError>;
+
+/// Common interface for the variable-length integer codecs in this module.
+///
+/// `write` encodes `self` into `buf` and returns the number of bytes used;
+/// `read` decodes a value starting at `buf[0]` into `self` and returns the
+/// number of bytes consumed.
+pub trait VarInt : Sized {
+    fn write(&self, buf: &mut [u8]) -> Result<usize>;
+    fn read(&mut self, buf: &[u8]) -> Result<usize>;
+}
 
 /// Packed `i32` storage.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
@@ -57,7 +96,10 @@ macro_rules! pack_read {
 }
 
 impl PackedI32 {
-    pub const MAX_LENGETH: usize = 5;
+    pub const MAX_LENGTH: usize = 5;
+
+    #[deprecated(since = "0.2.0", note = "use MAX_LENGTH instead")]
+    pub const MAX_LENGETH: usize = Self::MAX_LENGTH;
 
     pub fn write(&self, buf: &mut [u8]) -> Result<usize> {
         pack_write!(self, buf)
@@ -68,8 +110,18 @@ impl PackedI32 {
     }
 }
 
+impl VarInt for PackedI32 {
+    fn write(&self, buf: &mut [u8]) -> Result<usize> {
+        PackedI32::write(self, buf)
+    }
+
+    fn read(&mut self, buf: &[u8]) -> Result<usize> {
+        PackedI32::read(self, buf)
+    }
+}
+
 impl Display for PackedI32 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", self.0)
     }
 }
@@ -91,7 +143,10 @@ impl Into<i32> for PackedI32 {
 pub struct PackedI64(pub i64);
 
 impl PackedI64 {
-    pub const MAX_LENGETH: usize = 9;
+    pub const MAX_LENGTH: usize = 9;
+
+    #[deprecated(since = "0.2.0", note = "use MAX_LENGTH instead")]
+    pub const MAX_LENGETH: usize = Self::MAX_LENGTH;
 
     pub fn write(&self, buf: &mut [u8]) -> Result<usize> {
         pack_write!(self, buf)
@@ -102,6 +157,16 @@ impl PackedI64 {
     }
 }
 
+impl VarInt for PackedI64 {
+    fn write(&self, buf: &mut [u8]) -> Result<usize> {
+        PackedI64::write(self, buf)
+    }
+
+    fn read(&mut self, buf: &[u8]) -> Result<usize> {
+        PackedI64::read(self, buf)
+    }
+}
+
 impl From<i64> for PackedI64 {
     fn from(value: i64) -> Self {
         Self(value)
@@ -114,16 +179,210 @@ impl Into<i64> for PackedI64 {
     }
 }
 
+macro_rules! leb128_write {
+    ($val: expr, $buf: expr) => {
+        {
+            let mut val = $val;
+            let mut len = 0usize;
+            loop {
+                let mut byte = (val & 0x7f) as u8;
+                val >>= 7;
+                if val != 0 {
+                    byte |= 0x80;
+                }
+                $buf[len] = byte;
+                len += 1;
+                if val == 0 {
+                    break;
+                }
+            }
+            Ok(len)
+        }
+    };
+}
+
+macro_rules! leb128_read {
+    ($buf: expr, $t: ty) => {
+        {
+            let mut val: $t = 0;
+            let mut shift = 0u32;
+            let mut len = 0usize;
+            loop {
+                let byte = $buf[len];
+                val |= ((byte & 0x7f) as $t) << shift;
+                shift += 7;
+                len += 1;
+                if byte & 0x80 == 0 {
+                    break;
+                }
+            }
+            (val, len)
+        }
+    };
+}
+
+/// Unsigned LEB128-encoded `u32` (7 data bits per byte, high bit as continuation flag).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct PackedU32(pub u32);
+
+impl PackedU32 {
+    pub const MAX_LENGTH: usize = 5;
+}
+
+impl VarInt for PackedU32 {
+    fn write(&self, buf: &mut [u8]) -> Result<usize> {
+        leb128_write!(self.0, buf)
+    }
+
+    fn read(&mut self, buf: &[u8]) -> Result<usize> {
+        let (val, len) = leb128_read!(buf, u32);
+        self.0 = val;
+        Ok(len)
+    }
+}
+
+impl From<u32> for PackedU32 {
+    fn from(value: u32) -> Self {
+        Self(value)
+    }
+}
+
+impl Into<u32> for PackedU32 {
+    fn into(self) -> u32 {
+        self.0
+    }
+}
+
+/// Unsigned LEB128-encoded `u64`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct PackedU64(pub u64);
+
+impl PackedU64 {
+    pub const MAX_LENGTH: usize = 10;
+}
+
+impl VarInt for PackedU64 {
+    fn write(&self, buf: &mut [u8]) -> Result<usize> {
+        leb128_write!(self.0, buf)
+    }
+
+    fn read(&mut self, buf: &[u8]) -> Result<usize> {
+        let (val, len) = leb128_read!(buf, u64);
+        self.0 = val;
+        Ok(len)
+    }
+}
+
+impl From<u64> for PackedU64 {
+    fn from(value: u64) -> Self {
+        Self(value)
+    }
+}
+
+impl Into<u64> for PackedU64 {
+    fn into(self) -> u64 {
+        self.0
+    }
+}
+
+/// Zigzag-mapped, LEB128-encoded `i32` (`(n << 1) ^ (n >> 31)`), so
+/// small-magnitude negatives stay as compact as small positives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct ZigzagI32(pub i32);
+
+impl ZigzagI32 {
+    pub const MAX_LENGTH: usize = PackedU32::MAX_LENGTH;
+
+    fn zigzag(v: i32) -> u32 {
+        ((v << 1) ^ (v >> 31)) as u32
+    }
+
+    fn unzigzag(v: u32) -> i32 {
+        ((v >> 1) as i32) ^ -((v & 1) as i32)
+    }
+}
+
+impl VarInt for ZigzagI32 {
+    fn write(&self, buf: &mut [u8]) -> Result<usize> {
+        PackedU32(Self::zigzag(self.0)).write(buf)
+    }
+
+    fn read(&mut self, buf: &[u8]) -> Result<usize> {
+        let mut v = PackedU32::default();
+        let len = v.read(buf)?;
+        self.0 = Self::unzigzag(v.0);
+        Ok(len)
+    }
+}
+
+impl From<i32> for ZigzagI32 {
+    fn from(value: i32) -> Self {
+        Self(value)
+    }
+}
+
+impl Into<i32> for ZigzagI32 {
+    fn into(self) -> i32 {
+        self.0
+    }
+}
+
+/// Zigzag-mapped, LEB128-encoded `i64`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct ZigzagI64(pub i64);
+
+impl ZigzagI64 {
+    pub const MAX_LENGTH: usize = PackedU64::MAX_LENGTH;
+
+    fn zigzag(v: i64) -> u64 {
+        ((v << 1) ^ (v >> 63)) as u64
+    }
+
+    fn unzigzag(v: u64) -> i64 {
+        ((v >> 1) as i64) ^ -((v & 1) as i64)
+    }
+}
+
+impl VarInt for ZigzagI64 {
+    fn write(&self, buf: &mut [u8]) -> Result<usize> {
+        PackedU64(Self::zigzag(self.0)).write(buf)
+    }
+
+    fn read(&mut self, buf: &[u8]) -> Result<usize> {
+        let mut v = PackedU64::default();
+        let len = v.read(buf)?;
+        self.0 = Self::unzigzag(v.0);
+        Ok(len)
+    }
+}
+
+impl From<i64> for ZigzagI64 {
+    fn from(value: i64) -> Self {
+        Self(value)
+    }
+}
+
+impl Into<i64> for ZigzagI64 {
+    fn into(self) -> i64 {
+        self.0
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::packed_int::PackedI64;
+    use crate::packed_int::PackedU32;
+    use crate::packed_int::PackedU64;
+    use crate::packed_int::VarInt;
+    use crate::packed_int::ZigzagI32;
+    use crate::packed_int::ZigzagI64;
 
     use super::PackedI32;
 
     macro_rules! check {
         ($v: ident, $len: ident, $t: ty) => {
             {
-                let mut buf = [0u8; <$t>::MAX_LENGETH];
+                let mut buf = [0u8; <$t>::MAX_LENGTH];
 
                 let val = <$t>::from($v);
                 let size = val.write(&mut buf).unwrap();
@@ -146,6 +405,22 @@ mod tests {
         check!(v, len, PackedI64)
     }
 
+    fn check_u32(v: u32, len: Option<usize>) {
+        check!(v, len, PackedU32)
+    }
+
+    fn check_u64(v: u64, len: Option<usize>) {
+        check!(v, len, PackedU64)
+    }
+
+    fn check_zigzag_i32(v: i32, len: Option<usize>) {
+        check!(v, len, ZigzagI32)
+    }
+
+    fn check_zigzag_i64(v: i64, len: Option<usize>) {
+        check!(v, len, ZigzagI64)
+    }
+
     #[test]
     fn test_packed_i32() {
         check_i32(0, Some(1));
@@ -189,4 +464,44 @@ mod tests {
         check_i64(-770, None);
         check_i64(0xf010, None);
     }
+
+    #[test]
+    fn test_packed_u32() {
+        check_u32(0, Some(1));
+        check_u32(127, Some(1));
+        check_u32(128, Some(2));
+        check_u32(16383, Some(2));
+        check_u32(16384, Some(3));
+        check_u32(u32::MAX, Some(5));
+        check_u32(123456789, None);
+    }
+
+    #[test]
+    fn test_packed_u64() {
+        check_u64(0, Some(1));
+        check_u64(127, Some(1));
+        check_u64(128, Some(2));
+        check_u64(u64::MAX, Some(10));
+        check_u64(1234567890123, None);
+    }
+
+    #[test]
+    fn test_zigzag_i32() {
+        check_zigzag_i32(0, Some(1));
+        check_zigzag_i32(-1, Some(1));
+        check_zigzag_i32(1, Some(1));
+        check_zigzag_i32(-64, Some(1));
+        check_zigzag_i32(64, Some(2));
+        check_zigzag_i32(i32::MAX, Some(5));
+        check_zigzag_i32(i32::MIN, Some(5));
+    }
+
+    #[test]
+    fn test_zigzag_i64() {
+        check_zigzag_i64(0, Some(1));
+        check_zigzag_i64(-1, Some(1));
+        check_zigzag_i64(1, Some(1));
+        check_zigzag_i64(i64::MAX, Some(10));
+        check_zigzag_i64(i64::MIN, Some(10));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,172 @@
+//! Fixed-block memory pool for entry backing buffers.
+//!
+//! Key comparisons and index lookups allocate and free many small scratch
+//! buffers on the decode path (see the backends' `PKComparator`/
+//! `IDXComparator`), which thrashes the global allocator under load.
+//! [`BufferPool`] is a free list of equally sized blocks:
+//! [`BufferPool::alloc`] pops it and [`PooledBuffer::drop`] pushes onto it.
+//!
+//! The free list is a plain `Mutex<Vec<Vec<u8>>>` rather than a lock-free
+//! stack. An earlier version of this pool used a Treiber stack with a
+//! tagged-pointer head to dodge the ABA problem, but the tag only protects
+//! the CAS that *claims* a node — it does nothing about a thread that reads
+//! a node's `next` pointer, gets descheduled, and resumes after another
+//! thread has already popped, mutated, and re-pushed that same node: that's
+//! a data race (an unsynchronized concurrent read/write of the same memory)
+//! regardless of whether the reader's CAS then correctly fails. Safely
+//! reclaiming freed nodes in a lock-free stack needs hazard pointers or
+//! epoch-based reclamation (e.g. `crossbeam-epoch`); absent that dependency,
+//! a mutex is the honest way to make this sound. It's still far cheaper
+//! than going through the global allocator on every decode.
+
+use std::sync::Mutex;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+/// Fixed-block pool that [`BufferPool::alloc`] draws from and
+/// [`PooledBuffer::drop`] returns to. See the module docs for the free-list
+/// design. Attach one to a database handle so its comparator/decode path
+/// can reuse buffers instead of allocating and freeing one per comparison.
+pub struct BufferPool {
+    free: Mutex<Vec<Vec<u8>>>,
+    block_size: usize,
+    /// Blocks created so far, capped at `capacity`. Once it saturates,
+    /// further allocations past an empty free list fall through to the
+    /// ordinary global allocator (see [`BufferPool::alloc`]) rather than
+    /// growing the pool without bound.
+    created: AtomicUsize,
+    capacity: usize,
+}
+
+impl BufferPool {
+    /// Creates a pool of `capacity` blocks, each `block_size` bytes.
+    pub fn new(block_size: usize, capacity: usize) -> Self {
+        Self {
+            free: Mutex::new(Vec::new()),
+            block_size,
+            created: AtomicUsize::new(0),
+            capacity,
+        }
+    }
+
+    /// The fixed size of a pooled block. A request larger than this always
+    /// falls through to the global allocator; see [`Self::alloc`].
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    /// Pops a block off the free list, allocates a fresh one if the pool
+    /// hasn't yet reached `capacity`, or — for a block larger than
+    /// [`Self::block_size`], or once the pool is exhausted — falls through
+    /// to a plain heap allocation that [`PooledBuffer::drop`] won't return
+    /// to the pool.
+    pub fn alloc(&self, size: usize) -> PooledBuffer<'_> {
+        if size > self.block_size {
+            return PooledBuffer { bytes: Some(vec![0u8; size]), pool: None };
+        }
+
+        if let Some(mut bytes) = self.free.lock().unwrap().pop() {
+            bytes.resize(size, 0);
+            return PooledBuffer { bytes: Some(bytes), pool: Some(self) };
+        }
+
+        if self.created.fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| (n < self.capacity).then_some(n + 1)).is_ok() {
+            PooledBuffer { bytes: Some(vec![0u8; size]), pool: Some(self) }
+        } else {
+            PooledBuffer { bytes: Some(vec![0u8; size]), pool: None }
+        }
+    }
+
+    /// Pushes `bytes` back onto the free list.
+    fn free(&self, bytes: Vec<u8>) {
+        self.free.lock().unwrap().push(bytes);
+    }
+}
+
+/// A block drawn from a [`BufferPool`], returned to it on drop unless it
+/// was a fallback allocation (see [`BufferPool::alloc`]).
+pub struct PooledBuffer<'p> {
+    bytes: Option<Vec<u8>>,
+    pool: Option<&'p BufferPool>,
+}
+
+impl <'p> PooledBuffer<'p> {
+    pub fn as_slice(&self) -> &[u8] {
+        self.bytes.as_ref().expect("bytes taken only by Drop")
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        self.bytes.as_mut().expect("bytes taken only by Drop")
+    }
+}
+
+impl <'p> Drop for PooledBuffer<'p> {
+    fn drop(&mut self) {
+        if let (Some(bytes), Some(pool)) = (self.bytes.take(), self.pool) {
+            pool.free(bytes);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::thread;
+
+    use super::BufferPool;
+
+    #[test]
+    fn test_alloc_round_trip() {
+        let pool = BufferPool::new(16, 4);
+
+        let mut buf = pool.alloc(10);
+        buf.as_mut_slice().copy_from_slice(&[1u8; 10]);
+        assert_eq!(&[1u8; 10], buf.as_slice());
+    }
+
+    #[test]
+    fn test_recycles_freed_block() {
+        let pool = BufferPool::new(16, 1);
+
+        {
+            let _buf = pool.alloc(8);
+        }
+
+        // The pool has room for exactly one block; a second alloc after the
+        // first is freed must recycle it rather than fail or allocate past
+        // capacity.
+        let _buf = pool.alloc(8);
+        assert_eq!(1, pool.created.load(std::sync::atomic::Ordering::Relaxed));
+    }
+
+    #[test]
+    fn test_oversized_request_falls_through() {
+        let pool = BufferPool::new(4, 2);
+
+        let buf = pool.alloc(64);
+        assert_eq!(64, buf.as_slice().len());
+        assert_eq!(0, pool.created.load(std::sync::atomic::Ordering::Relaxed));
+    }
+
+    #[test]
+    fn test_concurrent_alloc_free() {
+        let pool = Arc::new(BufferPool::new(32, 8));
+
+        let handles: Vec<_> = (0..8).map(|_| {
+            let pool = pool.clone();
+            thread::spawn(move || {
+                for _ in 0..1000 {
+                    let mut buf = pool.alloc(32);
+                    buf.as_mut_slice()[0] = 7;
+                    assert_eq!(7, buf.as_slice()[0]);
+                }
+            })
+        }).collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert!(pool.created.load(std::sync::atomic::Ordering::Relaxed) <= 8);
+    }
+}
@@ -1,42 +1,69 @@
 use std::sync::Arc;
 use std::sync::RwLock;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
 use std::thread::JoinHandle;
 use std::thread::sleep;
 use std::thread::spawn;
 use std::time::Duration;
 use std::time::Instant;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
 
 pub trait Maintainable : Send + Sync {
     fn maintain(&self);
 }
 
+/// Adds a small random amount (up to `jitter`) to `interval`, so that many
+/// daemons started at the same time don't all wake up in lockstep.
+fn jittered_interval(interval: Duration, jitter: Duration) -> Duration {
+    if jitter.is_zero() {
+        return interval;
+    }
+
+    static SEED: AtomicU64 = AtomicU64::new(0);
+
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos() as u64)
+        .unwrap_or(0);
+
+    let mut x = SEED.fetch_add(nanos.wrapping_add(0x9E3779B97F4A7C15), Ordering::Relaxed) ^ nanos;
+    x ^= x << 13;
+    x ^= x >> 7;
+    x ^= x << 17;
+
+    let bound = jitter.as_millis() as u64;
+    let extra = if bound == 0 { 0 } else { x % bound };
+
+    interval + Duration::from_millis(extra)
+}
+
 struct DaemonData<T: Maintainable + 'static> {
     items: Vec<Arc<T>>,
     thread: Option<JoinHandle<()>>,
     terminated: bool,
     interval: Duration,
+    jitter: Duration,
 }
 
 impl <T: Maintainable> Maintainable for DaemonData<T> {
     fn maintain(&self) {
-        todo!()
+        for item in self.items.iter() {
+            item.maintain();
+        }
     }
 }
 
 impl <T: Maintainable + PartialEq> DaemonData<T> {
     pub fn new() -> Self {
-        Self { 
-            items: Vec::new(), 
-            thread: None, 
+        Self {
+            items: Vec::new(),
+            thread: None,
             terminated: false,
             interval: Duration::from_secs(60),
+            jitter: Duration::ZERO,
         }
     }
-
-    // pub fn interval(mut self, interval: Duration) -> Self {
-    //     self.interval = interval;
-    //     self
-    // }
 }
 
 pub struct Daemon<T: Maintainable + 'static> {
@@ -45,11 +72,32 @@ pub struct Daemon<T: Maintainable + 'static> {
 
 impl <T: Maintainable + PartialEq> Daemon<T> {
     pub fn new() -> Self {
-        Self { 
+        Self {
             data: Arc::new(RwLock::new(DaemonData::new()))
         }
     }
 
+    /// Sets the maintenance interval. The background thread reads it before
+    /// every wait, so a running thread picks it up from its next wait on.
+    pub fn interval(self, interval: Duration) -> Self {
+        {
+            let mut data = self.data.write().unwrap();
+            data.interval = interval;
+        }
+        self
+    }
+
+    /// Sets the maximum random jitter added to each maintenance interval, to
+    /// avoid many daemons waking up in lockstep. Like [`Self::interval`], it is
+    /// read before every wait, so a running thread picks it up from its next wait on.
+    pub fn jitter(self, jitter: Duration) -> Self {
+        {
+            let mut data = self.data.write().unwrap();
+            data.jitter = jitter;
+        }
+        self
+    }
+
     pub fn add(&self, item: Arc<T>) {
         let mut data = self.data.write().unwrap();
         data.items.push(item);
@@ -63,9 +111,9 @@ impl <T: Maintainable + PartialEq> Daemon<T> {
                 loop {
                     let interval = {
                         let data = local_data.read().unwrap();
-                        data.interval
+                        jittered_interval(data.interval, data.jitter)
                     };
-                    
+
                     while run_at.elapsed() < interval {
                         sleep(Duration::from_millis(100));
                         let data = local_data.read().unwrap();
@@ -76,9 +124,7 @@ impl <T: Maintainable + PartialEq> Daemon<T> {
 
                     {
                         let data = local_data.read().unwrap();
-                        for item in data.items.iter() {
-                            item.maintain();
-                        }
+                        data.maintain();
                     }
 
                     run_at = Instant::now();
@@ -93,4 +139,25 @@ impl <T: Maintainable + PartialEq> Daemon<T> {
         let mut data = self.data.write().unwrap();
         data.items.retain(|x| x != item);
     }
-}
\ No newline at end of file
+
+    /// Runs a maintenance pass immediately, on the calling thread, without
+    /// waiting for the next scheduled interval.
+    pub fn trigger(&self) {
+        let data = self.data.read().unwrap();
+        data.maintain();
+    }
+}
+
+impl <T: Maintainable + PartialEq> Drop for Daemon<T> {
+    fn drop(&mut self) {
+        let thread = {
+            let mut data = self.data.write().unwrap();
+            data.terminated = true;
+            data.thread.take()
+        };
+
+        if let Some(thread) = thread {
+            let _ = thread.join();
+        }
+    }
+}
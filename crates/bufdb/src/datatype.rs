@@ -2,6 +2,7 @@ use std::fmt::Display;
 use std::fmt::Write;
 use std::str::FromStr;
 
+use chrono::DateTime;
 use chrono::NaiveDateTime;
 use serde::Deserialize;
 use serde::Serialize;
@@ -10,6 +11,7 @@ use strum::EnumString;
 use strum::FromRepr;
 
 use crate::error::Error;
+use crate::error::ErrorKind;
 use crate::error::Result;
 
 /// Defines supported datatypes in bufdb.
@@ -90,6 +92,21 @@ impl Value {
     pub fn is_null(&self) -> bool {
         self == &Value::NULL
     }
+
+    /// The [`DataType`] this value's variant corresponds to, or `None` for
+    /// [`Value::NULL`], which carries no type of its own.
+    pub fn datatype(&self) -> Option<DataType> {
+        match self {
+            Value::NULL => None,
+            Value::STRING(_) => Some(DataType::STRING),
+            Value::DOUBLE(_) => Some(DataType::DOUBLE),
+            Value::INT(_) => Some(DataType::INT),
+            Value::LONG(_) => Some(DataType::LONG),
+            Value::DATETIME(_) => Some(DataType::DATETIME),
+            Value::BOOL(_) => Some(DataType::BOOL),
+            Value::BLOB(_) => Some(DataType::BLOB),
+        }
+    }
 }
 
 impl From<&str> for Value {
@@ -294,6 +311,115 @@ impl ConvertTo<bool> for Value {
     }
 }
 
+/// Declares how a raw string value should be parsed into a [`Value`].
+///
+/// A `Conversion` is typically parsed from a short, human-authored name (see [`FromStr`]) so a
+/// field's decoding strategy can be configured declaratively, e.g. from a [`FieldDefine`]'s
+/// `datatype` via [`FieldDefine::conversion`]. This lets callers feed string input (CSV cells,
+/// REST query params, config) straight into a [`Record`] through [`Record::set_from_str`].
+///
+/// [`FieldDefine`]: crate::model::FieldDefine
+/// [`FieldDefine::conversion`]: crate::model::FieldDefine::conversion
+/// [`Record`]: crate::record::Record
+/// [`Record::set_from_str`]: crate::record::Record::set_from_str
+#[derive(Debug, Clone, PartialEq)]
+pub enum Conversion {
+    /// Stores the trimmed input as-is.
+    Bytes,
+    /// Parses an `i32`, widening to `i64` if it doesn't fit.
+    Integer,
+    Float,
+    /// Matches (case-insensitively) `true`/`false`, `1`/`0`, `yes`/`no`.
+    Boolean,
+    /// Parses an RFC3339 timestamp or an epoch-millis integer.
+    Timestamp,
+    /// Parses a timestamp using the given `chrono` format string.
+    TimestampFmt(String),
+    /// Parses a timezone-aware timestamp using the given `chrono` format string.
+    TimestampTZFmt(String),
+}
+
+impl FromStr for Conversion {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "asis" | "bytes" | "string" => Ok(Self::Bytes),
+            "int" | "integer" => Ok(Self::Integer),
+            "float" => Ok(Self::Float),
+            "bool" | "boolean" => Ok(Self::Boolean),
+            "timestamp" => Ok(Self::Timestamp),
+            _ => {
+                if let Some((kind, fmt)) = s.split_once('|') {
+                    match kind {
+                        "timestamp" => return Ok(Self::TimestampFmt(fmt.into())),
+                        "timestamptz" => return Ok(Self::TimestampTZFmt(fmt.into())),
+                        _ => {}
+                    }
+                }
+
+                Err(ErrorKind::UndefinedExpr.into())
+            }
+        }
+    }
+}
+
+impl Conversion {
+    fn parse_bool(s: &str) -> Result<bool> {
+        match s.to_ascii_lowercase().as_str() {
+            "true" | "1" | "yes" => Ok(true),
+            "false" | "0" | "no" => Ok(false),
+            _ => Err(ErrorKind::DataType.into())
+        }
+    }
+
+    fn parse_timestamp(s: &str) -> Result<TimeStamp> {
+        if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+            Ok(dt.naive_utc().into())
+        } else if let Ok(millis) = s.parse::<i64>() {
+            Ok(millis.into())
+        } else {
+            Err(ErrorKind::DataType.into())
+        }
+    }
+
+    /// Parses `raw` into a [`Value`] according to this conversion.
+    ///
+    /// `raw` is trimmed first; an empty result yields [`Value::NULL`] rather than an error.
+    pub fn apply(&self, raw: &str) -> Result<Value> {
+        let raw = raw.trim();
+        if raw.is_empty() {
+            return Ok(Value::NULL);
+        }
+
+        match self {
+            Self::Bytes => Ok(Value::from(raw)),
+            Self::Integer => {
+                if let Ok(v) = raw.parse::<i32>() {
+                    Ok(Value::from(v))
+                } else {
+                    let v: i64 = raw.parse().map_err(|_| ErrorKind::DataType)?;
+                    Ok(Value::from(v))
+                }
+            },
+            Self::Float => {
+                let v: f64 = raw.parse().map_err(|_| ErrorKind::DataType)?;
+                Ok(Value::from(v))
+            },
+            Self::Boolean => Ok(Value::from(Self::parse_bool(raw)?)),
+            Self::Timestamp => Ok(Value::from(Self::parse_timestamp(raw)?)),
+            Self::TimestampFmt(fmt) => {
+                let dt = NaiveDateTime::parse_from_str(raw, fmt).map_err(|_| ErrorKind::DataType)?;
+                Ok(Value::from(TimeStamp::from(dt)))
+            },
+            Self::TimestampTZFmt(fmt) => {
+                let dt = DateTime::parse_from_str(raw, fmt).map_err(|_| ErrorKind::DataType)?;
+                Ok(Value::from(TimeStamp::from(dt.naive_utc())))
+            },
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::datatype::Value;
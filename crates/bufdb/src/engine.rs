@@ -27,4 +27,22 @@ impl <'a> StorageEngine<'a> for DBEngine {
     fn name() -> &'a str {
         "LevelDB"
     }
+}
+
+#[cfg(feature = "memory")]
+impl <'a> StorageEngine<'a> for DBEngine {
+    type ENVIRONMENT = bufdb_mem::env::MemEnv;
+
+    fn name() -> &'a str {
+        "Memory"
+    }
+}
+
+#[cfg(feature = "safe")]
+impl <'a> StorageEngine<'a> for DBEngine {
+    type ENVIRONMENT = bufdb_safe::env::SafeEnv;
+
+    fn name() -> &'a str {
+        "Safe"
+    }
 }
\ No newline at end of file
@@ -1,5 +1,6 @@
 use std::fs::create_dir_all;
 use std::fs::remove_dir_all;
+use std::path::Path;
 use std::sync::Arc;
 
 use bufdb_api::config::InstanceConfig;
@@ -115,6 +116,18 @@ impl Instance {
         self.inst.get(name).map(|s| Schema::new(self.inst.clone(), s))
     }
 
+    /// Writes a consistent, point-in-time copy of every currently open
+    /// schema to `target/<schema name>`. `target` must not already exist.
+    pub fn backup_to(&self, target: &Path) -> Result<()> {
+        create_dir_all(target)?;
+
+        for schema in self.inst.schemas.collect() {
+            schema.checkpoint(&target.join(schema.name()))?;
+        }
+
+        Ok(())
+    }
+
     pub fn drop_schema(&self, name: &str) -> Result<bool> {
         if let Some(schema) = self.inst.close(name) {
             if let Err(schema) = Arc::try_unwrap(schema) {
@@ -60,7 +60,7 @@ mod tests {
 
         {
             let config = TableConfig::new(false, false);
-            let kv = schema.create_kv_table("T_KV", config).unwrap();
+            let kv = schema.create_kv_table("T_KV", config, Vec::new()).unwrap();
             println!("Init kv table: {} - {}", kv, kv.config());
 
             kv.put("K_I32", 1i32).unwrap();
@@ -68,7 +68,7 @@ mod tests {
         }
 
         {
-            let kv = schema.open_kv_table("T_KV", TableConfig::new(false, false)).unwrap();
+            let kv = schema.open_kv_table("T_KV", TableConfig::new(false, false), &[]).unwrap();
             println!("Open kv table: {} - {}", kv, kv.config());
 
             let val: i32 = kv.get_or_default("K_I32").unwrap();
@@ -1,7 +1,11 @@
 use serde::Deserialize;
 use serde::Serialize;
 
+use crate::datatype::Conversion;
 use crate::datatype::DataType;
+use crate::error::ErrorKind;
+use crate::error::Result;
+use crate::record::Record;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize)]
 pub enum IndexType {
@@ -24,6 +28,20 @@ pub struct FieldDefine {
     pub comment: Option<String>
 }
 
+impl FieldDefine {
+    /// The [`Conversion`] that should parse a raw string value for this field, picked from its `datatype`.
+    pub fn conversion(&self) -> Conversion {
+        match self.datatype {
+            DataType::STRING => Conversion::Bytes,
+            DataType::DOUBLE => Conversion::Float,
+            DataType::INT | DataType::LONG => Conversion::Integer,
+            DataType::DATETIME => Conversion::Timestamp,
+            DataType::BOOL => Conversion::Boolean,
+            DataType::BLOB => Conversion::Bytes,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct OrderedField {
     pub field_name: String,
@@ -45,4 +63,42 @@ pub struct TableDefine {
     pub fields: Vec<FieldDefine>,
     pub key_fields: Vec<String>,
     pub indexes: Vec<IndexDefine>
+}
+
+impl TableDefine {
+    /// Validates `record`'s shape against this definition: its arity must
+    /// equal [`Self::fields`]'s, each non-null value must match its field's
+    /// declared `datatype`, and every column named in [`Self::key_fields`]
+    /// must be present and non-null. Errors carry only their `ErrorKind`
+    /// (`OutOfBounds`, `DataType` or `NullValue`); they do not name the
+    /// offending field.
+    ///
+    /// Callers are responsible for invoking this before a `Record` reaches
+    /// storage — [`crate::bulk::load_csv`] is currently the only built-in
+    /// writer that does so. There is no generic `Record`-shaped write path
+    /// in this crate yet (`TableImpl`/`MetaStorage` operate on raw
+    /// key/value bytes, not `Record`s), so this can't yet be enforced at a
+    /// single choke point.
+    pub fn validate_record(&self, record: &Record) -> Result<()> {
+        if record.len() != self.fields.len() {
+            return Err(ErrorKind::OutOfBounds.into());
+        }
+
+        for (index, field) in self.fields.iter().enumerate() {
+            if let Some(found) = record[index].datatype() {
+                if found != field.datatype {
+                    return Err(ErrorKind::DataType.into());
+                }
+            }
+        }
+
+        for key_field in &self.key_fields {
+            let index = self.fields.iter().position(|f| &f.name == key_field).ok_or(ErrorKind::OutOfBounds)?;
+            if record[index].is_null() {
+                return Err(ErrorKind::NullValue.into());
+            }
+        }
+
+        Ok(())
+    }
 }
\ No newline at end of file
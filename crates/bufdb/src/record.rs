@@ -1,11 +1,14 @@
 use std::ops::Index;
 use std::ops::IndexMut;
 
+use crate::datatype::Conversion;
 use crate::datatype::ConvertTo;
+use crate::datatype::DataType;
 use crate::datatype::TimeStamp;
 use crate::datatype::Value;
 use crate::error::ErrorKind;
 use crate::error::Result;
+use crate::model::TableDefine;
 
 #[derive(Debug, Clone)]
 pub struct Record {
@@ -187,6 +190,30 @@ impl Record {
     pub fn set_blob_vec(&mut self, index: usize, v: Vec<u8>) -> Result<()> {
         self.set_value(index, v)
     }
+
+    /// Parses `raw` with `conv` and stores the resulting value at `index`.
+    pub fn set_from_str(&mut self, index: usize, raw: &str, conv: &Conversion) -> Result<()> {
+        let value = conv.apply(raw)?;
+        let val = self.get_mut(index)?;
+        *val = value;
+        Ok(())
+    }
+
+    /// Parses `raw` at `index` using the [`Conversion`] of `table`'s matching [`FieldDefine`].
+    pub fn set_from_table(&mut self, index: usize, raw: &str, table: &TableDefine) -> Result<()> {
+        let field = table.fields.get(index).ok_or(ErrorKind::OutOfBounds)?;
+        let conv = field.conversion();
+        self.set_from_str(index, raw, &conv)?;
+
+        // `Conversion::Integer` yields an `INT` whenever the number fits one;
+        // widen it for a `LONG` field so the value matches the declared datatype.
+        if field.datatype == DataType::LONG {
+            if let Value::INT(v) = self.values[index] {
+                self.values[index] = Value::LONG(v as i64);
+            }
+        }
+        Ok(())
+    }
 }
 
 impl Index<usize> for Record {
@@ -232,8 +259,12 @@ impl<'a> IntoIterator for &'a mut Record {
 
 #[cfg(test)]
 mod tests {
+    use crate::datatype::Conversion;
     use crate::datatype::ConvertTo;
+    use crate::datatype::DataType;
     use crate::datatype::Value;
+    use crate::model::FieldDefine;
+    use crate::model::TableDefine;
 
     use super::Record;
 
@@ -291,4 +322,95 @@ mod tests {
 
         assert_eq!(111i32, all);
     }
+
+    #[test]
+    fn test_set_from_str() {
+        let mut record = Record::new(3);
+
+        record.set_from_str(0, "  Hello  ", &Conversion::Bytes).unwrap();
+        record.set_from_str(1, "100", &Conversion::Integer).unwrap();
+        record.set_from_str(2, "", &Conversion::Integer).unwrap();
+
+        assert_eq!(Some("Hello"), record.get_str(0).unwrap());
+        assert_eq!(Some(100), record.get_int(1).unwrap());
+        assert!(record.is_null(2).unwrap());
+
+        assert!(record.set_from_str(1, "not-a-number", &Conversion::Integer).is_err());
+    }
+
+    #[test]
+    fn test_set_from_table() {
+        let table = TableDefine {
+            name: "demo".into(),
+            comment: None,
+            fields: vec![
+                FieldDefine { name: "id".into(), datatype: DataType::LONG, comment: None },
+                FieldDefine { name: "active".into(), datatype: DataType::BOOL, comment: None },
+            ],
+            key_fields: vec!["id".into()],
+            indexes: vec![]
+        };
+
+        let mut record = Record::new(2);
+        record.set_from_table(0, "42", &table).unwrap();
+        record.set_from_table(1, "yes", &table).unwrap();
+
+        assert_eq!(Some(42), record.get_long(0).unwrap());
+        assert_eq!(Some(true), record.get_bool(1).unwrap());
+
+        assert!(record.set_from_table(2, "x", &table).is_err());
+    }
+
+    fn demo_table() -> TableDefine {
+        TableDefine {
+            name: "demo".into(),
+            comment: None,
+            fields: vec![
+                FieldDefine { name: "id".into(), datatype: DataType::LONG, comment: None },
+                FieldDefine { name: "name".into(), datatype: DataType::STRING, comment: None },
+            ],
+            key_fields: vec!["id".into()],
+            indexes: vec![],
+        }
+    }
+
+    #[test]
+    fn test_validate_record() {
+        let table = demo_table();
+
+        let mut record = Record::new(2);
+        record.set_long(0, 1).unwrap();
+        record.set_str(1, "Alice").unwrap();
+        assert!(table.validate_record(&record).is_ok());
+    }
+
+    #[test]
+    fn test_validate_record_wrong_arity() {
+        let table = demo_table();
+        let record = Record::new(3);
+
+        assert!(table.validate_record(&record).is_err());
+    }
+
+    #[test]
+    fn test_validate_record_wrong_datatype() {
+        let table = demo_table();
+
+        let mut record = Record::new(2);
+        record.set_str(0, "not-a-long").unwrap();
+        record.set_str(1, "Alice").unwrap();
+
+        assert!(table.validate_record(&record).is_err());
+    }
+
+    #[test]
+    fn test_validate_record_null_key() {
+        let table = demo_table();
+
+        let mut record = Record::new(2);
+        record.set_null(0).unwrap();
+        record.set_str(1, "Alice").unwrap();
+
+        assert!(table.validate_record(&record).is_err());
+    }
 }
\ No newline at end of file
@@ -0,0 +1,171 @@
+use std::str::FromStr;
+
+use bufdb_api::datatype::DataType;
+use bufdb_api::error::Error;
+use bufdb_api::error::ErrorKind;
+use bufdb_api::error::Result;
+use bufdb_api::error::SyntaxError;
+
+/// A DDL keyword recognized by [`Lexer`]. Type-name keywords (`INT`,
+/// `STRING`, ...) carry the [`DataType`] they stand for rather than getting
+/// their own variant, since the parser only ever wants the datatype.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Keyword {
+    Create,
+    Table,
+    Index,
+    Key,
+    Unique,
+    Normal,
+    Fulltext,
+    Asc,
+    Desc,
+    Type(DataType),
+}
+
+fn keyword_of(ident: &str) -> Option<Keyword> {
+    match ident.to_lowercase().as_str() {
+        "create" => Some(Keyword::Create),
+        "table" => Some(Keyword::Table),
+        "index" => Some(Keyword::Index),
+        "key" => Some(Keyword::Key),
+        "unique" => Some(Keyword::Unique),
+        "normal" => Some(Keyword::Normal),
+        "fulltext" => Some(Keyword::Fulltext),
+        "asc" => Some(Keyword::Asc),
+        "desc" => Some(Keyword::Desc),
+        lower => DataType::from_str(lower).ok().map(Keyword::Type),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum TokenKind {
+    Ident(String),
+    Keyword(Keyword),
+    Int(i64),
+    Str(String),
+    LParen,
+    RParen,
+    Comma,
+    Semicolon,
+    Eof,
+}
+
+/// One scanned token together with the 1-based line/column its first
+/// character started at, so [`super::parser::Parser`] can point a syntax
+/// error at the exact span that produced it.
+#[derive(Debug, Clone)]
+pub(super) struct Token {
+    pub(super) kind: TokenKind,
+    pub(super) line: usize,
+    pub(super) column: usize,
+}
+
+/// Scans a DDL statement into a [`Token`] stream. Identifiers and keywords
+/// share a scan path (an identifier is just any scanned word that isn't a
+/// recognized [`Keyword`]); string literals are single-quoted, integer
+/// literals are bare digit runs, and `(`, `)`, `,`, `;` are their own
+/// single-character tokens.
+pub(super) struct Lexer<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> Lexer<'a> {
+    pub(super) fn new(src: &'a str) -> Self {
+        Self { chars: src.chars().peekable(), line: 1, column: 1 }
+    }
+
+    fn advance(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(c) if c.is_whitespace()) {
+            self.advance();
+        }
+    }
+
+    fn error(&self, line: usize, column: usize, message: impl Into<String>) -> Error {
+        ErrorKind::Syntax(SyntaxError { line, column, message: message.into() }).into()
+    }
+
+    pub(super) fn tokenize(mut self) -> Result<Vec<Token>> {
+        let mut tokens = Vec::new();
+
+        loop {
+            self.skip_whitespace();
+            let (line, column) = (self.line, self.column);
+
+            let Some(c) = self.peek() else {
+                tokens.push(Token { kind: TokenKind::Eof, line, column });
+                break;
+            };
+
+            let kind = match c {
+                '(' => { self.advance(); TokenKind::LParen },
+                ')' => { self.advance(); TokenKind::RParen },
+                ',' => { self.advance(); TokenKind::Comma },
+                ';' => { self.advance(); TokenKind::Semicolon },
+                '\'' => self.scan_string(line, column)?,
+                c if c.is_ascii_digit() => self.scan_int(line, column)?,
+                c if c.is_alphabetic() || c == '_' => self.scan_word(),
+                other => return Err(self.error(line, column, format!("unexpected character '{other}'"))),
+            };
+
+            tokens.push(Token { kind, line, column });
+        }
+
+        Ok(tokens)
+    }
+
+    fn scan_string(&mut self, start_line: usize, start_column: usize) -> Result<TokenKind> {
+        self.advance();
+
+        let mut text = String::new();
+        loop {
+            match self.advance() {
+                Some('\'') => return Ok(TokenKind::Str(text)),
+                Some(c) => text.push(c),
+                None => return Err(self.error(start_line, start_column, "unterminated string literal")),
+            }
+        }
+    }
+
+    fn scan_int(&mut self, start_line: usize, start_column: usize) -> Result<TokenKind> {
+        let mut digits = String::new();
+        while matches!(self.peek(), Some(c) if c.is_ascii_digit()) {
+            digits.push(self.advance().expect("just peeked"));
+        }
+
+        // `digits` is a non-empty run of ASCII digits, so parsing fails only
+        // when the literal is too large for an `i64`.
+        digits.parse()
+            .map(TokenKind::Int)
+            .map_err(|_| self.error(start_line, start_column, "integer literal out of range"))
+    }
+
+    fn scan_word(&mut self) -> TokenKind {
+        let mut word = String::new();
+        while matches!(self.peek(), Some(c) if c.is_alphanumeric() || c == '_') {
+            word.push(self.advance().expect("just peeked"));
+        }
+
+        match keyword_of(&word) {
+            Some(keyword) => TokenKind::Keyword(keyword),
+            None => TokenKind::Ident(word),
+        }
+    }
+}
@@ -0,0 +1,28 @@
+mod lexer;
+mod parser;
+
+use bufdb_api::error::Result;
+use bufdb_api::model::TableDefine;
+
+use self::lexer::Lexer;
+use self::parser::Parser;
+
+/// Parses a single `CREATE TABLE` statement into the [`TableDefine`] it
+/// describes, e.g.:
+///
+/// ```text
+/// CREATE TABLE users (id INT KEY, name STRING, INDEX uniq_name UNIQUE (name DESC));
+/// ```
+///
+/// Field lines give a name and a [`bufdb_api::datatype::DataType`] keyword,
+/// optionally followed by `KEY` to add the field to `key_fields`. `INDEX`
+/// lines give a name, an optional `UNIQUE`/`NORMAL`/`FULLTEXT` (defaulting
+/// to `NORMAL`), and a parenthesized, comma-separated list of field names
+/// each optionally followed by `ASC`/`DESC` (defaulting to `ASC`). The
+/// trailing `;` is optional. Any failure comes back as
+/// [`bufdb_api::error::ErrorKind::Syntax`], carrying the line/column of the
+/// offending token.
+pub(crate) fn parse_table_define(sql: &str) -> Result<TableDefine> {
+    let tokens = Lexer::new(sql).tokenize()?;
+    Parser::new(tokens).parse_table_define()
+}
@@ -0,0 +1,197 @@
+use bufdb_api::datatype::DataType;
+use bufdb_api::error::Error;
+use bufdb_api::error::ErrorKind;
+use bufdb_api::error::Result;
+use bufdb_api::error::SyntaxError;
+use bufdb_api::model::CURRENT_SCHEMA_VERSION;
+use bufdb_api::model::FieldDefine;
+use bufdb_api::model::IndexDefine;
+use bufdb_api::model::IndexType;
+use bufdb_api::model::OrderMode;
+use bufdb_api::model::OrderedField;
+use bufdb_api::model::TableDefine;
+
+use super::lexer::Keyword;
+use super::lexer::Token;
+use super::lexer::TokenKind;
+
+/// Recursive-descent parser over a [`Token`] stream, producing a single
+/// [`TableDefine`] from a `CREATE TABLE` statement. See [`super::parse_table_define`]
+/// for the grammar.
+pub(super) struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    pub(super) fn new(tokens: Vec<Token>) -> Self {
+        Self { tokens, pos: 0 }
+    }
+
+    fn peek(&self) -> &Token {
+        // `tokenize` always ends the stream with `Eof`, so `pos` never runs
+        // past the end as long as callers only advance past a non-`Eof`.
+        &self.tokens[self.pos]
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.tokens[self.pos].clone();
+        if self.pos + 1 < self.tokens.len() {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn error(&self, message: impl Into<String>) -> Error {
+        let token = self.peek();
+        ErrorKind::Syntax(SyntaxError { line: token.line, column: token.column, message: message.into() }).into()
+    }
+
+    fn is_keyword(&self, keyword: Keyword) -> bool {
+        matches!(&self.peek().kind, TokenKind::Keyword(k) if *k == keyword)
+    }
+
+    fn eat_keyword(&mut self, keyword: Keyword) -> bool {
+        if self.is_keyword(keyword) {
+            self.advance();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: Keyword) -> Result<()> {
+        if self.eat_keyword(keyword) {
+            Ok(())
+        } else {
+            Err(self.error(format!("expected keyword {keyword:?}")))
+        }
+    }
+
+    fn eat_punct(&mut self, kind: &TokenKind) -> bool {
+        if &self.peek().kind == kind {
+            self.advance();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_punct(&mut self, kind: TokenKind) -> Result<()> {
+        if self.eat_punct(&kind) {
+            Ok(())
+        } else {
+            Err(self.error(format!("expected '{kind:?}'")))
+        }
+    }
+
+    fn expect_ident(&mut self) -> Result<String> {
+        let token = self.advance();
+        match token.kind {
+            TokenKind::Ident(name) => Ok(name),
+            other => Err(ErrorKind::Syntax(SyntaxError { line: token.line, column: token.column, message: format!("expected an identifier, found {other:?}") }).into()),
+        }
+    }
+
+    fn expect_type(&mut self) -> Result<DataType> {
+        let token = self.advance();
+        match token.kind {
+            TokenKind::Keyword(Keyword::Type(datatype)) => Ok(datatype),
+            other => Err(ErrorKind::Syntax(SyntaxError { line: token.line, column: token.column, message: format!("expected a datatype, found {other:?}") }).into()),
+        }
+    }
+
+    fn expect_eof(&mut self) -> Result<()> {
+        match self.peek().kind {
+            TokenKind::Eof => Ok(()),
+            ref other => Err(self.error(format!("unexpected trailing token {other:?}"))),
+        }
+    }
+
+    pub(super) fn parse_table_define(mut self) -> Result<TableDefine> {
+        self.expect_keyword(Keyword::Create)?;
+        self.expect_keyword(Keyword::Table)?;
+        let name = self.expect_ident()?;
+
+        self.expect_punct(TokenKind::LParen)?;
+
+        let mut fields = Vec::new();
+        let mut key_fields = Vec::new();
+        let mut indexes = Vec::new();
+
+        loop {
+            if self.is_keyword(Keyword::Index) {
+                indexes.push(self.parse_index_define()?);
+            } else {
+                let (field, is_key) = self.parse_field_define()?;
+                if is_key {
+                    key_fields.push(field.name.clone());
+                }
+                fields.push(field);
+            }
+
+            if !self.eat_punct(&TokenKind::Comma) {
+                break;
+            }
+        }
+
+        self.expect_punct(TokenKind::RParen)?;
+        self.eat_punct(&TokenKind::Semicolon);
+        self.expect_eof()?;
+
+        for index in &indexes {
+            for ordered in &index.fields {
+                if let Some(field) = fields.iter_mut().find(|f| f.name == ordered.field_name) {
+                    field.indexed = true;
+                }
+            }
+        }
+
+        Ok(TableDefine { name, comment: None, fields, key_fields, indexes, version: CURRENT_SCHEMA_VERSION })
+    }
+
+    fn parse_field_define(&mut self) -> Result<(FieldDefine, bool)> {
+        let name = self.expect_ident()?;
+        let datatype = self.expect_type()?;
+        let is_key = self.eat_keyword(Keyword::Key);
+
+        let field = FieldDefine::new(name, datatype).set_nullable(!is_key);
+        Ok((field, is_key))
+    }
+
+    fn parse_index_define(&mut self) -> Result<IndexDefine> {
+        self.expect_keyword(Keyword::Index)?;
+        let name = self.expect_ident()?;
+
+        let index_type = if self.eat_keyword(Keyword::Unique) {
+            IndexType::UNIQUE
+        } else if self.eat_keyword(Keyword::Fulltext) {
+            IndexType::FULLTEXT
+        } else {
+            self.eat_keyword(Keyword::Normal);
+            IndexType::NORMAL
+        };
+
+        self.expect_punct(TokenKind::LParen)?;
+
+        let mut fields = Vec::new();
+        loop {
+            let field_name = self.expect_ident()?;
+            let order_mode = if self.eat_keyword(Keyword::Desc) {
+                OrderMode::DESC
+            } else {
+                self.eat_keyword(Keyword::Asc);
+                OrderMode::ASC
+            };
+            fields.push(OrderedField { field_name, order_mode });
+
+            if !self.eat_punct(&TokenKind::Comma) {
+                break;
+            }
+        }
+
+        self.expect_punct(TokenKind::RParen)?;
+
+        Ok(IndexDefine { name, index_type, fields, comment: None })
+    }
+}
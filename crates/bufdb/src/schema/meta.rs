@@ -1,14 +1,54 @@
+use std::sync::Arc;
+
+use bufdb_api::config::RecordCodec;
+use bufdb_api::config::TableConfig;
 use bufdb_api::error::ErrorKind;
 use bufdb_api::error::Result;
+use bufdb_api::migration::MigrationRegistry;
+use bufdb_api::model::CURRENT_SCHEMA_VERSION;
 use bufdb_api::model::TableDefine;
 use bufdb_storage::Database;
 use bufdb_storage::Environment;
+use bufdb_storage::PrimaryCursor;
 use bufdb_storage::StorageEngine;
+use bufdb_storage::entry::BufferEntry;
+use bufdb_storage::entry::Entry;
 use bufdb_storage::io::Inputable;
 use bufdb_storage::io::Outputable;
+use rkyv::Deserialize as RkyvDeserialize;
+
+/// Reserved key the applied schema version is stored under, alongside the
+/// [`TableDefine`] rows this table keys by name. Table names are not
+/// checked against it, so a table created under exactly this name shares
+/// its row.
+const VERSION_KEY: &str = "$SCHEMA_VERSION$";
+
+/// Suffix appended to a table's name to form the reserved key its
+/// [`TableConfig`] is stored under, alongside the table's own [`TableDefine`]
+/// row. Table names are not checked against it, so a table whose name ends
+/// in this suffix is read as a config row.
+const CONFIG_KEY_SUFFIX: &str = "$CONFIG$";
+
+/// Reserved key [`CURRENT_SCHEMA_VERSION`] is stamped under when a
+/// [`crate::schema::SchemaImpl`] is created, so a schema with zero tables
+/// still carries a durable record of the format it was initialized under,
+/// rather than that only ever existing implicitly on each [`TableDefine`]
+/// row's own `version` field.
+const FORMAT_VERSION_KEY: &str = "$SCHEMA_FORMAT_VERSION$";
+
+fn config_key(name: &str) -> String {
+    format!("{name}{CONFIG_KEY_SUFFIX}")
+}
 
 pub(super) struct MetaStorage<'a, T: StorageEngine<'a>> {
     pub db: Option<<<T as StorageEngine<'a>>::ENVIRONMENT as Environment<'a>>::DATABASE>,
+    /// Codec [`Self::put`]/[`Self::get`] encode/decode [`TableDefine`] rows
+    /// with. Fixed for the lifetime of the schema's metadata table — see
+    /// [`RecordCodec`].
+    pub codec: RecordCodec,
+    /// Upgraders [`Self::decode`] runs a [`RecordCodec::Json`] row through
+    /// when its stored `version` trails [`CURRENT_SCHEMA_VERSION`].
+    pub migrations: Arc<MigrationRegistry>,
 }
 
 impl <'a, T: StorageEngine<'a>> MetaStorage<'a, T> {
@@ -22,11 +62,66 @@ impl <'a, T: StorageEngine<'a>> MetaStorage<'a, T> {
         }
     }
 
+    fn encode(&self, define: &TableDefine) -> Result<BufferEntry> {
+        match self.codec {
+            RecordCodec::Json => {
+                let json: String = define.try_into()?;
+                json.to_entry()
+            }
+            RecordCodec::Archive => Ok(define.archive().to_vec().into()),
+        }
+    }
+
+    /// Decodes a stored row, upgrading a [`RecordCodec::Json`] row whose
+    /// `version` trails [`CURRENT_SCHEMA_VERSION`] through
+    /// [`Self::migrations`] before the final parse, and rejecting one stored
+    /// under a newer version than this build understands with
+    /// [`ErrorKind::IncompatibleSchema`]. A [`RecordCodec::Archive`] row
+    /// carries its own [`TableDefine::access_archived`] version check and
+    /// isn't migrated here.
+    fn decode(&self, data: &BufferEntry) -> Result<TableDefine> {
+        match self.codec {
+            RecordCodec::Json => {
+                let json = String::from_entry(data)?;
+                let mut value: serde_json::Value = serde_json::from_str(&json)?;
+
+                let version = value.get("version").and_then(|v| v.as_u64()).unwrap_or(0) as u16;
+                if version > CURRENT_SCHEMA_VERSION {
+                    return Err(ErrorKind::IncompatibleSchema.into());
+                } else if version < CURRENT_SCHEMA_VERSION {
+                    value = self.migrations.upgrade(value, version, CURRENT_SCHEMA_VERSION)?;
+                }
+
+                Ok(serde_json::from_value(value)?)
+            }
+            RecordCodec::Archive => {
+                let archived = TableDefine::access_archived(data.slice())?;
+                Ok(archived.deserialize(&mut rkyv::Infallible).expect("rkyv::Infallible deserialization cannot fail"))
+            }
+        }
+    }
+
+    /// Rejects an `IndexDefine`/`OrderedField` naming a field whose
+    /// [`bufdb_api::model::FieldDefine::indexed`] is `false` — such a field
+    /// was never marked for the index it's now part of, so the storage layer
+    /// has no index database to write its entries into.
+    fn validate(&self, define: &TableDefine) -> Result<()> {
+        for index in &define.indexes {
+            for ordered in &index.fields {
+                let indexed = define.fields.iter().any(|f| f.name == ordered.field_name && f.indexed);
+                if !indexed {
+                    return Err(ErrorKind::Configuration.into());
+                }
+            }
+        }
+        Ok(())
+    }
+
     pub fn put(&self, define: &TableDefine) -> Result<()> {
         if let Some(ref db) = self.db {
-            let json: String = define.try_into()?;
+            self.validate(define)?;
             let key = define.name.to_entry()?;
-            let data = json.to_entry()?;
+            let data = self.encode(define)?;
             db.put(&key, &data)
         } else {
             Err(ErrorKind::AlreadyClosed.into())
@@ -37,8 +132,7 @@ impl <'a, T: StorageEngine<'a>> MetaStorage<'a, T> {
         if let Some(ref db) = self.db {
             let key = name.to_entry()?;
             if let Some(data) = db.get(&key)? {
-                let json = String::from_entry(&data)?;
-                let define = TableDefine::try_from(json.as_str())?;
+                let define = self.decode(&data)?;
                 Ok(Some(define))
             } else {
                 Ok(None)
@@ -48,10 +142,123 @@ impl <'a, T: StorageEngine<'a>> MetaStorage<'a, T> {
         }
     }
 
+    /// Persists `config` as the effective [`TableConfig`] for table `name`,
+    /// under a reserved sibling key (see [`config_key`]) so reopening the
+    /// environment restores the table's configured mode instead of
+    /// defaulting it.
+    pub fn put_config(&self, name: &str, config: &TableConfig) -> Result<()> {
+        if let Some(ref db) = self.db {
+            let key = config_key(name).to_entry()?;
+            let json = serde_json::to_string(config)?;
+            let data = json.to_entry()?;
+            db.put(&key, &data)
+        } else {
+            Err(ErrorKind::AlreadyClosed.into())
+        }
+    }
+
+    pub fn get_config(&self, name: &str) -> Result<Option<TableConfig>> {
+        if let Some(ref db) = self.db {
+            let key = config_key(name).to_entry()?;
+            if let Some(data) = db.get(&key)? {
+                let json = String::from_entry(&data)?;
+                let config: TableConfig = serde_json::from_str(&json)?;
+                Ok(Some(config))
+            } else {
+                Ok(None)
+            }
+        } else {
+            Err(ErrorKind::AlreadyClosed.into())
+        }
+    }
+
     pub fn delete(&self, name: &str) -> Result<()> {
         if let Some(ref db) = self.db {
             let key = name.to_entry()?;
-            db.delete(&key)
+            db.delete(&key)?;
+            let config_key = config_key(name).to_entry()?;
+            db.delete(&config_key)
+        } else {
+            Err(ErrorKind::AlreadyClosed.into())
+        }
+    }
+
+    /// Lists every [`TableDefine`] currently stored, i.e. the schema's
+    /// applied state as of the last successful [`crate::schema::SchemaImpl::migrate`].
+    pub fn list(&self) -> Result<Vec<TableDefine>> {
+        if let Some(ref db) = self.db {
+            let mut cursor = db.open_cursor()?;
+
+            let mut key = BufferEntry::default();
+            let mut data = BufferEntry::default();
+            let mut has_entry = cursor.search_range(&mut key, Some(&mut data))?;
+
+            let mut defines = Vec::new();
+            while has_entry {
+                if key.slice() != VERSION_KEY.as_bytes() && key.slice() != FORMAT_VERSION_KEY.as_bytes() && !key.slice().ends_with(CONFIG_KEY_SUFFIX.as_bytes()) {
+                    defines.push(self.decode(&data)?);
+                }
+
+                has_entry = cursor.next(Some(&mut key), Some(&mut data))?;
+            }
+
+            Ok(defines)
+        } else {
+            Err(ErrorKind::AlreadyClosed.into())
+        }
+    }
+
+    /// The schema version applied by the last successful `migrate` call,
+    /// or `0` if `migrate` has never run.
+    pub fn version(&self) -> Result<u64> {
+        if let Some(ref db) = self.db {
+            let key = VERSION_KEY.to_entry()?;
+            match db.get(&key)? {
+                Some(data) => u64::from_entry(&data),
+                None => Ok(0),
+            }
+        } else {
+            Err(ErrorKind::AlreadyClosed.into())
+        }
+    }
+
+    pub fn set_version(&self, version: u64) -> Result<()> {
+        if let Some(ref db) = self.db {
+            let key = VERSION_KEY.to_entry()?;
+            let data = version.to_entry()?;
+            db.put(&key, &data)
+        } else {
+            Err(ErrorKind::AlreadyClosed.into())
+        }
+    }
+
+    /// Stamps [`CURRENT_SCHEMA_VERSION`] under [`FORMAT_VERSION_KEY`] if it
+    /// isn't already recorded. Called once from [`crate::schema::SchemaImpl::new`];
+    /// idempotent so reopening an existing schema never overwrites the
+    /// version it was actually created under.
+    pub fn ensure_format_version(&self) -> Result<()> {
+        if let Some(ref db) = self.db {
+            let key = FORMAT_VERSION_KEY.to_entry()?;
+            if db.get(&key)?.is_none() {
+                let data = CURRENT_SCHEMA_VERSION.to_entry()?;
+                db.put(&key, &data)?;
+            }
+            Ok(())
+        } else {
+            Err(ErrorKind::AlreadyClosed.into())
+        }
+    }
+
+    /// The [`CURRENT_SCHEMA_VERSION`] this metadata table was stamped with
+    /// by [`Self::ensure_format_version`], or `None` for a schema that
+    /// predates that stamp.
+    pub fn format_version(&self) -> Result<Option<u16>> {
+        if let Some(ref db) = self.db {
+            let key = FORMAT_VERSION_KEY.to_entry()?;
+            match db.get(&key)? {
+                Some(data) => Ok(Some(u16::from_entry(&data)?)),
+                None => Ok(None),
+            }
         } else {
             Err(ErrorKind::AlreadyClosed.into())
         }
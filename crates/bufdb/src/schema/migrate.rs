@@ -0,0 +1,212 @@
+use std::collections::HashSet;
+
+use bufdb_api::config::Compression;
+use bufdb_api::datatype::DataType;
+use bufdb_api::error::ErrorKind;
+use bufdb_api::error::Result;
+use bufdb_api::model::FieldDefine;
+use bufdb_api::model::IndexDefine;
+use bufdb_api::model::IndexType;
+use bufdb_api::model::OrderMode;
+use bufdb_api::model::OrderedField;
+use bufdb_api::model::TableDefine;
+use bufdb_storage::Database;
+use bufdb_storage::KeyCreator;
+use bufdb_storage::PrimaryCursor;
+use bufdb_storage::SDatabaseConfig;
+use bufdb_storage::StorageEngine;
+use bufdb_storage::entry::BufferEntry;
+use bufdb_storage::entry::Entry;
+use bufdb_storage::io::BufferOutput;
+use bufdb_storage::io::Output;
+
+use crate::table::StringKeyComparator;
+use crate::table::TableImpl;
+
+/// One step of the ordered plan [`diff`] computes to bring a schema's
+/// current tables to a target state.
+#[derive(Debug, Clone)]
+pub(super) enum MigrationOp {
+    CreateTable(TableDefine),
+    DropTable(String),
+    AddField { table: String, field: FieldDefine },
+    DropField { table: String, field: String },
+    AddIndex { table: String, index: IndexDefine },
+    DropIndex { table: String, index: String },
+}
+
+/// Computes the ordered operations that bring `current` to `target`: drops
+/// first, walking `current` in order (for a surviving table its removed
+/// indexes, then its removed fields; a table absent from `target` is
+/// dropped whole), then creates, walking `target` in order (a new table,
+/// then its indexes; for a surviving table its added fields, then its
+/// added indexes). Applying the same `current`/`target` pair twice yields an empty
+/// plan the second time, which is what makes `migrate` idempotent.
+pub(super) fn diff(current: &[TableDefine], target: &[TableDefine]) -> Vec<MigrationOp> {
+    let mut ops = Vec::new();
+
+    for table in current {
+        match target.iter().find(|t| t.name == table.name) {
+            Some(next) => {
+                for index in &table.indexes {
+                    if !next.indexes.iter().any(|i| i.name == index.name) {
+                        ops.push(MigrationOp::DropIndex { table: table.name.clone(), index: index.name.clone() });
+                    }
+                }
+
+                for field in &table.fields {
+                    if !next.fields.iter().any(|f| f.name == field.name) {
+                        ops.push(MigrationOp::DropField { table: table.name.clone(), field: field.name.clone() });
+                    }
+                }
+            },
+            None => ops.push(MigrationOp::DropTable(table.name.clone())),
+        }
+    }
+
+    for table in target {
+        match current.iter().find(|t| t.name == table.name) {
+            None => {
+                ops.push(MigrationOp::CreateTable(TableDefine { indexes: Vec::new(), ..table.clone() }));
+
+                for index in &table.indexes {
+                    ops.push(MigrationOp::AddIndex { table: table.name.clone(), index: index.clone() });
+                }
+            },
+            Some(prev) => {
+                for field in &table.fields {
+                    if !prev.fields.iter().any(|f| f.name == field.name) {
+                        ops.push(MigrationOp::AddField { table: table.name.clone(), field: field.clone() });
+                    }
+                }
+
+                for index in &table.indexes {
+                    if !prev.indexes.iter().any(|i| i.name == index.name) {
+                        ops.push(MigrationOp::AddIndex { table: table.name.clone(), index: index.clone() });
+                    }
+                }
+            },
+        }
+    }
+
+    ops
+}
+
+/// Checks that a field being added to an existing table carries a default
+/// whose JSON shape is consistent with its [`DataType`], so the field has
+/// something sane to read back on rows that predate it.
+pub(super) fn validate_default(field: &FieldDefine) -> Result<()> {
+    let default = field.default.as_ref().ok_or(ErrorKind::Migration)?;
+
+    let consistent = match field.datatype {
+        DataType::STRING | DataType::DATETIME | DataType::BLOB => default.is_string(),
+        DataType::DOUBLE => default.is_number(),
+        DataType::INT | DataType::LONG => default.is_i64() || default.is_u64(),
+        DataType::BOOL => default.is_boolean(),
+    };
+
+    if consistent {
+        Ok(())
+    } else {
+        Err(ErrorKind::Migration.into())
+    }
+}
+
+/// Creates a secondary index database for `index` over `table`, surfacing a
+/// [`ErrorKind::Migration`] conflict instead of silently losing rows if a
+/// `UNIQUE` index's derived key collides across existing data. The database
+/// backend backfills the index from every existing primary row as part of
+/// creating it (see each backend's `IndexListener::init`), so there's
+/// nothing left to rebuild here once creation succeeds.
+pub(super) fn rebuild_index<'a, T: StorageEngine<'a>>(env: &T::ENVIRONMENT, table: &TableImpl<'a, T>, index: &IndexDefine) -> Result<()> {
+    if index.index_type == IndexType::FULLTEXT {
+        return table.create_fulltext_index(&index.name, index.fields.clone());
+    }
+
+    if index.index_type == IndexType::UNIQUE {
+        check_unique(table, index)?;
+    }
+
+    let config = SDatabaseConfig {
+        readonly: false,
+        temporary: false,
+        unique: index.index_type == IndexType::UNIQUE,
+        comparator: StringKeyComparator {},
+        creator: FieldIndexKeyCreator { fields: index.fields.clone() },
+        merge_operator: None,
+        compression: Compression::None,
+        column_encodings: Vec::new(),
+    };
+
+    table.create_index(env, &index.name, config)
+}
+
+/// Scans every live row of `table`'s primary database, failing with
+/// [`ErrorKind::Migration`] the first time two rows derive the same key for
+/// `index`'s fields.
+fn check_unique<'a, T: StorageEngine<'a>>(table: &TableImpl<'a, T>, index: &IndexDefine) -> Result<()> {
+    let mut cursor = table.database().open_cursor()?;
+
+    let mut key = BufferEntry::default();
+    let mut data = BufferEntry::default();
+    let mut has_entry = cursor.search_range(&mut key, Some(&mut data))?;
+
+    let mut seen = HashSet::new();
+    while has_entry {
+        if let Some(skey) = derive_index_key(&index.fields, &data)? {
+            if !seen.insert(skey.slice().to_vec()) {
+                return Err(ErrorKind::Migration.into());
+            }
+        }
+
+        has_entry = cursor.next(Some(&mut key), Some(&mut data))?;
+    }
+
+    Ok(())
+}
+
+/// Derives a secondary key from a primary row for the given ordered index
+/// fields. Rows whose value isn't a JSON object (i.e. every row until
+/// typed, field-aware records land) have nothing to extract and are left
+/// out of the index, same as [`bufdb_storage::KeyCreator::create_key`]
+/// returning `None` for any other reason.
+fn derive_index_key(fields: &[OrderedField], data: &BufferEntry) -> Result<Option<BufferEntry>> {
+    let Ok(serde_json::Value::Object(value)) = serde_json::from_slice::<serde_json::Value>(data.slice()) else {
+        return Ok(None);
+    };
+
+    let mut combined = Vec::new();
+    for field in fields {
+        let text = match value.get(&field.field_name) {
+            Some(serde_json::Value::String(s)) => s.clone(),
+            Some(other) => other.to_string(),
+            None => String::new(),
+        };
+
+        let mut part = BufferOutput::new();
+        part.write_string(Some(&text))?;
+        let part: BufferEntry = part.into();
+
+        if field.order_mode == OrderMode::DESC {
+            combined.extend(part.slice().iter().map(|b| !b));
+        } else {
+            combined.extend_from_slice(part.slice());
+        }
+    }
+
+    Ok(Some(BufferEntry::from(combined)))
+}
+
+/// Derives a [`derive_index_key`] secondary key from each primary row,
+/// honoring every [`OrderedField`]'s [`OrderMode`] by bit-complementing its
+/// encoded bytes, which reverses byte-order comparison for that field.
+#[derive(Debug, Clone)]
+struct FieldIndexKeyCreator {
+    fields: Vec<OrderedField>,
+}
+
+impl KeyCreator for FieldIndexKeyCreator {
+    fn create_key(&self, _key: &BufferEntry, data: &BufferEntry) -> Result<Option<BufferEntry>> {
+        derive_index_key(&self.fields, data)
+    }
+}
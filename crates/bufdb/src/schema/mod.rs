@@ -5,32 +5,52 @@ use std::sync::Arc;
 use std::sync::atomic::AtomicI64;
 
 use bufdb_api::config::CacheConfig;
+use bufdb_api::config::Compression;
 use bufdb_api::config::SchemaConfig;
 use bufdb_api::config::TableConfig;
+use bufdb_api::datatype::DataType;
 use bufdb_api::error::ErrorKind;
 use bufdb_api::error::Result;
+use bufdb_api::model::CURRENT_SCHEMA_VERSION;
+use bufdb_api::model::FieldDefine;
+use bufdb_api::model::IndexType;
 use bufdb_api::model::TableDefine;
 use bufdb_storage::DatabaseConfig;
 use bufdb_storage::Environment;
 use bufdb_storage::EnvironmentConfig;
 use bufdb_storage::KeyComparator;
+use bufdb_storage::MergeOperator;
 use bufdb_storage::StorageEngine;
 use bufdb_storage::cache::CachePool;
 use bufdb_storage::cache::Poolable;
 use bufdb_storage::cache::now;
 use bufdb_storage::get_timestamp;
+use bufdb_storage::ordered_key::OrderedKeyComparator;
 use bufdb_storage::set_timestamp;
 
 use crate::daemon::Maintainable;
 use crate::engine::DBEngine;
 use crate::instance::InstImpl;
 use crate::table::KVTable;
+use crate::table::OrderedTable;
 use crate::table::StringKeyComparator;
 use crate::table::TableImpl;
 
 use self::meta::MetaStorage;
+use self::migrate::MigrationOp;
 
+mod ddl;
 mod meta;
+mod migrate;
+
+/// Whether `requested` matches `stored` column-for-column by name,
+/// [`DataType`], nullability and indexed role, in order. Ignores `comment`,
+/// `default` and `stored`, which don't change how a caller reads a field
+/// back.
+fn fields_match(stored: &[FieldDefine], requested: &[FieldDefine]) -> bool {
+    stored.len() == requested.len()
+        && stored.iter().zip(requested).all(|(a, b)| a.name == b.name && a.datatype == b.datatype && a.nullable == b.nullable && a.indexed == b.indexed)
+}
 
 pub(crate) struct SchemaImpl<'a, T: StorageEngine<'a>> {
     name: String,
@@ -61,15 +81,24 @@ impl <'a, T: StorageEngine<'a>> SchemaImpl<'a, T> {
             readonly: config.readonly(),
             temporary: config.temporary(),
             comparator: StringKeyComparator {},
+            merge_operator: None,
+            compression: Compression::None,
+            ttl: None,
+            column_encodings: Vec::new(),
         };
         let meta = env.create_database("SYS_META", db_config)?;
-
-        Ok(Self { 
-            name, 
-            config, 
-            env, 
-            meta: MetaStorage { db: Some(meta) },
-            tables: CachePool::new(), 
+        let record_codec = config.record_codec();
+        let migrations = config.migrations();
+
+        let meta = MetaStorage { db: Some(meta), codec: record_codec, migrations };
+        meta.ensure_format_version()?;
+
+        Ok(Self {
+            name,
+            config,
+            env,
+            meta,
+            tables: CachePool::new(),
             last_access: AtomicI64::new(now()),
         })
     }
@@ -82,9 +111,15 @@ impl <'a, T: StorageEngine<'a>> SchemaImpl<'a, T> {
         &self.config
     }
 
-    fn open<C: KeyComparator>(&self, name: &str, config: TableConfig, comparator: C) -> Result<Arc<TableImpl<'a, T>>> {
+    /// Writes a consistent copy of this schema's data to `target`, which
+    /// must not already exist. See [`Environment::checkpoint`].
+    pub fn checkpoint(&self, target: &Path) -> Result<()> {
+        self.env.checkpoint(target)
+    }
+
+    fn open<C: KeyComparator>(&self, name: &str, config: TableConfig, comparator: C, merge_operator: Option<MergeOperator>) -> Result<Arc<TableImpl<'a, T>>> {
         self.touch();
-        
+
         if let Some(table) = self.tables.get(name) {
             if table.config().readonly() != config.readonly() || table.config().temporary() != config.temporary() {
                 Err(ErrorKind::Configuration.into())
@@ -92,7 +127,7 @@ impl <'a, T: StorageEngine<'a>> SchemaImpl<'a, T> {
                 Ok(table)
             }
         } else {
-            let table = TableImpl::new(&self.env, name, config, comparator)?;
+            let table = TableImpl::new(&self.env, name, config, comparator, merge_operator)?;
             let table = Arc::new(table);
             if self.config.max_cache().is_some() {
                 self.tables.put(table.clone());
@@ -101,25 +136,92 @@ impl <'a, T: StorageEngine<'a>> SchemaImpl<'a, T> {
         }
     }
 
-    pub fn create_kv_table(&self, name: &str, config: TableConfig) -> Result<Arc<TableImpl<'a, T>>> {
+    /// Creates `name` as a plain string-keyed table, persisting `fields` as
+    /// its column definitions (name, [`DataType`], nullability, indexed
+    /// role) alongside the [`TableDefine`] so a later [`Self::open_kv_table`]
+    /// can confirm the table is still being opened under the layout it was
+    /// created with.
+    pub fn create_kv_table(&self, name: &str, config: TableConfig, fields: Vec<FieldDefine>) -> Result<Arc<TableImpl<'a, T>>> {
         if config.temporary() && config.readonly() {
             Err(ErrorKind::Configuration.into())
         } else if self.meta.exists(name)? {
             Err(ErrorKind::CreateDuplicate.into())
         } else {
-            let table = self.open(name, config, StringKeyComparator {})?;
+            let table = self.open(name, config.clone(), StringKeyComparator {}, None)?;
+            let define = fields.into_iter().fold(TableDefine::new(name), TableDefine::add_field);
+            self.meta.put(&define)?;
+            if !config.temporary() {
+                self.meta.put_config(name, &config)?;
+            }
+            Ok(table)
+        }
+    }
+
+    /// Opens `name`, restoring the [`TableConfig`] persisted by
+    /// [`Self::create_kv_table`] rather than defaulting to `config` when one
+    /// was saved. `fields` must match (by name, [`DataType`], nullability
+    /// and indexed role, in order) the columns `name` was created with, or
+    /// this fails with [`ErrorKind::Configuration`] instead of silently
+    /// opening a table under a layout the caller didn't ask for.
+    pub fn open_kv_table(&self, name: &str, config: TableConfig, fields: &[FieldDefine]) -> Result<Arc<TableImpl<'a, T>>> {
+        if config.temporary() && config.readonly() {
+            Err(ErrorKind::Configuration.into())
+        } else {
+            let define = self.meta.get(name)?.ok_or(ErrorKind::NotFound)?;
+            if !fields_match(&define.fields, fields) {
+                return Err(ErrorKind::Configuration.into());
+            }
+
+            let config = self.meta.get_config(name)?.unwrap_or(config);
+            let table = self.open(name, config, StringKeyComparator {}, None)?;
+            Ok(table)
+        }
+    }
+
+    /// Adds `field` to `name`'s persisted [`TableDefine`], the same
+    /// additive, backfill-required path [`Self::migrate`] takes for a
+    /// `MigrationOp::AddField`, then bumps the schema's applied version the
+    /// same way a successful `migrate` does. `field.default` is required so
+    /// existing rows have something to read back for the new column; see
+    /// [`migrate::validate_default`].
+    pub fn alter_kv_table(&self, name: &str, field: FieldDefine) -> Result<u64> {
+        self.apply_migration(MigrationOp::AddField { table: name.to_string(), field })?;
+
+        let version = self.meta.version()? + 1;
+        self.meta.set_version(version)?;
+        Ok(version)
+    }
+
+    /// Like [`Self::create_kv_table`], but keyed by [`bufdb_storage::ordered_key::OrderedKeyComparator`]
+    /// instead of plain lexical UTF-8, so range scans over numeric or
+    /// datetime keys follow their logical order. The [`DataType`] keys must
+    /// conform to is enforced above this layer, by [`OrderedTable`].
+    pub fn create_ordered_table(&self, name: &str, config: TableConfig) -> Result<Arc<TableImpl<'a, T>>> {
+        if config.temporary() && config.readonly() {
+            Err(ErrorKind::Configuration.into())
+        } else if self.meta.exists(name)? {
+            Err(ErrorKind::CreateDuplicate.into())
+        } else {
+            let table = self.open(name, config.clone(), OrderedKeyComparator {}, None)?;
             self.meta.put(&TableDefine::new(name))?;
+            if !config.temporary() {
+                self.meta.put_config(name, &config)?;
+            }
             Ok(table)
         }
     }
 
-    pub fn open_kv_table(&self, name: &str, config: TableConfig) -> Result<Arc<TableImpl<'a, T>>> {
+    /// Opens `name` as created by [`Self::create_ordered_table`], restoring
+    /// its persisted [`TableConfig`] the same way [`Self::open_kv_table`]
+    /// does.
+    pub fn open_ordered_table(&self, name: &str, config: TableConfig) -> Result<Arc<TableImpl<'a, T>>> {
         if config.temporary() && config.readonly() {
             Err(ErrorKind::Configuration.into())
         } else if !self.meta.exists(name)? {
             Err(ErrorKind::NotFound.into())
         } else {
-            let table = self.open(name, config, StringKeyComparator {})?;
+            let config = self.meta.get_config(name)?.unwrap_or(config);
+            let table = self.open(name, config, OrderedKeyComparator {}, None)?;
             Ok(table)
         }
     }
@@ -130,20 +232,206 @@ impl <'a, T: StorageEngine<'a>> SchemaImpl<'a, T> {
     //     self.tables.get(name)
     // }
 
+    /// Closes `name`, evicting it from the in-process [`CachePool`]. For a
+    /// temporary table this also drops its backing database so scratch data
+    /// never outlives the handle that created it, rather than leaking until
+    /// the whole environment is torn down.
     pub fn close(&self, name: &str, config: &TableConfig) -> Option<Arc<TableImpl<'a, T>>> {
         self.touch();
 
         if config.temporary() {
-            let _ = self.meta.delete(name);            
+            let _ = self.env.drop_database(name);
+            let _ = self.meta.delete(name);
         }
         self.tables.remove(name)
     }
 
+    /// Permanently deletes `name`: evicts any cached handle, drops its
+    /// backing database through [`Environment::drop_database`], then
+    /// removes its [`TableDefine`]/[`TableConfig`] rows from [`MetaStorage`].
+    /// Fails with [`ErrorKind::NotFound`] if `name` was never created.
+    pub fn drop_kv_table(&self, name: &str) -> Result<()> {
+        self.touch();
+
+        if !self.meta.exists(name)? {
+            return Err(ErrorKind::NotFound.into());
+        }
+
+        self.tables.remove(name);
+        self.env.drop_database(name)?;
+        self.meta.delete(name)
+    }
+
+    /// Deletes every row of `name` through [`Environment::truncate_database`]
+    /// without dropping the table itself — its [`TableDefine`]/[`TableConfig`]
+    /// are left exactly as they were. Fails with [`ErrorKind::NotFound`] if
+    /// `name` was never created.
+    pub fn truncate_kv_table(&self, name: &str) -> Result<()> {
+        self.touch();
+
+        if !self.meta.exists(name)? {
+            return Err(ErrorKind::NotFound.into());
+        }
+
+        self.tables.remove(name);
+        self.env.truncate_database(name)
+    }
+
+    /// Renames `old` to `new` through [`Environment::rename_database`],
+    /// re-keying its [`TableDefine`] and, if one was persisted, its
+    /// [`TableConfig`] under `new`. Fails with [`ErrorKind::NotFound`] if
+    /// `old` doesn't exist, or [`ErrorKind::CreateDuplicate`] if `new`
+    /// already does.
+    pub fn rename_kv_table(&self, old: &str, new: &str) -> Result<()> {
+        self.touch();
+
+        if !self.meta.exists(old)? {
+            return Err(ErrorKind::NotFound.into());
+        } else if self.meta.exists(new)? {
+            return Err(ErrorKind::CreateDuplicate.into());
+        }
+
+        self.tables.remove(old);
+        self.env.rename_database(old, new)?;
+
+        let mut define = self.meta.get(old)?.ok_or(ErrorKind::NotFound)?;
+        define.name = new.to_string();
+        let config = self.meta.get_config(old)?;
+
+        self.meta.delete(old)?;
+        self.meta.put(&define)?;
+        if let Some(config) = config {
+            self.meta.put_config(new, &config)?;
+        }
+
+        Ok(())
+    }
+
     // pub fn exists(&self, name: &str) -> Result<bool> {
     //     let key = name.to_entry()?;
     //     let data = self.meta.get(&key)?;
     //     Ok(data.is_some())
     // }
+
+    /// Brings this schema's tables to match `target`: diffs it against the
+    /// currently applied definitions and applies the resulting plan (every
+    /// table, field and index drop first, then every create and add; see
+    /// `migrate::diff` for the order within each half).
+    /// Returns the schema version now applied. An unchanged `target` diffs
+    /// to an empty plan and is a no-op; a `target` that fails partway
+    /// through leaves every op applied so far persisted, so simply calling
+    /// `migrate` again with the same `target` picks up where it left off.
+    pub fn migrate(&self, target: &[TableDefine]) -> Result<u64> {
+        let current = self.meta.list()?;
+        let ops = migrate::diff(&current, target);
+
+        if ops.is_empty() {
+            return self.meta.version();
+        }
+
+        for op in ops {
+            self.apply_migration(op)?;
+        }
+
+        let version = self.meta.version()? + 1;
+        self.meta.set_version(version)?;
+        Ok(version)
+    }
+
+    /// Creates a single table from `define`, without treating the rest of
+    /// the schema as subject to change the way [`Self::migrate`]'s `target`
+    /// does. Errors with [`ErrorKind::CreateDuplicate`] if `define.name`
+    /// already exists.
+    fn create_table(&self, define: TableDefine) -> Result<TableDefine> {
+        if self.meta.exists(&define.name)? {
+            return Err(ErrorKind::CreateDuplicate.into());
+        }
+
+        self.apply_migration(MigrationOp::CreateTable(TableDefine { indexes: Vec::new(), ..define.clone() }))?;
+        for index in &define.indexes {
+            self.apply_migration(MigrationOp::AddIndex { table: define.name.clone(), index: index.clone() })?;
+        }
+
+        Ok(define)
+    }
+
+    fn apply_migration(&self, op: MigrationOp) -> Result<()> {
+        match op {
+            MigrationOp::CreateTable(define) => {
+                let config = TableConfig::new(self.config.readonly(), self.config.temporary());
+                self.open(&define.name, config, StringKeyComparator {}, None)?;
+                self.meta.put(&define)
+            },
+            MigrationOp::DropTable(name) => {
+                self.tables.remove(&name);
+                self.env.drop_database(&name)?;
+                self.meta.delete(&name)
+            },
+            MigrationOp::AddField { table, field } => {
+                migrate::validate_default(&field)?;
+
+                let mut define = self.meta.get(&table)?.ok_or(ErrorKind::NotFound)?;
+                define.fields.push(field);
+                self.meta.put(&define)
+            },
+            MigrationOp::DropField { table, field } => {
+                let mut define = self.meta.get(&table)?.ok_or(ErrorKind::NotFound)?;
+                if define.key_fields.iter().any(|k| k == &field) {
+                    return Err(ErrorKind::Migration.into());
+                }
+
+                define.fields.retain(|f| f.name != field);
+                self.meta.put(&define)
+            },
+            MigrationOp::AddIndex { table, index } => {
+                let mut define = self.meta.get(&table)?.ok_or(ErrorKind::NotFound)?;
+                let table_impl = self.open_table_for_index(&table)?;
+                migrate::rebuild_index(&self.env, &table_impl, &index)?;
+
+                define.indexes.push(index);
+                self.meta.put(&define)
+            },
+            MigrationOp::DropIndex { table, index } => {
+                let mut define = self.meta.get(&table)?.ok_or(ErrorKind::NotFound)?;
+                let table_impl = self.open_table_for_index(&table)?;
+
+                let index_type = define.indexes.iter().find(|i| i.name == index).map(|i| i.index_type).unwrap_or_default();
+                if index_type == IndexType::FULLTEXT {
+                    table_impl.drop_fulltext_index(&index)?;
+                } else {
+                    table_impl.drop_index(&self.env, &index)?;
+                }
+
+                define.indexes.retain(|i| i.name != index);
+                self.meta.put(&define)
+            },
+        }
+    }
+
+    /// Opens `table` (which `migrate` has already confirmed exists) for
+    /// index maintenance, without going through [`Self::open`]'s `KVTable`
+    /// caller-facing config checks.
+    fn open_table_for_index(&self, table: &str) -> Result<Arc<TableImpl<'a, T>>> {
+        let config = TableConfig::new(self.config.readonly(), self.config.temporary());
+        self.open(table, config, StringKeyComparator {}, None)
+    }
+
+    /// Re-persists every stored [`TableDefine`] whose `version` still trails
+    /// [`CURRENT_SCHEMA_VERSION`], so a schema opened under an older build
+    /// converges to the current on-disk shape over time instead of paying
+    /// the upgrade cost on every [`MetaStorage::list`]/`get`. A row that
+    /// can't be upgraded (no registered migration step for its version) is
+    /// left in place; `maintain` runs best-effort and doesn't surface the
+    /// error.
+    fn upgrade_stale_metadata(&self) {
+        let Ok(defines) = self.meta.list() else { return; };
+
+        for define in defines {
+            if define.version < CURRENT_SCHEMA_VERSION {
+                let _ = self.meta.put(&define);
+            }
+        }
+    }
 }
 
 unsafe impl <'a, T: StorageEngine<'a>> Send for SchemaImpl<'a, T> {}
@@ -156,6 +444,8 @@ impl <'a, T: StorageEngine<'a>> Maintainable for SchemaImpl<'a, T> {
         for table in tables {
             table.maintain();
         }
+
+        self.upgrade_stale_metadata();
     }
 }
 
@@ -199,15 +489,81 @@ impl Schema {
         self.schema.config()
     }
 
-    pub fn create_kv_table(&self, name: &str, config: TableConfig) -> Result<KVTable> {
-        let table = self.schema.create_kv_table(name, config)?;
+    /// Creates `name`, persisting `fields` as its column definitions. See
+    /// [`SchemaImpl::create_kv_table`].
+    pub fn create_kv_table(&self, name: &str, config: TableConfig, fields: Vec<FieldDefine>) -> Result<KVTable> {
+        let table = self.schema.create_kv_table(name, config, fields)?;
         Ok(KVTable::new(self.schema.clone(), table))
     }
 
-    pub fn open_kv_table(&self, name: &str, config: TableConfig) -> Result<KVTable> {
-        let table = self.schema.open_kv_table(name, config)?;
+    /// Opens `name`, requiring `fields` to match the columns it was created
+    /// with. See [`SchemaImpl::open_kv_table`].
+    pub fn open_kv_table(&self, name: &str, config: TableConfig, fields: &[FieldDefine]) -> Result<KVTable> {
+        let table = self.schema.open_kv_table(name, config, fields)?;
         Ok(KVTable::new(self.schema.clone(), table))
     }
+
+    /// Adds `field` to `name`, requiring [`FieldDefine::default`] so
+    /// existing rows have something to backfill, and returns the schema
+    /// version now applied. See [`SchemaImpl::alter_kv_table`].
+    pub fn alter_kv_table(&self, name: &str, field: FieldDefine) -> Result<u64> {
+        self.schema.alter_kv_table(name, field)
+    }
+
+    /// Permanently deletes `name`. See [`SchemaImpl::drop_kv_table`].
+    pub fn drop_kv_table(&self, name: &str) -> Result<()> {
+        self.schema.drop_kv_table(name)
+    }
+
+    /// Deletes every row of `name` without dropping the table itself. See
+    /// [`SchemaImpl::truncate_kv_table`].
+    pub fn truncate_kv_table(&self, name: &str) -> Result<()> {
+        self.schema.truncate_kv_table(name)
+    }
+
+    /// Renames `old` to `new`. See [`SchemaImpl::rename_kv_table`].
+    pub fn rename_kv_table(&self, old: &str, new: &str) -> Result<()> {
+        self.schema.rename_kv_table(old, new)
+    }
+
+    /// Like [`Self::create_kv_table`], but every key must be a `key_type`
+    /// [`bufdb_api::datatype::Value`] rather than a string, and ranges scan
+    /// in that value's logical order. See [`OrderedTable`].
+    pub fn create_ordered_table(&self, name: &str, config: TableConfig, key_type: DataType) -> Result<OrderedTable> {
+        let table = self.schema.create_ordered_table(name, config)?;
+        Ok(OrderedTable::new(self.schema.clone(), table, key_type))
+    }
+
+    /// Opens `name` as created by [`Self::create_ordered_table`]. `key_type`
+    /// must match the [`DataType`] the table was created with; it isn't
+    /// persisted, so a mismatch silently misreads every key rather than
+    /// erroring here.
+    pub fn open_ordered_table(&self, name: &str, config: TableConfig, key_type: DataType) -> Result<OrderedTable> {
+        let table = self.schema.open_ordered_table(name, config)?;
+        Ok(OrderedTable::new(self.schema.clone(), table, key_type))
+    }
+
+    /// Writes a consistent, point-in-time copy of this schema's data to
+    /// `target`, which must not already exist. Safe to call while tables in
+    /// this schema keep taking reads and writes.
+    pub fn backup_to(&self, target: &Path) -> Result<()> {
+        self.schema.checkpoint(target)
+    }
+
+    /// Brings this schema's tables to match `target`, returning the schema
+    /// version now applied. See [`SchemaImpl::migrate`].
+    pub fn migrate(&self, target: &[TableDefine]) -> Result<u64> {
+        self.schema.migrate(target)
+    }
+
+    /// Parses `sql` as a single `CREATE TABLE` statement (see [`ddl`] for the
+    /// grammar) and creates the table it describes, returning the resulting
+    /// [`TableDefine`]. Fails with [`ErrorKind::Syntax`] if `sql` doesn't
+    /// parse, or [`ErrorKind::CreateDuplicate`] if the table already exists.
+    pub fn create_table_from_sql(&self, sql: &str) -> Result<TableDefine> {
+        let define = ddl::parse_table_define(sql)?;
+        self.schema.create_table(define)
+    }
 }
 
 unsafe impl Send for Schema {}
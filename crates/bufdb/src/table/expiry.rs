@@ -0,0 +1,41 @@
+use std::io::Write;
+
+use bufdb_lib::error::Result;
+use bufdb_storage::entry::BufferEntry;
+use bufdb_storage::entry::Entry;
+use bufdb_storage::entry::SliceEntry;
+use bufdb_storage::io::BufferOutput;
+use bufdb_storage::io::Input;
+use bufdb_storage::io::Output;
+
+/// Width, in bytes, of the absolute expiry timestamp prefixed onto every
+/// value stored through [`crate::table::KVTable`].
+const PREFIX_LEN: usize = 8;
+
+/// Sentinel expiry meaning the entry never expires.
+pub(crate) const NO_EXPIRY: i64 = i64::MAX;
+
+/// Prefixes `value` with `expires_at`, an absolute millisecond timestamp (or
+/// [`NO_EXPIRY`]), ready to be written as a `KVTable` value.
+pub(crate) fn encode(expires_at: i64, value: &BufferEntry) -> Result<BufferEntry> {
+    let mut output = BufferOutput::new();
+    output.write_i64(expires_at)?;
+    output.write_all(value.slice())?;
+    Ok(output.into())
+}
+
+/// Reads the absolute expiry timestamp prefixed onto a stored value.
+pub(crate) fn expires_at(data: &BufferEntry) -> Result<i64> {
+    Ok(data.as_input().read_i64()?)
+}
+
+/// Whether `expires_at` (as read by [`expires_at`]) is in the past relative
+/// to `now`.
+pub(crate) fn is_expired(expires_at: i64, now: i64) -> bool {
+    expires_at != NO_EXPIRY && expires_at <= now
+}
+
+/// The stored value with its expiry prefix stripped off.
+pub(crate) fn strip(data: &BufferEntry) -> SliceEntry {
+    SliceEntry::new(&data.slice()[PREFIX_LEN..])
+}
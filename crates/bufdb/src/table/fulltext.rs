@@ -0,0 +1,239 @@
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::io::Write;
+use std::sync::Mutex;
+
+use bufdb_api::model::OrderedField;
+use bufdb_lib::error::ErrorKind;
+use bufdb_lib::error::Result;
+use bufdb_storage::entry::BufferEntry;
+use bufdb_storage::entry::Entry;
+use bufdb_storage::io::BufferOutput;
+use bufdb_storage::io::Input;
+use bufdb_storage::io::Output;
+use fst::Automaton;
+use fst::IntoStreamer;
+use fst::Streamer;
+use fst::automaton::Levenshtein;
+use fst::automaton::Str;
+
+/// Key a [`FulltextIndex`]'s serialized transducer is persisted under in
+/// its table's primary database. User keys are not checked against it, so
+/// a row stored under exactly this key shares its entry.
+pub(crate) fn reserved_key(name: &str) -> String {
+    format!("$FULLTEXT:{name}$")
+}
+
+/// Splits `text` into the lowercase alphanumeric runs a [`FulltextIndex`] is
+/// built over. Punctuation and whitespace are token boundaries; empty runs
+/// (consecutive boundaries) are dropped.
+pub(crate) fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Inverted, FST-backed secondary index over the tokenized terms of one or
+/// more text fields (`IndexDefine.fields` selects and orders them;
+/// `OrderMode` is meaningless for a term and is ignored).
+///
+/// The transducer (`fst::Map`) is immutable once built, so a row written
+/// after the last [`Self::rebuild`] can't be folded into it in place.
+/// Instead its terms land in `pending`, an overlay every lookup consults
+/// alongside the transducer, until the next `rebuild` walks the existing
+/// `fst`, merges in `pending`, and replaces both with a freshly built pair
+/// — so readers always see one consistent transducer, never a half-updated
+/// one.
+pub(crate) struct FulltextIndex {
+    fields: Vec<OrderedField>,
+    fst: fst::Map<Vec<u8>>,
+    postings: Vec<Vec<BufferEntry>>,
+    pending: Mutex<BTreeMap<String, Vec<BufferEntry>>>,
+}
+
+impl FulltextIndex {
+    /// Builds a transducer from `terms`, already grouped and sorted by term.
+    fn build(fields: Vec<OrderedField>, terms: BTreeMap<String, Vec<BufferEntry>>) -> Result<Self> {
+        let mut builder = fst::MapBuilder::memory();
+        let mut postings = Vec::with_capacity(terms.len());
+
+        for (term, keys) in terms {
+            builder.insert(&term, postings.len() as u64).map_err(|_| ErrorKind::Archive)?;
+            postings.push(keys);
+        }
+
+        let bytes = builder.into_inner().map_err(|_| ErrorKind::Archive)?;
+        let fst = fst::Map::new(bytes).map_err(|_| ErrorKind::Archive)?;
+
+        Ok(Self { fields, fst, postings, pending: Mutex::new(BTreeMap::new()) })
+    }
+
+    /// Builds a transducer from scratch by tokenizing every row a primary
+    /// cursor scan of a table yields, via `rows` (primary key, row value).
+    pub(crate) fn scan(fields: Vec<OrderedField>, rows: impl Iterator<Item = Result<(BufferEntry, BufferEntry)>>) -> Result<Self> {
+        let mut terms: BTreeMap<String, Vec<BufferEntry>> = BTreeMap::new();
+
+        for row in rows {
+            let (key, data) = row?;
+            for term in Self::terms_of(&fields, &data) {
+                terms.entry(term).or_default().push(key.clone());
+            }
+        }
+
+        Self::build(fields, terms)
+    }
+
+    /// Tokenizes the indexed fields' values out of a row stored as a JSON
+    /// object, the same row shape [`crate::schema::migrate::derive_index_key`]
+    /// reads for a B-tree secondary index. A row that isn't a JSON object
+    /// (or is missing a field) contributes no terms for it, same as that
+    /// function returning `None`.
+    fn terms_of(fields: &[OrderedField], data: &BufferEntry) -> Vec<String> {
+        let Ok(value) = serde_json::from_slice::<serde_json::Value>(data.slice()) else {
+            return Vec::new();
+        };
+
+        let mut terms = Vec::new();
+        for field in fields {
+            let text = match value.get(&field.field_name) {
+                Some(serde_json::Value::String(s)) => s.clone(),
+                Some(other) => other.to_string(),
+                None => continue,
+            };
+
+            terms.extend(tokenize(&text));
+        }
+
+        terms
+    }
+
+    /// Records `key`'s terms in the pending overlay; folded into the
+    /// transducer by the next [`Self::rebuild`].
+    pub(crate) fn note_write(&self, key: &BufferEntry, data: &BufferEntry) {
+        let terms = Self::terms_of(&self.fields, data);
+        if terms.is_empty() {
+            return;
+        }
+
+        let mut pending = self.pending.lock().unwrap();
+        for term in terms {
+            pending.entry(term).or_default().push(key.clone());
+        }
+    }
+
+    /// Whether [`Self::note_write`] has recorded anything since the last
+    /// rebuild.
+    pub(crate) fn is_dirty(&self) -> bool {
+        !self.pending.lock().unwrap().is_empty()
+    }
+
+    /// Folds the pending overlay into a freshly built transducer that
+    /// covers every term the old one had plus everything written since.
+    pub(crate) fn rebuild(&mut self) -> Result<()> {
+        let pending = std::mem::take(&mut *self.pending.lock().unwrap());
+        if pending.is_empty() {
+            return Ok(());
+        }
+
+        let mut merged: BTreeMap<String, Vec<BufferEntry>> = BTreeMap::new();
+        let mut stream = self.fst.stream();
+        while let Some((term, offset)) = stream.next() {
+            let term = String::from_utf8_lossy(term).into_owned();
+            merged.insert(term, self.postings[offset as usize].clone());
+        }
+        drop(stream);
+
+        for (term, mut keys) in pending {
+            merged.entry(term).or_default().append(&mut keys);
+        }
+
+        let rebuilt = Self::build(self.fields.clone(), merged)?;
+        self.fst = rebuilt.fst;
+        self.postings = rebuilt.postings;
+
+        Ok(())
+    }
+
+    /// Every primary key indexed under a term starting with `prefix`.
+    pub(crate) fn prefix(&self, prefix: &str) -> Vec<BufferEntry> {
+        self.collect(Str::new(prefix).starts_with())
+    }
+
+    /// Every primary key indexed under a term within `distance` edits of
+    /// `term`.
+    pub(crate) fn fuzzy(&self, term: &str, distance: u32) -> Result<Vec<BufferEntry>> {
+        let automaton = Levenshtein::new(term, distance).map_err(|_| ErrorKind::Archive)?;
+        Ok(self.collect(automaton))
+    }
+
+    fn collect<A: Automaton>(&self, automaton: A) -> Vec<BufferEntry> {
+        let mut keys = Vec::new();
+
+        // Terms written since the last rebuild are only in `pending`.
+        for (term, pending_keys) in self.pending.lock().unwrap().iter() {
+            let mut state = automaton.start();
+            for &b in term.as_bytes() {
+                state = automaton.accept(&state, b);
+            }
+            if automaton.is_match(&state) {
+                keys.extend(pending_keys.iter().cloned());
+            }
+        }
+
+        let mut stream = self.fst.search(automaton).into_stream();
+        while let Some((_, offset)) = stream.next() {
+            keys.extend(self.postings[offset as usize].iter().cloned());
+        }
+
+        keys
+    }
+
+    /// Serializes the transducer's raw FST bytes followed by its posting
+    /// lists, ready to be stored as a single value under a reserved key in
+    /// the table's primary database (see [`super::TableImpl::create_fulltext_index`]).
+    pub(crate) fn to_entry(&self) -> Result<BufferEntry> {
+        let mut output = BufferOutput::new();
+
+        let fst_bytes = self.fst.as_fst().as_bytes();
+        output.write_u32(fst_bytes.len() as u32)?;
+        output.write_all(fst_bytes)?;
+
+        output.write_u32(self.postings.len() as u32)?;
+        for keys in &self.postings {
+            output.write_u32(keys.len() as u32)?;
+            for key in keys {
+                output.write_u32(key.slice().len() as u32)?;
+                output.write_all(key.slice())?;
+            }
+        }
+
+        Ok(output.into())
+    }
+
+    /// Reads back a [`Self::to_entry`] blob.
+    pub(crate) fn from_entry(fields: Vec<OrderedField>, data: &BufferEntry) -> Result<Self> {
+        let mut input = data.as_input();
+
+        let fst_len = input.read_u32()? as usize;
+        let mut fst_bytes = vec![0u8; fst_len];
+        input.read_exact(&mut fst_bytes)?;
+        let fst = fst::Map::new(fst_bytes).map_err(|_| ErrorKind::Archive)?;
+
+        let postings_len = input.read_u32()? as usize;
+        let mut postings = Vec::with_capacity(postings_len);
+        for _ in 0..postings_len {
+            let keys_len = input.read_u32()? as usize;
+            let mut keys = Vec::with_capacity(keys_len);
+            for _ in 0..keys_len {
+                let key_len = input.read_u32()? as usize;
+                let mut key = vec![0u8; key_len];
+                input.read_exact(&mut key)?;
+                keys.push(BufferEntry::from(key));
+            }
+            postings.push(keys);
+        }
+
+        Ok(Self { fields, fst, postings, pending: Mutex::new(BTreeMap::new()) })
+    }
+}
@@ -1,58 +1,278 @@
 pub(crate) mod comparator;
+mod expiry;
+mod fulltext;
 
+use std::cmp::Ordering;
 use std::fmt::Display;
+use std::ops::Bound;
+use std::ops::RangeBounds;
 use std::sync::Arc;
+use std::sync::Mutex;
 use std::sync::atomic::AtomicI64;
+use std::time::Duration;
 
+use bufdb_api::datatype::DataType;
+use bufdb_api::datatype::Value;
+use bufdb_api::model::OrderedField;
 use bufdb_lib::config::TableConfig;
+use bufdb_lib::error::ErrorKind;
 use bufdb_lib::error::Result;
 use bufdb_storage::Database;
 use bufdb_storage::DatabaseConfig;
 use bufdb_storage::Environment;
 use bufdb_storage::KeyComparator;
+use bufdb_storage::KeyCreator;
+use bufdb_storage::MergeOperator;
+use bufdb_storage::PrimaryCursor;
+use bufdb_storage::SDatabaseConfig;
 use bufdb_storage::StorageEngine;
+use bufdb_storage::Transaction;
 use bufdb_storage::cache::Poolable;
 use bufdb_storage::cache::now;
+use bufdb_storage::entry::BufferEntry;
+use bufdb_storage::entry::Entry;
+use bufdb_storage::entry::compare;
 use bufdb_storage::get_timestamp;
 use bufdb_storage::io::Inputable;
 use bufdb_storage::io::Outputable;
+use bufdb_storage::ordered_key;
 use bufdb_storage::set_timestamp;
 
 use crate::daemon::Maintainable;
 use crate::engine::DBEngine;
 use crate::schema::SchemaImpl;
 
+use self::fulltext::FulltextIndex;
+
+/// Cap on how many entries a single [`Maintainable::maintain`] tick sweeps
+/// for expired keys, so a large table is cleaned incrementally instead of
+/// blocking the daemon thread.
+const SWEEP_LIMIT: usize = 256;
+
+/// The concrete primary database type of engine `T`.
+type Db<'a, T> = <<T as StorageEngine<'a>>::ENVIRONMENT as Environment<'a>>::DATABASE;
+/// The concrete primary cursor type of engine `T`.
+type DbCursor<'a, T> = <<T as StorageEngine<'a>>::ENVIRONMENT as Environment<'a>>::CURSOR;
+/// The transaction type a primary database of engine `T` hands out.
+type DbTransaction<'a, T> = <Db<'a, T> as Database<'a, DbCursor<'a, T>>>::TRANSACTION;
+/// The concrete secondary database type of engine `T`.
+type SDb<'a, T> = <<T as StorageEngine<'a>>::ENVIRONMENT as Environment<'a>>::SDATABASE;
+
 pub(crate) struct TableImpl<'a, T: StorageEngine<'a>> {
     name: String,
     config: TableConfig,
     db: <<T as StorageEngine<'a>>::ENVIRONMENT as Environment<'a>>::DATABASE,
-    sdbs: Vec<<<T as StorageEngine<'a>>::ENVIRONMENT as Environment<'a>>::SDATABASE>,
+    /// Secondary indexes created over this table, keyed by index name, so
+    /// [`Self::drop_index`] can find the one to tear down and every handle
+    /// stays open (and its listener registered) for as long as the table is.
+    sdbs: Mutex<Vec<(String, SDb<'a, T>)>>,
+    /// Fulltext (FST-backed) indexes created over this table, keyed by
+    /// index name. Unlike [`Self::sdbs`] these aren't backed by the storage
+    /// engine's secondary-database machinery — an FST doesn't fit the
+    /// one-row-per-key model that assumes — so this struct rebuilds and
+    /// persists them itself (see [`Self::create_fulltext_index`]).
+    fulltext: Mutex<Vec<(String, FulltextIndex)>>,
     last_access: AtomicI64,
+    /// Key to resume the next [`Self::sweep_expired`] tick from, so a table
+    /// larger than [`SWEEP_LIMIT`] is swept across several ticks instead of
+    /// one. `None` means the next tick starts over from the beginning.
+    sweep_cursor: Mutex<Option<BufferEntry>>,
 }
 
 impl <'a, T: StorageEngine<'a>> TableImpl<'a, T> {
-    pub fn new<S: Into<String>, C: KeyComparator>(env: &T::ENVIRONMENT, name: S, config: TableConfig, comparator: C) -> Result<Self> {
+    pub fn new<S: Into<String>, C: KeyComparator>(env: &T::ENVIRONMENT, name: S, config: TableConfig, comparator: C, merge_operator: Option<MergeOperator>) -> Result<Self> {
         let name: String = name.into();
 
         let db_config = DatabaseConfig {
             readonly: config.readonly(),
             temporary: config.temporary(),
-            comparator
+            comparator,
+            merge_operator,
+            compression: config.compression(),
+            ttl: None,
+            column_encodings: config.column_encodings().to_vec(),
         };
         let db = env.create_database(&name, db_config)?;
-        
-        Ok(Self { 
-            name, 
-            config, 
+
+        Ok(Self {
+            name,
+            config,
             db,
-            sdbs: Vec::new(),
-            last_access: AtomicI64::new(now()) 
+            sdbs: Mutex::new(Vec::new()),
+            fulltext: Mutex::new(Vec::new()),
+            last_access: AtomicI64::new(now()),
+            sweep_cursor: Mutex::new(None),
         })
     }
 
     pub fn config(&self) -> &TableConfig {
         &self.config
     }
+
+    /// This table's primary database, so schema-level migration code can
+    /// create/drop secondary indexes over it and scan its existing rows.
+    pub(crate) fn database(&self) -> &Db<'a, T> {
+        &self.db
+    }
+
+    /// Creates a secondary index database over this table's primary data,
+    /// backfilling it from every existing row, and keeps it open for as
+    /// long as this table is.
+    pub(crate) fn create_index<C: KeyComparator, G: KeyCreator + 'a>(&self, env: &T::ENVIRONMENT, name: &str, config: SDatabaseConfig<C, G>) -> Result<()> {
+        let sdb = env.create_secondary_database(&self.db, name, config)?;
+        self.sdbs.lock().unwrap().push((name.into(), sdb));
+        Ok(())
+    }
+
+    /// Drops a secondary index previously created with [`Self::create_index`].
+    pub(crate) fn drop_index(&self, env: &T::ENVIRONMENT, name: &str) -> Result<()> {
+        env.drop_secondary_database(name)?;
+        self.sdbs.lock().unwrap().retain(|(n, _)| n != name);
+        Ok(())
+    }
+
+    /// Creates an FST-backed fulltext index over `fields`, restoring a
+    /// transducer persisted by an earlier [`Self::rebuild_fulltext_indexes`]
+    /// if one is stored, or else building one from scratch by scanning
+    /// every row currently in the primary database.
+    pub(crate) fn create_fulltext_index(&self, name: &str, fields: Vec<OrderedField>) -> Result<()> {
+        let reserved = fulltext::reserved_key(name).to_entry()?;
+
+        let index = match self.db.get(&reserved)? {
+            Some(data) => FulltextIndex::from_entry(fields, &data)?,
+            None => {
+                let rows = self.scan_rows()?;
+                let index = FulltextIndex::scan(fields, rows.into_iter().map(Ok))?;
+                self.db.put(&reserved, &index.to_entry()?)?;
+                index
+            }
+        };
+
+        self.fulltext.lock().unwrap().push((name.into(), index));
+        Ok(())
+    }
+
+    /// Drops a fulltext index previously created with
+    /// [`Self::create_fulltext_index`].
+    pub(crate) fn drop_fulltext_index(&self, name: &str) -> Result<()> {
+        self.fulltext.lock().unwrap().retain(|(n, _)| n != name);
+        self.db.delete(&fulltext::reserved_key(name).to_entry()?)
+    }
+
+    /// Every primary key indexed by `index` under a term starting with
+    /// `prefix`.
+    pub(crate) fn fulltext_prefix(&self, index: &str, prefix: &str) -> Result<Vec<BufferEntry>> {
+        self.with_fulltext(index, |idx| Ok(idx.prefix(prefix)))
+    }
+
+    /// Every primary key indexed by `index` under a term within `distance`
+    /// edits of `term`.
+    pub(crate) fn fulltext_fuzzy(&self, index: &str, term: &str, distance: u32) -> Result<Vec<BufferEntry>> {
+        self.with_fulltext(index, |idx| idx.fuzzy(term, distance))
+    }
+
+    fn with_fulltext<R>(&self, name: &str, f: impl FnOnce(&FulltextIndex) -> Result<R>) -> Result<R> {
+        let fulltext = self.fulltext.lock().unwrap();
+        let (_, index) = fulltext.iter().find(|(n, _)| n == name).ok_or(ErrorKind::NotFound)?;
+        f(index)
+    }
+
+    /// Records `key`/`data` in every fulltext index's pending overlay, so
+    /// the next [`Self::rebuild_fulltext_indexes`] picks it up.
+    pub(crate) fn note_fulltext_write(&self, key: &BufferEntry, data: &BufferEntry) {
+        for (_, index) in self.fulltext.lock().unwrap().iter() {
+            index.note_write(key, data);
+        }
+    }
+
+    /// Folds each dirty fulltext index's pending overlay into a freshly
+    /// built transducer and re-persists it. Called from [`Maintainable::maintain`]
+    /// alongside [`Self::sweep_expired`].
+    fn rebuild_fulltext_indexes(&self) -> Result<()> {
+        for (name, index) in self.fulltext.lock().unwrap().iter_mut() {
+            if index.is_dirty() {
+                index.rebuild()?;
+                self.db.put(&fulltext::reserved_key(name).to_entry()?, &index.to_entry()?)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Every row currently in the primary database, with its TTL prefix
+    /// stripped (see [`expiry::strip`]) so it reads the same as what
+    /// [`Self::note_fulltext_write`] sees on a live write, for
+    /// [`Self::create_fulltext_index`]'s initial scan.
+    fn scan_rows(&self) -> Result<Vec<(BufferEntry, BufferEntry)>> {
+        let mut cursor = self.db.open_cursor()?;
+
+        let mut key = BufferEntry::default();
+        let mut data = BufferEntry::default();
+        let mut has_entry = cursor.search_range(&mut key, Some(&mut data))?;
+
+        let mut rows = Vec::new();
+        while has_entry {
+            rows.push((key.clone(), BufferEntry::from(expiry::strip(&data).slice().to_vec())));
+            has_entry = cursor.next(Some(&mut key), Some(&mut data))?;
+        }
+
+        Ok(rows)
+    }
+
+    /// Folds `operand` into `key`'s current value through this table's
+    /// merge operator. Returns an error if the table was opened without one.
+    pub fn merge<V: Outputable>(&self, key: &str, operand: V) -> Result<()> {
+        let k = key.to_entry()?;
+        let v = operand.to_entry()?;
+
+        self.db.merge(&k, &v)
+    }
+
+    /// Scans up to [`SWEEP_LIMIT`] entries starting where the previous tick
+    /// left off, deleting any whose expiry prefix (see [`expiry`]) is in the
+    /// past. Resumes from the last scanned key on the next call, so a table
+    /// with more than `SWEEP_LIMIT` live entries is swept incrementally.
+    fn sweep_expired(&self) -> Result<()> {
+        let resume = self.sweep_cursor.lock().unwrap().take();
+
+        let mut cursor = self.db.open_cursor()?;
+
+        let mut key = resume.clone().unwrap_or_default();
+        let mut data = BufferEntry::default();
+        let mut has_entry = cursor.search_range(&mut key, Some(&mut data))?;
+
+        // `search_range` lands back on the key we already swept last tick;
+        // step past it so every tick makes forward progress.
+        if has_entry && resume.is_some() {
+            has_entry = cursor.next(Some(&mut key), Some(&mut data))?;
+        }
+
+        let now = now();
+        let mut expired = Vec::new();
+        let mut last_key = None;
+        let mut scanned = 0usize;
+
+        while has_entry && scanned < SWEEP_LIMIT {
+            if expiry::is_expired(expiry::expires_at(&data)?, now) {
+                expired.push(key.clone());
+            }
+
+            last_key = Some(key.clone());
+            scanned += 1;
+
+            has_entry = cursor.next(Some(&mut key), Some(&mut data))?;
+        }
+
+        drop(cursor);
+
+        for key in &expired {
+            self.db.delete(key)?;
+        }
+
+        *self.sweep_cursor.lock().unwrap() = if has_entry { last_key } else { None };
+
+        Ok(())
+    }
 }
 
 unsafe impl <'a, T: StorageEngine<'a>> Send for TableImpl<'a, T> {}
@@ -60,6 +280,8 @@ unsafe impl <'a, T: StorageEngine<'a>> Sync for TableImpl<'a, T> {}
 
 impl <'a, T: StorageEngine<'a>> Maintainable for TableImpl<'a, T> {
     fn maintain(&self) {
+        let _ = self.sweep_expired();
+        let _ = self.rebuild_fulltext_indexes();
     }
 }
 
@@ -99,17 +321,42 @@ impl KVTable {
     }
 
     pub fn put<V: Outputable>(&self, key: &str, value: V) -> Result<()> {
+        let expires_at = self.table.config().default_ttl()
+            .map(|ttl| now() + ttl.as_millis() as i64)
+            .unwrap_or(expiry::NO_EXPIRY);
+
+        self.put_raw(key, value, expires_at)
+    }
+
+    /// Stores `value` under `key` with an explicit per-entry TTL, overriding
+    /// the table's [`TableConfig::default_ttl`]. Once `ttl` elapses the entry
+    /// reads back as absent and is reclaimed by the next background sweep
+    /// (see [`crate::daemon::Maintainable::maintain`]).
+    pub fn put_with_ttl<V: Outputable>(&self, key: &str, value: V, ttl: Duration) -> Result<()> {
+        let expires_at = now() + ttl.as_millis() as i64;
+
+        self.put_raw(key, value, expires_at)
+    }
+
+    fn put_raw<V: Outputable>(&self, key: &str, value: V, expires_at: i64) -> Result<()> {
         let k = key.to_entry()?;
-        let v = value.to_entry()?;
+        let raw = value.to_entry()?;
+        let v = expiry::encode(expires_at, &raw)?;
 
-        self.table.db.put(&k, &v)
+        self.table.db.put(&k, &v)?;
+        self.table.note_fulltext_write(&k, &raw);
+        Ok(())
     }
 
     pub fn get<V: Inputable>(&self, key: &str) -> Result<Option<V>> {
         let k = key.to_entry()?;
         if let Some(data) = self.table.db.get(&k)? {
-            let v = V::from_entry(&data)?;
-            Ok(Some(v))
+            if expiry::is_expired(expiry::expires_at(&data)?, now()) {
+                Ok(None)
+            } else {
+                let v = V::from_entry(&expiry::strip(&data))?;
+                Ok(Some(v))
+            }
         } else {
             Ok(None)
         }
@@ -132,8 +379,179 @@ impl KVTable {
 
     pub fn exists(&self, key: &str) -> Result<bool> {
         let k = key.to_entry()?;
-        let data = self.table.db.get(&k)?;
-        Ok(data.is_some())
+        match self.table.db.get(&k)? {
+            Some(data) => Ok(!expiry::is_expired(expiry::expires_at(&data)?, now())),
+            None => Ok(false),
+        }
+    }
+
+    /// Folds `operand` into `key`'s current value through the table's merge
+    /// operator, e.g. to aggregate a counter or append to a set without a
+    /// racy `get` then `put`.
+    pub fn merge<V: Outputable>(&self, key: &str, operand: V) -> Result<()> {
+        self.table.merge(key, operand)
+    }
+
+    /// Runs `f` against a fresh transaction on this table's database,
+    /// committing its staged writes if `f` succeeds and rolling them back if
+    /// it returns an error.
+    ///
+    /// Fails with `ErrorKind::Configuration` if this table has a secondary
+    /// index (an `INDEXED`/`UNIQUE` column): a transaction's commit does not
+    /// yet fan its writes out to one, so opening one would silently desync
+    /// it from the primary data.
+    pub fn with_transaction<F, R>(&self, f: F) -> Result<R>
+    where
+        F: FnOnce(&DbTransaction<'static, DBEngine>) -> Result<R>,
+    {
+        if !self.table.sdbs.lock().unwrap().is_empty() {
+            return Err(ErrorKind::Configuration.into());
+        }
+
+        let tx = self.table.db.begin_transaction()?;
+
+        match f(&tx) {
+            Ok(value) => {
+                tx.commit()?;
+                Ok(value)
+            },
+            Err(err) => {
+                tx.rollback()?;
+                Err(err)
+            }
+        }
+    }
+
+    /// Iterates the live (non-expired) entries whose key falls in `range`,
+    /// in key order, decoding each key back to a `String` and its value
+    /// through `V`. `range`'s bounds are encoded the same way a plain `put`
+    /// key is, so they compare consistently with what's actually stored.
+    pub fn scan<V: Inputable>(&self, range: impl RangeBounds<&str>) -> Result<impl Iterator<Item = Result<(String, V)>>> {
+        let start = Self::encode_bound(range.start_bound())?;
+        let end = Self::encode_bound(range.end_bound())?;
+
+        let entries = self.collect_range(start, end, None)?;
+        Ok(entries.into_iter().map(Self::decode_entry))
+    }
+
+    /// Like [`Self::scan`], but bounded to keys starting with `prefix`
+    /// rather than an explicit range.
+    pub fn prefix_scan<V: Inputable>(&self, prefix: &str) -> Result<impl Iterator<Item = Result<(String, V)>>> {
+        let start = Bound::Included(prefix.to_entry()?);
+
+        let entries = self.collect_range(start, Bound::Unbounded, Some(prefix))?;
+        Ok(entries.into_iter().map(Self::decode_entry))
+    }
+
+    /// Every primary key `index` (an `IndexType::FULLTEXT` index) has
+    /// tokenized a term starting with `prefix` under, in no particular
+    /// order.
+    pub fn fulltext_prefix(&self, index: &str, prefix: &str) -> Result<Vec<String>> {
+        self.table.fulltext_prefix(index, prefix)?
+            .into_iter()
+            .map(|key| String::from_entry(&key))
+            .collect()
+    }
+
+    /// Every primary key `index` has tokenized a term within `distance`
+    /// edits of `term` under.
+    pub fn fulltext_fuzzy(&self, index: &str, term: &str, distance: u32) -> Result<Vec<String>> {
+        self.table.fulltext_fuzzy(index, term, distance)?
+            .into_iter()
+            .map(|key| String::from_entry(&key))
+            .collect()
+    }
+
+    /// Whether this table's database holds no entries at all, without
+    /// scanning and decoding every one the way [`Self::count_range`] would.
+    /// Expired entries not yet removed by the background sweep still count.
+    pub fn is_empty(&self) -> Result<bool> {
+        self.table.database().is_empty()
+    }
+
+    /// Counts the live entries in `range`, without decoding their values.
+    pub fn count_range(&self, range: impl RangeBounds<&str>) -> Result<usize> {
+        let start = Self::encode_bound(range.start_bound())?;
+        let end = Self::encode_bound(range.end_bound())?;
+
+        Ok(self.collect_range(start, end, None)?.len())
+    }
+
+    /// Deletes every live entry in `range`, returning how many were removed.
+    pub fn delete_range(&self, range: impl RangeBounds<&str>) -> Result<usize> {
+        let start = Self::encode_bound(range.start_bound())?;
+        let end = Self::encode_bound(range.end_bound())?;
+
+        let entries = self.collect_range(start, end, None)?;
+        for (key, _) in &entries {
+            self.table.db.delete(key)?;
+        }
+
+        Ok(entries.len())
+    }
+
+    fn encode_bound(bound: Bound<&&str>) -> Result<Bound<BufferEntry>> {
+        Ok(match bound {
+            Bound::Included(s) => Bound::Included(s.to_entry()?),
+            Bound::Excluded(s) => Bound::Excluded(s.to_entry()?),
+            Bound::Unbounded => Bound::Unbounded,
+        })
+    }
+
+    fn decode_entry<V: Inputable>((key, data): (BufferEntry, BufferEntry)) -> Result<(String, V)> {
+        let key = String::from_entry(&key)?;
+        let value = V::from_entry(&expiry::strip(&data))?;
+
+        Ok((key, value))
+    }
+
+    /// Walks this table's primary cursor from `start` to `end`, optionally
+    /// stopping as soon as a key no longer starts with `prefix`, and
+    /// collects every live entry found along the way. Expired entries are
+    /// skipped rather than reclaimed; that stays [`TableImpl::sweep_expired`]'s
+    /// job.
+    fn collect_range(&self, start: Bound<BufferEntry>, end: Bound<BufferEntry>, prefix: Option<&str>) -> Result<Vec<(BufferEntry, BufferEntry)>> {
+        let mut cursor = self.table.db.open_cursor()?;
+
+        let mut key = match &start {
+            Bound::Included(k) | Bound::Excluded(k) => k.clone(),
+            Bound::Unbounded => BufferEntry::default(),
+        };
+        let mut data = BufferEntry::default();
+        let mut has_entry = cursor.search_range(&mut key, Some(&mut data))?;
+
+        // `search_range` lands on `start` itself when present; step past it
+        // for an exclusive start bound.
+        if has_entry {
+            if let Bound::Excluded(start_key) = &start {
+                if compare(&key, start_key) == Ordering::Equal {
+                    has_entry = cursor.next(Some(&mut key), Some(&mut data))?;
+                }
+            }
+        }
+
+        let now = now();
+        let mut entries = Vec::new();
+
+        while has_entry {
+            let past_end = match &end {
+                Bound::Included(end_key) => compare(&key, end_key) == Ordering::Greater,
+                Bound::Excluded(end_key) => compare(&key, end_key) != Ordering::Less,
+                Bound::Unbounded => false,
+            };
+            let off_prefix = prefix.map_or(false, |prefix| !key.slice().starts_with(prefix.as_bytes()));
+            if past_end || off_prefix {
+                break;
+            }
+
+            if !expiry::is_expired(expiry::expires_at(&data)?, now) {
+                entries.push((key.clone(), data.clone()));
+            }
+
+            has_entry = cursor.next(Some(&mut key), Some(&mut data))?;
+        }
+
+        Ok(entries)
     }
 }
 
@@ -147,4 +565,157 @@ impl Display for KVTable {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.table.name())
     }
+}
+
+/// A table keyed by an order-preserving encoding (see [`ordered_key`]) of a
+/// single [`DataType`] instead of [`KVTable`]'s plain UTF-8 string keys, so
+/// range scans over numeric or datetime keys follow the keys' logical order
+/// rather than their lexical byte order. Every key handed to this table must
+/// be a [`Value`] of its declared `key_type`, or [`ErrorKind::DataType`] is
+/// returned. Unlike `KVTable`, entries written here don't carry a TTL.
+pub struct OrderedTable {
+    schema: Arc<SchemaImpl<'static, DBEngine>>,
+    table: Arc<TableImpl<'static, DBEngine>>,
+    key_type: DataType,
+}
+
+impl OrderedTable {
+    pub(crate) fn new(schema: Arc<SchemaImpl<'static, DBEngine>>, table: Arc<TableImpl<'static, DBEngine>>, key_type: DataType) -> Self {
+        Self {
+            schema,
+            table,
+            key_type,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        self.table.name()
+    }
+
+    pub fn config(&self) -> &TableConfig {
+        self.table.config()
+    }
+
+    /// The [`DataType`] every key of this table must encode.
+    pub fn key_type(&self) -> DataType {
+        self.key_type
+    }
+
+    fn check_key(&self, key: &Value) -> Result<()> {
+        match key.datatype() {
+            Some(found) if found == self.key_type => Ok(()),
+            _ => Err(ErrorKind::DataType.into()),
+        }
+    }
+
+    pub fn put<V: Outputable>(&self, key: &Value, value: V) -> Result<()> {
+        self.check_key(key)?;
+
+        let k = ordered_key::encode(key);
+        let v = value.to_entry()?;
+        self.table.db.put(&k, &v)
+    }
+
+    pub fn get<V: Inputable>(&self, key: &Value) -> Result<Option<V>> {
+        self.check_key(key)?;
+
+        let k = ordered_key::encode(key);
+        match self.table.db.get(&k)? {
+            Some(data) => Ok(Some(V::from_entry(&data)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn exists(&self, key: &Value) -> Result<bool> {
+        self.check_key(key)?;
+
+        let k = ordered_key::encode(key);
+        Ok(self.table.db.get(&k)?.is_some())
+    }
+
+    pub fn delete(&self, key: &Value) -> Result<()> {
+        self.check_key(key)?;
+
+        let k = ordered_key::encode(key);
+        self.table.db.delete(&k)
+    }
+
+    fn encode_bound(&self, bound: Bound<&Value>) -> Result<Bound<BufferEntry>> {
+        match bound {
+            Bound::Included(v) => {
+                self.check_key(v)?;
+                Ok(Bound::Included(ordered_key::encode(v)))
+            },
+            Bound::Excluded(v) => {
+                self.check_key(v)?;
+                Ok(Bound::Excluded(ordered_key::encode(v)))
+            },
+            Bound::Unbounded => Ok(Bound::Unbounded),
+        }
+    }
+
+    /// Iterates the entries whose key falls in `range`, in key order,
+    /// decoding each key back to a [`Value`] of this table's `key_type` and
+    /// its value through `V`.
+    pub fn scan<V: Inputable>(&self, range: impl RangeBounds<Value>) -> Result<impl Iterator<Item = Result<(Value, V)>>> {
+        let start = self.encode_bound(range.start_bound())?;
+        let end = self.encode_bound(range.end_bound())?;
+
+        let entries = self.collect_range(start, end)?;
+        let key_type = self.key_type;
+        Ok(entries.into_iter().map(move |(key, data)| {
+            let key = ordered_key::decode(key_type, &key)?;
+            let value = V::from_entry(&data)?;
+            Ok((key, value))
+        }))
+    }
+
+    fn collect_range(&self, start: Bound<BufferEntry>, end: Bound<BufferEntry>) -> Result<Vec<(BufferEntry, BufferEntry)>> {
+        let mut cursor = self.table.db.open_cursor()?;
+
+        let mut key = match &start {
+            Bound::Included(k) | Bound::Excluded(k) => k.clone(),
+            Bound::Unbounded => BufferEntry::default(),
+        };
+        let mut data = BufferEntry::default();
+        let mut has_entry = cursor.search_range(&mut key, Some(&mut data))?;
+
+        if has_entry {
+            if let Bound::Excluded(start_key) = &start {
+                if compare(&key, start_key) == Ordering::Equal {
+                    has_entry = cursor.next(Some(&mut key), Some(&mut data))?;
+                }
+            }
+        }
+
+        let mut entries = Vec::new();
+
+        while has_entry {
+            let past_end = match &end {
+                Bound::Included(end_key) => compare(&key, end_key) == Ordering::Greater,
+                Bound::Excluded(end_key) => compare(&key, end_key) != Ordering::Less,
+                Bound::Unbounded => false,
+            };
+            if past_end {
+                break;
+            }
+
+            entries.push((key.clone(), data.clone()));
+            has_entry = cursor.next(Some(&mut key), Some(&mut data))?;
+        }
+
+        Ok(entries)
+    }
+}
+
+impl Drop for OrderedTable {
+    fn drop(&mut self) {
+        self.schema.close(self.table.name(), self.config());
+    }
+}
+
+impl Display for OrderedTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.table.name())
+    }
 }
\ No newline at end of file